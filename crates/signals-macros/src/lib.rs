@@ -0,0 +1,172 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Error, Fields, Type, parse_macro_input};
+
+/// Derives a store holding a signal per field of a struct.
+///
+/// For `struct Settings { volume: f32, theme: Theme }` this generates a
+/// `Copy` struct `SettingsStore` with the same visibility and the fields
+/// `volume: Signal<f32>` and `theme: Signal<Theme>`, and the methods:
+///
+/// - `new(Settings) -> SettingsStore`, creating the signals in the current
+///   scope;
+/// - `snapshot(&self) -> Settings`, reading every field without tracking,
+///   which requires the field types to be `Clone`;
+/// - `set_all(&self, Settings)`, writing every field inside one batch.
+///
+/// A field marked `#[store(nested)]` must itself derive `Store`, and is held
+/// as its store instead of a signal, so its fields are reactive one by one
+/// too.
+///
+/// The generated code refers to `::samara_signals`, which re-exports this
+/// macro.
+#[proc_macro_derive(Store, attributes(store))]
+pub fn derive_store(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+struct Field {
+    ident: syn::Ident,
+    vis: syn::Visibility,
+    ty: Type,
+    nested: bool,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "Store cannot be derived for generic structs",
+        ));
+    }
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "Store can only be derived for structs",
+        ));
+    };
+    let Fields::Named(named) = &data.fields else {
+        return Err(Error::new_spanned(
+            &data.fields,
+            "Store can only be derived for structs with named fields",
+        ));
+    };
+
+    let fields = named
+        .named
+        .iter()
+        .map(|field| {
+            Ok(Field {
+                ident: field.ident.clone().unwrap(),
+                vis: field.vis.clone(),
+                ty: field.ty.clone(),
+                nested: is_nested(&field.attrs)?,
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let vis = &input.vis;
+    let ident = &input.ident;
+    let store = format_ident!("{}Store", ident);
+
+    let mut declarations = Vec::new();
+    let mut constructors = Vec::new();
+    let mut snapshots = Vec::new();
+    let mut writes = Vec::new();
+    for Field {
+        ident: name,
+        vis: field_vis,
+        ty,
+        nested,
+    } in &fields
+    {
+        if *nested {
+            let nested_store = nested_store_type(ty)?;
+            declarations.push(quote! { #field_vis #name: #nested_store });
+            constructors.push(quote! { #name: #nested_store::new(value.#name) });
+            snapshots.push(quote! { #name: self.#name.snapshot() });
+            writes.push(quote! { self.#name.set_all(value.#name); });
+        } else {
+            declarations.push(quote! { #field_vis #name: ::samara_signals::Signal<#ty> });
+            constructors.push(quote! { #name: ::samara_signals::signal(value.#name) });
+            snapshots.push(quote! { #name: self.#name.get_untracked() });
+            writes.push(quote! { self.#name.set(value.#name); });
+        }
+    }
+
+    let doc = format!("A signal per field of [`{ident}`], generated by `#[derive(Store)]`.");
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(Clone, Copy, Debug)]
+        #vis struct #store {
+            #(#declarations,)*
+        }
+
+        impl #store {
+            /// Creates the signals holding `value`'s fields in the current
+            /// scope.
+            #[track_caller]
+            pub fn new(value: #ident) -> Self {
+                Self {
+                    #(#constructors,)*
+                }
+            }
+
+            /// Reads every field without tracking.
+            pub fn snapshot(&self) -> #ident {
+                #ident {
+                    #(#snapshots,)*
+                }
+            }
+
+            /// Writes every field inside one batch, so effects reading several
+            /// of them run once.
+            pub fn set_all(&self, value: #ident) {
+                ::samara_signals::start_batch();
+                #(#writes)*
+                ::samara_signals::end_batch();
+            }
+        }
+    })
+}
+
+/// Whether the field is marked `#[store(nested)]`
+fn is_nested(attrs: &[syn::Attribute]) -> syn::Result<bool> {
+    let mut nested = false;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("store")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("nested") {
+                nested = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `nested`"))
+            }
+        })?;
+    }
+    Ok(nested)
+}
+
+/// The store generated for a nested field's type, `path::Type` becoming
+/// `path::TypeStore`
+fn nested_store_type(ty: &Type) -> syn::Result<syn::Path> {
+    let Type::Path(path) = ty else {
+        return Err(Error::new_spanned(
+            ty,
+            "#[store(nested)] fields must name a struct deriving Store",
+        ));
+    };
+    let mut store = path.path.clone();
+    let last = store.segments.last_mut().unwrap();
+    if !last.arguments.is_none() {
+        return Err(Error::new_spanned(
+            ty,
+            "#[store(nested)] fields cannot be generic",
+        ));
+    }
+    last.ident = format_ident!("{}Store", last.ident);
+    Ok(store)
+}
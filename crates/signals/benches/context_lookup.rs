@@ -0,0 +1,39 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use samara_signals::*;
+
+#[derive(Clone)]
+struct Theme(u32);
+
+/// An effect `depth` scopes below the provider of `Theme` that looks it up
+/// on every run
+fn hot_effect(depth: usize) -> Signal<u32> {
+    let tick = signal(0);
+    let mut leaf = scope(|| provide_context(Theme(1)));
+    for _ in 1..depth {
+        leaf = leaf.run_in(|| scope(|| {}));
+    }
+    leaf.run_in(|| {
+        effect(move || {
+            tick.get();
+            std::hint::black_box(use_context::<Theme>().map(|theme| theme.0));
+        });
+    });
+    tick
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    for depth in [1, 10, 100] {
+        c.bench_function(&format!("use_context depth {depth} effect run"), |b| {
+            let tick = hot_effect(depth);
+            let mut i = 0;
+            b.iter(|| {
+                i += 1;
+                tick.set(i);
+            });
+            cleanup();
+        });
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
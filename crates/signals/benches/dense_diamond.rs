@@ -0,0 +1,36 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use samara_signals::*;
+
+/// `layers` layers of `width` memos, each reading every memo of the layer
+/// above, with an effect on each memo of the last layer
+fn dense_diamond(width: usize, layers: usize) -> Signal<i32> {
+    let src = signal(1);
+    let mut above: Vec<Computed<i32>> = vec![memo(move || src.get())];
+    for _ in 0..layers {
+        above = (0..width)
+            .map(|i| {
+                let inputs = above.clone();
+                memo(move || inputs.iter().map(|input| input.get()).sum::<i32>() + i as i32)
+            })
+            .collect();
+    }
+    for node in above {
+        effect(move || {
+            node.get();
+        });
+    }
+    src
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    for (width, layers) in [(10, 10), (100, 2), (30, 5)] {
+        c.bench_function(&format!("dense diamond {width} wide {layers} deep"), |b| {
+            let src = dense_diamond(width, layers);
+            b.iter(|| src.update(|value| *value += 1));
+            cleanup();
+        });
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
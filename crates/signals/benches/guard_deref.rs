@@ -0,0 +1,31 @@
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use samara_signals::*;
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let values = signal((0..64).collect::<Vec<u64>>());
+
+    c.bench_function("read guard deref 1M", |b| {
+        b.iter(|| {
+            let guard = values.read();
+            let mut sum = 0;
+            for i in 0..1_000_000 {
+                sum += black_box(&guard)[i % 64];
+            }
+            sum
+        });
+    });
+
+    c.bench_function("write guard deref_mut 1M", |b| {
+        b.iter(|| {
+            let mut guard = values.write();
+            for i in 0..1_000_000 {
+                black_box(&mut guard)[i % 64] += 1;
+            }
+        });
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,99 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use samara_signals::*;
+
+/// Counts allocations, to show the traffic an update causes
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    f();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+/// `w` memos of the source, each watched by an effect
+fn wide(w: usize) -> Signal<i32> {
+    let src = signal(1);
+    for _ in 0..w {
+        let doubled = memo(move || src.get() * 2);
+        effect(move || {
+            let _ = doubled.get();
+        });
+    }
+    src
+}
+
+/// A chain of `depth` memos, each also watched by an effect that propagation
+/// comes back to after the rest of the chain
+fn branchy(depth: usize) -> Signal<i32> {
+    let src = signal(1);
+    let mut chain = vec![memo(move || src.get())];
+    for _ in 1..depth {
+        let prev = *chain.last().unwrap();
+        chain.push(memo(move || prev.get() + 1));
+    }
+    let last = *chain.last().unwrap();
+    effect(move || {
+        let _ = last.get();
+    });
+    for &node in &chain[..depth - 1] {
+        effect(move || {
+            let _ = node.get();
+        });
+    }
+    src
+}
+
+/// A graph's name, how to build it and the size to build it at
+type Graph = (&'static str, fn(usize) -> Signal<i32>, usize);
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let graphs: [Graph; 2] = [("1000 * 1", wide, 1000), ("branchy * 24", branchy, 24)];
+    for (name, build, size) in graphs {
+        // Each graph gets a fresh thread, so its first update is also the
+        // first traversal of its shape
+        std::thread::spawn(move || {
+            let src = build(size);
+            let first = allocations(|| src.set(2));
+            let steady = allocations(|| src.set(3));
+            println!(
+                "{name}: {first} allocations on the first update, {steady} after, stack depth {}",
+                stats().max_stack_depth
+            );
+        })
+        .join()
+        .unwrap();
+
+        c.bench_function(&format!("stack {name}"), |b| {
+            let mut src = build(size);
+            b.iter(|| src += 1);
+            cleanup();
+        });
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
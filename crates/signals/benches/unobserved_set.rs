@@ -0,0 +1,28 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use samara_signals::*;
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("unobserved signal");
+    group.sample_size(20);
+    group.bench_function("1M sets", |b| {
+        let s = signal(0u64);
+        b.iter(|| {
+            for i in 0..1_000_000 {
+                s.set(std::hint::black_box(i));
+            }
+        });
+    });
+    group.bench_function("1M sets of a String", |b| {
+        let s = signal(String::new());
+        b.iter(|| {
+            for _ in 0..1_000_000 {
+                s.set(String::new());
+            }
+        });
+    });
+    group.finish();
+    cleanup();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
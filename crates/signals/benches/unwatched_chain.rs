@@ -0,0 +1,66 @@
+use std::time::{Duration, Instant};
+
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use samara_signals::*;
+
+/// A chain of `depth` memos that nothing watches
+fn chain(depth: usize) -> (Signal<i32>, Computed<i32>) {
+    let src = signal(1);
+    let mut last = memo(move || src.get());
+    for _ in 1..depth {
+        let prev = last;
+        last = memo(move || prev.get() + 1);
+    }
+    (src, last)
+}
+
+/// Time `sets` writes, each made after reading the end of the chain so that
+/// no memo is left pending from the previous one
+///
+/// A buffer larger than the cache is written before every write, for both
+/// chains to be timed from the same cold start. Evicting with another chain
+/// instead would leave behind whatever reading it touches, which changes
+/// with how the runtime walks unwatched chains rather than with `set()`.
+fn time_sets((src, last): (Signal<i32>, Computed<i32>), sets: i32) -> Duration {
+    let mut evict = vec![0u8; 32 << 20];
+    let mut elapsed = Duration::ZERO;
+    for i in 0..sets {
+        last.get();
+        for byte in evict.iter_mut().step_by(64) {
+            *byte = byte.wrapping_add(1);
+        }
+        let start = Instant::now();
+        src.set(i);
+        elapsed += start.elapsed();
+    }
+    std::hint::black_box(&evict);
+    elapsed
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    // Writes stop at the first memo, so the chain's depth must not matter
+    let shallow = time_sets(chain(10), 1000);
+    let deep = time_sets(chain(1000), 1000);
+    println!("1000 sets: {shallow:?} with a chain of 10, {deep:?} with a chain of 1000");
+    assert!(
+        deep < shallow * 3,
+        "set() slows down with the depth of an unwatched chain"
+    );
+
+    for depth in [10, 100, 1000] {
+        c.bench_function(&format!("unwatched chain {depth} set"), |b| {
+            let (src, last) = chain(depth);
+            b.iter_batched(
+                || {
+                    last.get();
+                },
+                |_| src.set(0),
+                BatchSize::PerIteration,
+            );
+            cleanup();
+        });
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,147 @@
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::{Rc, Weak};
+use std::task::{Context, Poll, Waker};
+
+use crate::runtime::REACTIVE_SYSTEM;
+
+#[derive(Default)]
+struct TokenState {
+    cancelled: Cell<bool>,
+    waiters: RefCell<Vec<Waker>>,
+    children: RefCell<Vec<Weak<TokenState>>>,
+}
+
+/// A flag for cooperative cancellation of async work.
+///
+/// Unlike aborting, which stops a task at its next await point, a token lets
+/// the task notice that it should stop and clean up on its own terms. Every
+/// scope that spawns a task gets a token, which is provided as a context and
+/// cancelled when the scope is cleaned up, before its tasks are aborted; see
+/// [`cancellation_token`].
+///
+/// Clones share the same state. Cancelling a token also cancels the tokens
+/// derived from it with [`child_token`](CancellationToken::child_token).
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    state: Rc<TokenState>,
+}
+
+impl CancellationToken {
+    /// Creates a token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a token that is cancelled along with this one, but can also
+    /// be cancelled on its own.
+    pub fn child_token(&self) -> Self {
+        let child = Self::new();
+        if self.is_cancelled() {
+            child.cancel();
+        } else {
+            let mut children = self.state.children.borrow_mut();
+            children.retain(|child| child.strong_count() > 0);
+            children.push(Rc::downgrade(&child.state));
+        }
+        child
+    }
+
+    /// Cancels this token and its children, waking everything waiting on
+    /// [`cancelled`](CancellationToken::cancelled).
+    pub fn cancel(&self) {
+        if self.state.cancelled.replace(true) {
+            return;
+        }
+        for waker in self.state.waiters.take() {
+            waker.wake();
+        }
+        for child in self.state.children.take() {
+            if let Some(state) = child.upgrade() {
+                CancellationToken { state }.cancel();
+            }
+        }
+    }
+
+    /// Whether the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.get()
+    }
+
+    /// Returns a future that resolves once the token is cancelled.
+    pub fn cancelled(&self) -> Cancelled {
+        Cancelled {
+            token: self.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+/// Future returned by [`CancellationToken::cancelled`].
+pub struct Cancelled {
+    token: CancellationToken,
+}
+
+impl Future for Cancelled {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.token.is_cancelled() {
+            return Poll::Ready(());
+        }
+        let mut waiters = self.token.state.waiters.borrow_mut();
+        if !waiters.iter().any(|waker| waker.will_wake(cx.waker())) {
+            waiters.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
+
+/// Returns the cancellation token of the current scope, creating it on first
+/// use.
+///
+/// The token is a child of the nearest enclosing scope's token and is also
+/// provided as a context, so code running in the scope, including its async
+/// tasks, can get it with `use_context::<CancellationToken>()`. Spawning a
+/// task creates the token of its scope. It is cancelled when the scope is
+/// disposed or, for an effect, before it re-runs; a fresh token is created
+/// for the next run.
+///
+/// [`Scope::dispose_async`](crate::Scope::dispose_async) cancels the tokens
+/// and lets every task run once before aborting them, which gives tasks
+/// waiting on [`cancelled`](CancellationToken::cancelled) the chance to exit
+/// cleanly. [`Scope::dispose`](crate::Scope::dispose) cancels the tokens
+/// too, but aborts the tasks right away.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let flushed = signal(false);
+/// let s = scope(move || {
+///     spawn(async move {
+///         let token = use_context::<CancellationToken>().unwrap();
+///         token.cancelled().await;
+///         flushed.set(true);
+///     });
+/// });
+/// join_timeout(std::time::Duration::from_millis(1)).await;
+///
+/// let dispose = s.dispose_async();
+/// spawn(dispose);
+/// join().await;
+/// assert!(flushed.get());
+/// # });
+/// ```
+pub fn cancellation_token() -> CancellationToken {
+    REACTIVE_SYSTEM.with(|ctx| ctx.cancellation_token())
+}
@@ -1,7 +1,7 @@
 use crate::{
     NodeKey,
     runtime::REACTIVE_SYSTEM,
-    types::{Location, NodeInner, caller},
+    types::{Location, caller},
 };
 use std::{marker::PhantomData, ops::Deref};
 
@@ -33,6 +33,11 @@ impl<T: 'static> Computed<T> {
         }
     }
 
+    /// The key of the underlying computed node.
+    pub fn node(&self) -> NodeKey {
+        self.node
+    }
+
     pub fn track(&self) {
         REACTIVE_SYSTEM.with(|ctx| {
             ctx.computed_track(self.node);
@@ -104,13 +109,7 @@ impl<T> Deref for ComputedRef<'_, T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        let value = REACTIVE_SYSTEM.with(|ctx| {
-            if let NodeInner::Computed(inner) = &ctx.inner.borrow().nodes[self.node].inner {
-                unsafe { &*(inner.borrow().as_any() as *const dyn std::any::Any as *const T) }
-            } else {
-                panic!("Node is not a Computed");
-            }
-        });
+        let value = REACTIVE_SYSTEM.with(|ctx| ctx.computed_value(self.node));
         unsafe { &*(value as *const T) }
     }
 }
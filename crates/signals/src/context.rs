@@ -1,4 +1,13 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::rc::Rc;
+
 use crate::runtime::REACTIVE_SYSTEM;
+use crate::scope::Scope;
+use crate::signal::{Signal, signal};
+#[cfg(any(debug_assertions, feature = "track-caller"))]
+use crate::types::format_location;
 
 /// Provide a context value in the current scope.
 ///
@@ -60,6 +69,35 @@ pub fn provide_context<T: 'static>(value: T) {
     });
 }
 
+/// Remove a context value from the current scope.
+///
+/// This undoes a [`provide_context`] made in the current scope. Only the
+/// current scope is affected: if a parent scope provides a value of the same
+/// type, it becomes visible again to subsequent [`use_context`] calls.
+///
+/// Returns the removed value, or `None` if the current scope did not provide
+/// one of this type.
+///
+/// # Example
+///
+/// ```rust
+/// # use samara_signals::{scope, provide_context, remove_context, use_context};
+/// scope(|| {
+///     provide_context(1);
+///
+///     scope(|| {
+///         provide_context(2);
+///         assert_eq!(use_context::<i32>(), Some(2));
+///
+///         assert_eq!(remove_context::<i32>(), Some(2));
+///         assert_eq!(use_context::<i32>(), Some(1));
+///     });
+/// });
+/// ```
+pub fn remove_context<T: 'static>() -> Option<T> {
+    REACTIVE_SYSTEM.with(|ctx| ctx.remove_context())
+}
+
 /// Use a context value from the current or any parent scope.
 ///
 /// This walks up the parent chain to find the nearest context of the given type.
@@ -106,6 +144,90 @@ pub fn use_context<T: 'static + Clone>() -> Option<T> {
     REACTIVE_SYSTEM.with(|ctx| ctx.use_context())
 }
 
+/// Use a context value, providing a default in the current scope if none is found.
+///
+/// The parent chain is searched like [`use_context`]. On a hit `default` is
+/// never called; on a miss its result is provided in the current scope, so
+/// it is also visible to child scopes created afterwards, and a clone is
+/// returned.
+///
+/// # Example
+///
+/// ```rust
+/// # use samara_signals::{scope, use_context, use_context_or_provide};
+/// #[derive(Clone, PartialEq, Debug)]
+/// struct Config(i32);
+///
+/// scope(|| {
+///     let config = use_context_or_provide(|| Config(1));
+///     assert_eq!(config, Config(1));
+///
+///     scope(|| {
+///         // Already provided by the parent, so the default is not used
+///         let config = use_context_or_provide(|| Config(2));
+///         assert_eq!(config, Config(1));
+///     });
+/// });
+/// ```
+pub fn use_context_or_provide<T: 'static + Clone>(default: impl FnOnce() -> T) -> T {
+    if let Some(value) = use_context::<T>() {
+        return value;
+    }
+    let value = default();
+    provide_context(value.clone());
+    value
+}
+
+/// Use a context value from the current or any parent scope, panicking if
+/// none is found.
+///
+/// # Panics
+///
+/// Panics if no context of type `T` has been provided. The message names the
+/// requested type and the location of the call. In debug builds it also
+/// lists where each scope that was searched was created, from the current
+/// scope up to the root, which usually points straight at the scope that is
+/// missing a provider.
+///
+/// # Example
+///
+/// ```rust
+/// # use samara_signals::{scope, provide_context, expect_context};
+/// scope(|| {
+///     provide_context(1);
+///     assert_eq!(expect_context::<i32>(), 1);
+/// });
+/// ```
+#[track_caller]
+pub fn expect_context<T: 'static + Clone>() -> T {
+    match use_context::<T>() {
+        Some(value) => value,
+        None => missing_context(std::any::type_name::<T>(), std::panic::Location::caller()),
+    }
+}
+
+#[cold]
+fn missing_context(type_name: &str, requested_at: &std::panic::Location) -> ! {
+    #[allow(unused_mut)]
+    let mut message = format!(
+        "expect_context::<{type_name}>() at {requested_at}: no such context has been provided"
+    );
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    {
+        use std::fmt::Write;
+
+        message.push_str("\nsearched scopes (innermost first):");
+        for (name, location) in REACTIVE_SYSTEM.with(|ctx| ctx.scope_chain()) {
+            message.push_str("\n  scope ");
+            if let Some(name) = name {
+                let _ = write!(message, "{name:?} ");
+            }
+            let _ = write!(message, "created at {}", format_location(&location));
+        }
+    }
+    panic!("{message}")
+}
+
 /// Check if a context of the given type exists in the current or any parent scope.
 ///
 /// This is useful for conditional logic or providing default values.
@@ -134,6 +256,199 @@ pub fn has_context<T: 'static>() -> bool {
     REACTIVE_SYSTEM.with(|ctx| ctx.has_context::<T>())
 }
 
+/// Provide a reactive context value in the current scope.
+///
+/// Plain contexts are frozen: consumers get a clone of the value at the time
+/// they look it up. A reactive context stores the value in a [`Signal`] owned
+/// by the current scope and provides the signal instead, so effects and
+/// computeds in any descendant scope that read it through
+/// [`use_reactive_context`] re-run when the provider sets a new value.
+///
+/// The signal is disposed together with the providing scope.
+///
+/// # Example
+///
+/// ```rust
+/// # use samara_signals::{effect, scope, provide_reactive_context, use_reactive_context};
+/// scope(|| {
+///     let theme = provide_reactive_context("light");
+///
+///     scope(|| {
+///         effect(|| {
+///             let theme = use_reactive_context::<&str>().unwrap();
+///             println!("theme is {}", theme.get());
+///         });
+///     });
+///
+///     theme.set("dark"); // The effect runs again
+/// });
+/// ```
+#[track_caller]
+pub fn provide_reactive_context<T: 'static + Clone>(value: T) -> Signal<T> {
+    let value = signal(value);
+    provide_context(value);
+    value
+}
+
+/// Use a reactive context provided with [`provide_reactive_context`] in the
+/// current or any parent scope.
+///
+/// Returns the provider's signal; reading it inside an effect or computed
+/// subscribes to changes.
+pub fn use_reactive_context<T: 'static + Clone>() -> Option<Signal<T>> {
+    use_context::<Signal<T>>()
+}
+
+/// Provide a shared, possibly unsized context value such as an
+/// `Rc<dyn Trait>` in the current scope.
+///
+/// [`use_context`] needs a concrete `Clone` type, so it cannot look up a value
+/// by trait. Values provided here are retrieved with [`use_context_dyn`],
+/// which clones the `Rc` rather than the value. Shadowing and inheritance
+/// work exactly like [`provide_context`]; in fact the value is stored as an
+/// `Rc<T>` context, so `use_context::<Rc<T>>()` finds it too.
+///
+/// # Example
+///
+/// ```rust
+/// # use std::rc::Rc;
+/// # use samara_signals::{scope, provide_context_dyn, use_context_dyn};
+/// trait Logger {
+///     fn log(&self, message: &str) -> String;
+/// }
+///
+/// struct Prefixed;
+/// impl Logger for Prefixed {
+///     fn log(&self, message: &str) -> String {
+///         format!("[app] {message}")
+///     }
+/// }
+///
+/// scope(|| {
+///     provide_context_dyn::<dyn Logger>(Rc::new(Prefixed));
+///
+///     scope(|| {
+///         let logger = use_context_dyn::<dyn Logger>().unwrap();
+///         assert_eq!(logger.log("ready"), "[app] ready");
+///     });
+/// });
+/// ```
+pub fn provide_context_dyn<T: ?Sized + 'static>(value: Rc<T>) {
+    provide_context(value);
+}
+
+/// Use a context value provided with [`provide_context_dyn`] in the current
+/// or any parent scope.
+pub fn use_context_dyn<T: ?Sized + 'static>() -> Option<Rc<T>> {
+    use_context::<Rc<T>>()
+}
+
+/// All contexts visible at some point, captured for re-providing elsewhere.
+///
+/// Portals and other off-tree rendering create scopes outside the subtree
+/// whose contexts they should see. Capture a snapshot where the contexts are
+/// visible, then provide it into the detached scope.
+///
+/// A snapshot holds clones of the internal `Rc`s, so capturing is cheap and
+/// values are shared rather than cloned. It includes every context stored by
+/// type, including reactive and `dyn` contexts, but not keyed contexts.
+///
+/// # Example
+///
+/// ```rust
+/// # use samara_signals::*;
+/// let snapshot = scope(|| {}).run_in(|| {
+///     provide_context(1i32);
+///     ContextSnapshot::capture()
+/// });
+///
+/// let ((), portal) = snapshot.run_with(|| {
+///     assert_eq!(use_context::<i32>(), Some(1));
+/// });
+/// portal.dispose();
+/// ```
+#[derive(Clone, Default)]
+pub struct ContextSnapshot {
+    contexts: HashMap<TypeId, Rc<dyn Any>>,
+}
+
+impl ContextSnapshot {
+    /// Captures the contexts visible from the current scope.
+    ///
+    /// When several scopes on the parent chain provide the same type, the
+    /// innermost value is captured, just as [`use_context`] would find it.
+    pub fn capture() -> Self {
+        Self {
+            contexts: REACTIVE_SYSTEM.with(|ctx| ctx.capture_contexts()),
+        }
+    }
+
+    /// Provides the captured contexts in `scope`, overwriting any values of
+    /// the same types it already provides.
+    pub fn provide_into(&self, scope: &Scope) {
+        REACTIVE_SYSTEM.with(|ctx| ctx.provide_contexts(scope.node(), &self.contexts));
+    }
+
+    /// Creates a child scope of the current scope, provides the captured
+    /// contexts in it and runs `f` there, returning the closure's output
+    /// together with the scope.
+    #[track_caller]
+    pub fn run_with<O>(&self, f: impl FnOnce() -> O) -> (O, Scope) {
+        let scope = Scope::new(REACTIVE_SYSTEM.with(|ctx| ctx.current_scope())).child();
+        self.provide_into(&scope);
+        (scope.run_in(f), scope)
+    }
+
+    /// The number of captured contexts.
+    pub fn len(&self) -> usize {
+        self.contexts.len()
+    }
+
+    /// Whether no contexts were visible when the snapshot was captured.
+    pub fn is_empty(&self) -> bool {
+        self.contexts.is_empty()
+    }
+}
+
+/// Provide a context value under an explicit key in the current scope.
+///
+/// Type-keyed contexts allow a single value per type, so two unrelated
+/// libraries that both provide an `i32` would collide. Keyed contexts are
+/// identified by the key *and* the value type, and are stored separately
+/// from the ones installed with [`provide_context`].
+///
+/// Providing the same key again in the same scope overwrites the previous
+/// value; providing it in a child scope shadows the parent's value.
+///
+/// # Example
+///
+/// ```rust
+/// # use samara_signals::{scope, provide_context_keyed, use_context_keyed};
+/// scope(|| {
+///     provide_context_keyed("width", 10);
+///     provide_context_keyed("height", 20);
+///
+///     scope(|| {
+///         assert_eq!(use_context_keyed::<_, i32>(&"width"), Some(10));
+///         assert_eq!(use_context_keyed::<_, i32>(&"height"), Some(20));
+///     });
+/// });
+/// ```
+pub fn provide_context_keyed<K: Eq + Hash + 'static, T: 'static>(key: K, value: T) {
+    REACTIVE_SYSTEM.with(|ctx| {
+        ctx.provide_context_keyed(key, value);
+    });
+}
+
+/// Use a keyed context value from the current or any parent scope.
+///
+/// Like [`use_context`], this walks up the parent chain and returns the
+/// nearest value provided with [`provide_context_keyed`] under an equal key
+/// and with the same value type `T`.
+pub fn use_context_keyed<K: Eq + Hash + 'static, T: 'static + Clone>(key: &K) -> Option<T> {
+    REACTIVE_SYSTEM.with(|ctx| ctx.use_context_keyed(key))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,157 @@
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use crate::effect::effect;
+use crate::signal::{Signal, signal};
+
+/// A change made to a [`SignalMap`](crate::SignalMap) or
+/// [`SignalBTreeMap`](crate::SignalBTreeMap).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MapDelta<K, V> {
+    /// `key` was added, or its value replaced, with `value`.
+    Insert { key: K, value: V },
+    /// `key` was removed.
+    Remove { key: K },
+}
+
+/// A change made to a [`SignalSet`](crate::SignalSet).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SetDelta<T> {
+    /// The member was added.
+    Insert(T),
+    /// The member was removed.
+    Remove(T),
+}
+
+/// A callback registered with one of the collections' `subscribe_deltas`.
+///
+/// The callback stays registered until [`unsubscribe`](Subscription::unsubscribe)
+/// is called or the collection is dropped; dropping the handle does not
+/// remove it.
+pub struct Subscription {
+    remove: Box<dyn FnOnce()>,
+}
+
+impl Subscription {
+    /// Removes the callback. Changes it has not been given yet are dropped.
+    pub fn unsubscribe(self) {
+        (self.remove)();
+    }
+}
+
+type Callback<D> = Rc<RefCell<dyn FnMut(&[D])>>;
+
+struct Subscriber<D> {
+    id: u64,
+    /// Changes made since this subscriber was last called
+    pending: Vec<D>,
+    callback: Callback<D>,
+}
+
+struct Subscribers<D> {
+    next_id: u64,
+    list: Vec<Subscriber<D>>,
+}
+
+/// The changes of a collection, buffered per subscriber and delivered by an
+/// effect once per flush
+pub(crate) struct DeltaChannel<D> {
+    subscribers: Rc<RefCell<Subscribers<D>>>,
+    /// Set after a change is recorded, re-running the delivering effect
+    changed: Signal<()>,
+}
+
+impl<D> Clone for DeltaChannel<D> {
+    fn clone(&self) -> Self {
+        Self {
+            subscribers: self.subscribers.clone(),
+            changed: self.changed,
+        }
+    }
+}
+
+impl<D: Clone + 'static> DeltaChannel<D> {
+    /// Create a channel whose delivering effect is owned by the current scope
+    #[track_caller]
+    pub fn new() -> Self {
+        let subscribers = Rc::new(RefCell::new(Subscribers {
+            next_id: 0,
+            list: Vec::new(),
+        }));
+        let changed = signal(());
+        let delivered = subscribers.clone();
+        effect(move || {
+            changed.track();
+            deliver(&delivered);
+        });
+        Self {
+            subscribers,
+            changed,
+        }
+    }
+
+    /// Register `f`, which only sees the changes recorded from now on
+    pub fn subscribe(&self, f: impl FnMut(&[D]) + 'static) -> Subscription {
+        let mut subscribers = self.subscribers.borrow_mut();
+        let id = subscribers.next_id;
+        subscribers.next_id += 1;
+        subscribers.list.push(Subscriber {
+            id,
+            pending: Vec::new(),
+            callback: Rc::new(RefCell::new(f)),
+        });
+
+        let subscribers: Weak<RefCell<Subscribers<D>>> = Rc::downgrade(&self.subscribers);
+        Subscription {
+            remove: Box::new(move || {
+                if let Some(subscribers) = subscribers.upgrade() {
+                    subscribers.borrow_mut().list.retain(|sub| sub.id != id);
+                }
+            }),
+        }
+    }
+
+    /// Queue a change for every subscriber, building it only if there is one
+    ///
+    /// Call [`notify`](Self::notify) once the change has been applied, so
+    /// subscribers never see a change before the collection does.
+    pub fn record(&self, delta: impl FnOnce() -> D) {
+        let mut subscribers = self.subscribers.borrow_mut();
+        let Some((last, rest)) = subscribers.list.split_last_mut() else {
+            return;
+        };
+        let delta = delta();
+        for sub in rest {
+            sub.pending.push(delta.clone());
+        }
+        last.pending.push(delta);
+    }
+
+    /// Schedule delivery of the recorded changes, with the next flush
+    pub fn notify(&self) {
+        let pending = (self.subscribers.borrow().list.iter()).any(|sub| !sub.pending.is_empty());
+        if pending {
+            self.changed.set(());
+        }
+    }
+}
+
+/// Hand every subscriber its pending changes, until none are left
+///
+/// Subscribers may change the collection, subscribe or unsubscribe, so no
+/// borrow is held while they run; changes they make are delivered by the
+/// next round.
+fn deliver<D>(subscribers: &RefCell<Subscribers<D>>) {
+    loop {
+        let round: Vec<_> = (subscribers.borrow_mut().list.iter_mut())
+            .filter(|sub| !sub.pending.is_empty())
+            .map(|sub| (sub.callback.clone(), std::mem::take(&mut sub.pending)))
+            .collect();
+        if round.is_empty() {
+            return;
+        }
+        for (callback, pending) in round {
+            (callback.borrow_mut())(&pending);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+use futures_core::Stream;
+use serde::Serialize;
+
+use crate::observer::RuntimeObserver;
+use crate::runtime::REACTIVE_SYSTEM;
+use crate::snapshot::NodeKind;
+use crate::types::NodeKey;
+
+/// How many events a [`DevtoolsEvents`] stream holds before dropping the
+/// oldest.
+const CAPACITY: usize = 1024;
+
+/// A mutation of the reactive graph, as reported by [`devtools_events`].
+///
+/// Serializes as an object tagged with its variant name, such as
+/// `{"type":"LinkCreated","dep":...,"sub":...}`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "type")]
+pub enum DevtoolsEvent {
+    /// A node was added. `location` is `file:line:col`, `None` in release
+    /// builds without the `track-caller` feature.
+    NodeCreated {
+        node: NodeKey,
+        kind: NodeKind,
+        location: Option<String>,
+    },
+    NodeDisposed {
+        node: NodeKey,
+    },
+    /// `sub` started depending on `dep`.
+    LinkCreated {
+        dep: NodeKey,
+        sub: NodeKey,
+    },
+    /// `sub` stopped depending on `dep`.
+    LinkRemoved {
+        dep: NodeKey,
+        sub: NodeKey,
+    },
+    EffectRan {
+        node: NodeKey,
+        duration_us: u64,
+    },
+    SignalSet {
+        node: NodeKey,
+    },
+}
+
+/// The ring buffer shared between the reactive system and one stream
+#[derive(Default)]
+pub struct DevtoolsBuffer {
+    events: RefCell<VecDeque<DevtoolsEvent>>,
+    dropped: Cell<u64>,
+    waker: RefCell<Option<Waker>>,
+}
+
+impl DevtoolsBuffer {
+    fn push(&self, event: DevtoolsEvent) {
+        let mut events = self.events.borrow_mut();
+        if events.len() == CAPACITY {
+            events.pop_front();
+            self.dropped.set(self.dropped.get() + 1);
+        }
+        events.push_back(event);
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl RuntimeObserver for DevtoolsBuffer {
+    fn node_created(
+        &self,
+        node: NodeKey,
+        kind: NodeKind,
+        location: Option<&'static std::panic::Location<'static>>,
+    ) {
+        self.push(DevtoolsEvent::NodeCreated {
+            node,
+            kind,
+            location: location.map(ToString::to_string),
+        });
+    }
+
+    fn node_disposed(&self, node: NodeKey) {
+        self.push(DevtoolsEvent::NodeDisposed { node });
+    }
+
+    fn link_created(&self, dep: NodeKey, sub: NodeKey) {
+        self.push(DevtoolsEvent::LinkCreated { dep, sub });
+    }
+
+    fn link_removed(&self, dep: NodeKey, sub: NodeKey) {
+        self.push(DevtoolsEvent::LinkRemoved { dep, sub });
+    }
+
+    fn effect_ran(&self, effect: NodeKey, duration: Duration) {
+        self.push(DevtoolsEvent::EffectRan {
+            node: effect,
+            duration_us: duration.as_micros() as u64,
+        });
+    }
+
+    fn signal_set(&self, signal: NodeKey) {
+        self.push(DevtoolsEvent::SignalSet { node: signal });
+    }
+}
+
+/// Stream returned by [`devtools_events`].
+pub struct DevtoolsEvents {
+    buffer: Rc<DevtoolsBuffer>,
+}
+
+impl DevtoolsEvents {
+    /// How many events were dropped so far because the stream was not
+    /// consumed fast enough.
+    pub fn dropped(&self) -> u64 {
+        self.buffer.dropped.get()
+    }
+}
+
+impl Stream for DevtoolsEvents {
+    type Item = DevtoolsEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match self.buffer.events.borrow_mut().pop_front() {
+            Some(event) => Poll::Ready(Some(event)),
+            None => {
+                *self.buffer.waker.borrow_mut() = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Streams the mutations of the current thread's reactive graph from now on,
+/// for forwarding to devtools over a WebSocket or similar.
+///
+/// Events are recorded by the reactive system as they happen and buffered
+/// until the stream is polled. The buffer holds 1024 events; when it is full
+/// the oldest are dropped, so a slow consumer never blocks the reactive
+/// thread, see [`DevtoolsEvents::dropped`]. The stream never ends; dropping
+/// it stops the recording.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// # use futures_util::StreamExt;
+/// let mut events = devtools_events();
+/// let a = signal(1);
+/// a.set(2);
+///
+/// let first = futures_util::FutureExt::now_or_never(events.next()).flatten().unwrap();
+/// let json = serde_json::to_value(&first).unwrap();
+/// assert_eq!(json["type"], "NodeCreated");
+/// assert_eq!(json["kind"], "Signal");
+/// ```
+pub fn devtools_events() -> DevtoolsEvents {
+    let buffer = Rc::new(DevtoolsBuffer::default());
+    REACTIVE_SYSTEM.with(|ctx| ctx.add_devtools_buffer(Rc::downgrade(&buffer)));
+    DevtoolsEvents { buffer }
+}
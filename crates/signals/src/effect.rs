@@ -1,4 +1,5 @@
 use crate::runtime::REACTIVE_SYSTEM;
+use crate::system::{CapacityStats, CompactReport, CountStats, FlushStats};
 use crate::types::{Location, NodeKey, caller};
 
 #[derive(Clone, Copy)]
@@ -11,11 +12,29 @@ impl Effect {
         let node = REACTIVE_SYSTEM.with(move |ctx| ctx.new_effect(effect, caller));
         Self { node }
     }
+
+    /// The key of the underlying effect node.
+    pub fn node(&self) -> NodeKey {
+        self.node
+    }
+    #[track_caller]
     pub fn dispose(&self) {
+        let caller = caller();
         REACTIVE_SYSTEM.with(|ctx| {
-            ctx.dispose_scope(self.node);
+            ctx.dispose_scope(self.node, caller);
         });
     }
+
+    /// Labels this effect in snapshots and diagnostics, like
+    /// [`Scope::set_name`](crate::Scope::set_name).
+    pub fn set_name(&self, name: &str) {
+        REACTIVE_SYSTEM.with(|ctx| ctx.set_node_name(self.node, name));
+    }
+
+    /// The name set with [`set_name`](Effect::set_name).
+    pub fn name(&self) -> Option<String> {
+        REACTIVE_SYSTEM.with(|ctx| ctx.node_name(self.node))
+    }
 }
 
 #[track_caller]
@@ -34,9 +53,9 @@ pub fn trigger<F: Fn() + 'static>(f: F) {
 ///
 /// The cleanup function will be called in LIFO order (last registered, first called).
 ///
-/// # Panics
-///
-/// Panics if called outside of any reactive scope (effect or scope).
+/// Outside of any scope or effect the callback is attached to the root scope
+/// and only runs on a global [`cleanup`](crate::cleanup). Use
+/// [`try_on_cleanup`] to detect that case.
 ///
 /// # Example
 ///
@@ -56,18 +75,253 @@ pub fn on_cleanup<F: FnOnce() + 'static>(f: F) {
     });
 }
 
+/// Error returned by [`try_on_cleanup`] when there is no scope to attach to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OutsideScopeError;
+
+impl std::fmt::Display for OutsideScopeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("on_cleanup called outside of any scope or effect")
+    }
+}
+
+impl std::error::Error for OutsideScopeError {}
+
+/// Like [`on_cleanup`], but refuses to attach the callback to the root scope.
+///
+/// Libraries can use this to tell whether they were called from inside a
+/// scope or effect; `f` is dropped without running when they were not.
+///
+/// # Example
+///
+/// ```rust
+/// # use samara_signals::*;
+/// assert_eq!(try_on_cleanup(|| {}), Err(OutsideScopeError));
+///
+/// scope(|| {
+///     assert_eq!(try_on_cleanup(|| {}), Ok(()));
+/// });
+/// ```
+pub fn try_on_cleanup<F: FnOnce() + 'static>(f: F) -> Result<(), OutsideScopeError> {
+    REACTIVE_SYSTEM.with(|ctx| {
+        if ctx.in_root_scope() {
+            return Err(OutsideScopeError);
+        }
+        ctx.on_cleanup(f);
+        Ok(())
+    })
+}
+
 pub fn start_batch() {
     REACTIVE_SYSTEM.with(|ctx| {
         ctx.start_batch();
     });
 }
 
+/// Close the batch opened by the matching [`start_batch`], flushing queued
+/// effects once the outermost batch closes.
+///
+/// # Panics
+///
+/// Panics with the caller's location if there is no open batch. The batch
+/// depth is left untouched, so the runtime keeps working afterwards.
+#[track_caller]
 pub fn end_batch() {
+    let caller = caller();
     REACTIVE_SYSTEM.with(|ctx| {
-        ctx.end_batch();
+        ctx.end_batch(caller);
     });
 }
 
+/// Run all queued effects immediately.
+///
+/// This is a no-op when nothing is queued. Inside an open batch it
+/// force-flushes the effects queued so far; writes made after the call are
+/// still deferred until the batch closes.
+///
+/// # Panics
+///
+/// Panics if called while effects are already being flushed, i.e. from
+/// inside an effect that is run by a flush.
+///
+/// # Example
+///
+/// ```rust
+/// # use samara_signals::*;
+/// let s = signal(0);
+/// let _e = effect(move || {
+///     s.get();
+/// });
+///
+/// start_batch();
+/// s.set(1);
+/// flush_sync(); // The effect observes `1` here
+/// end_batch();
+/// ```
+pub fn flush_sync() {
+    REACTIVE_SYSTEM.with(|ctx| {
+        ctx.flush_sync();
+    });
+}
+
+/// Enable or disable automatic batching of writes.
+///
+/// In auto-batch mode a write outside an explicit batch still marks its
+/// subscribers, but queued effects only run at the next [`poll`](crate::poll)
+/// or [`join`](crate::join) of the executor, or on [`flush_sync`]. Many writes
+/// in a row therefore coalesce into a single run of each affected effect.
+///
+/// Disabling the mode immediately flushes anything deferred so far.
+///
+/// # Example
+///
+/// ```rust
+/// # use samara_signals::*;
+/// let s = signal(0);
+/// let _e = effect(move || {
+///     s.get();
+/// });
+///
+/// set_auto_batch(true);
+/// s.set(1);
+/// s.set(2);
+/// assert_eq!(pending_effects(), 1);
+/// flush_sync();
+/// set_auto_batch(false);
+/// ```
+pub fn set_auto_batch(enabled: bool) {
+    REACTIVE_SYSTEM.with(|ctx| ctx.set_auto_batch(enabled));
+}
+
+/// Returns whether automatic batching is enabled, see [`set_auto_batch`].
+pub fn auto_batch() -> bool {
+    REACTIVE_SYSTEM.with(|ctx| ctx.auto_batch())
+}
+
+/// Returns how many effects the queue can hold before it has to grow.
+///
+/// The queue keeps its allocation between flushes, so this stays put once
+/// it has grown to fit the largest flush. Meant for tests and tuning.
+pub fn queue_capacity() -> usize {
+    REACTIVE_SYSTEM.with(|ctx| ctx.queue_capacity())
+}
+
+/// Returns the statistics of the most recent flush that ran at least one
+/// queued effect, or `None` if no such flush has happened yet.
+pub fn last_flush_stats() -> Option<FlushStats> {
+    REACTIVE_SYSTEM.with(|ctx| ctx.last_flush_stats())
+}
+
+/// Register a callback that receives the [`FlushStats`] of every flush that
+/// ran at least one queued effect, replacing any previous callback.
+///
+/// The callback runs after the queue has been drained.
+pub fn on_flush<F: Fn(&FlushStats) + 'static>(f: F) {
+    REACTIVE_SYSTEM.with(|ctx| ctx.on_flush(f));
+}
+
+/// A flush hook registered with [`on_flush_start`] or [`on_flush_end`].
+///
+/// The hook stays registered until [`remove`](HookHandle::remove) is called;
+/// dropping the handle does not remove it.
+pub struct HookHandle {
+    id: u64,
+}
+
+impl HookHandle {
+    /// Unregisters the hook. Does nothing if the thread's reactive system is
+    /// gone.
+    pub fn remove(self) {
+        let _ = REACTIVE_SYSTEM.try_with(|ctx| ctx.remove_flush_hook(self.id));
+    }
+}
+
+/// Register a callback that runs at the start of every flush with at least
+/// one queued effect, before any of them runs.
+///
+/// Flushes started by effects of a running flush are part of it and do not
+/// call the hook again. The hook runs inside a batch, so effects queued by
+/// its writes run in the flush that is starting.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// # use std::{cell::Cell, rc::Rc};
+/// let a = signal(0);
+/// let _e = effect(move || {
+///     a.get();
+/// });
+///
+/// let starts = Rc::new(Cell::new(0));
+/// let hook = on_flush_start({
+///     let starts = starts.clone();
+///     move || starts.set(starts.get() + 1)
+/// });
+/// a.set(1);
+/// assert_eq!(starts.get(), 1);
+/// hook.remove();
+/// a.set(2);
+/// assert_eq!(starts.get(), 1);
+/// ```
+pub fn on_flush_start(f: impl FnMut() + 'static) -> HookHandle {
+    let id = REACTIVE_SYSTEM.with(|ctx| ctx.add_flush_start_hook(f));
+    HookHandle { id }
+}
+
+/// Register a callback that runs with the [`FlushStats`] of every flush that
+/// ran at least one queued effect, once all of them have settled.
+///
+/// Unlike [`on_flush`], any number of hooks can be registered. The hook runs
+/// inside a batch, so effects queued by its writes wait for the next flush
+/// instead of re-entering this one.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// # use std::{cell::Cell, rc::Rc};
+/// let a = signal(0);
+/// let _e = effect(move || {
+///     a.get();
+/// });
+///
+/// let frames = Rc::new(Cell::new(0));
+/// let _hook = on_flush_end({
+///     let frames = frames.clone();
+///     move |_| frames.set(frames.get() + 1)
+/// });
+/// start_batch();
+/// a.set(1);
+/// a.set(2);
+/// end_batch();
+/// assert_eq!(frames.get(), 1);
+/// ```
+pub fn on_flush_end(f: impl FnMut(&FlushStats) + 'static) -> HookHandle {
+    let id = REACTIVE_SYSTEM.with(|ctx| ctx.add_flush_end_hook(f));
+    HookHandle { id }
+}
+
+/// Returns `true` while at least one [`start_batch`] has not been matched by
+/// an [`end_batch`].
+///
+/// Effects flushed by the outermost `end_batch` run after the depth has
+/// already dropped back to zero, so this reports `false` from inside them.
+pub fn is_batching() -> bool {
+    batch_depth() > 0
+}
+
+/// Returns the current batch nesting depth.
+pub fn batch_depth() -> usize {
+    REACTIVE_SYSTEM.with(|ctx| ctx.batch_depth())
+}
+
+/// Returns the number of queued effects that have not run yet.
+///
+/// Inside a batch this is the number of effects waiting for the batch to
+/// close; during a flush it counts the effects still left to run.
+pub fn pending_effects() -> usize {
+    REACTIVE_SYSTEM.with(|ctx| ctx.pending_effects())
+}
+
 /// Returns the current counts of nodes and links in the reactive system.
 ///
 /// Returns a tuple of `(nodes_count, links_count)`.
@@ -75,6 +329,98 @@ pub fn count() -> (usize, usize) {
     REACTIVE_SYSTEM.with(|ctx| ctx.count())
 }
 
+/// Makes room for at least `nodes` more nodes and `links` more links, so
+/// building a graph of known size does not reallocate along the way.
+///
+/// Removed nodes and links free their slots for reuse, so a graph whose size
+/// stays steady keeps its allocation without this.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// reserve(100, 1000);
+/// assert!(links_capacity() >= count().1 + 1000);
+/// ```
+pub fn reserve(nodes: usize, links: usize) {
+    REACTIVE_SYSTEM.with(|ctx| ctx.reserve(nodes, links));
+}
+
+/// Returns how many links the reactive system can hold before it has to
+/// grow. Meant for tests and tuning, see [`reserve`].
+pub fn links_capacity() -> usize {
+    REACTIVE_SYSTEM.with(|ctx| ctx.links_capacity())
+}
+
+/// Gives back memory the reactive system holds beyond what its live nodes
+/// need, after a burst of short-lived scopes has grown it.
+///
+/// The maps of cleanups, contexts and other per-scope data are rebuilt at
+/// their current size, and the effect queue and traversal stack drop the
+/// room they grew to. The node and link maps are slot maps: live entries
+/// cannot move, so only free slots above the highest live one could ever be
+/// reclaimed, and the underlying slot map cannot truncate even those. The
+/// node map therefore keeps its allocation, and the link map is only
+/// replaced once no link is left. See [`compact_report`] for how much of
+/// their room is free.
+///
+/// Returns `false` without doing anything when called during a batch, a
+/// flush or a tracked read, which may be using the structures it rebuilds.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// for _ in 0..100 {
+///     scope(|| provide_context(0u8)).dispose();
+/// }
+/// assert!(shrink_to_fit());
+/// assert_eq!(capacity_stats().contexts.capacity, 0);
+/// ```
+pub fn shrink_to_fit() -> bool {
+    REACTIVE_SYSTEM.with(|ctx| ctx.shrink_to_fit())
+}
+
+/// Returns how many entries the node, link, cleanup and context maps hold
+/// and how many they have room for, see [`CapacityStats`].
+pub fn capacity_stats() -> CapacityStats {
+    REACTIVE_SYSTEM.with(|ctx| ctx.capacity_stats())
+}
+
+/// Describes how the live nodes and links are spread over the slots of
+/// their maps, see [`CompactReport`] and [`shrink_to_fit`].
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// let scopes: Vec<_> = (0..10).map(|_| scope(|| {})).collect();
+/// scopes[0].dispose();
+/// let report = compact_report();
+/// assert_eq!(report.nodes.holes(), 1);
+/// println!("{report}");
+/// ```
+pub fn compact_report() -> CompactReport {
+    REACTIVE_SYSTEM.with(|ctx| ctx.compact_report())
+}
+
+/// Returns the current counts of nodes by kind and of links, see
+/// [`CountStats`].
+///
+/// Unlike [`count`], the root scope is not counted, so a fresh thread
+/// reports all zeros.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// let a = signal(1);
+/// let _doubled = memo(move || a.get() * 2);
+///
+/// let stats = stats();
+/// assert_eq!((stats.signals, stats.computeds), (1, 1));
+/// assert_eq!(stats.total_nodes_excluding_root, count().0 - 1);
+/// ```
+pub fn stats() -> CountStats {
+    REACTIVE_SYSTEM.with(|ctx| ctx.stats())
+}
+
 pub fn serialize() -> String {
     REACTIVE_SYSTEM.with(|ctx| serde_json::ser::to_string(ctx).unwrap())
 }
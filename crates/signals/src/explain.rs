@@ -0,0 +1,73 @@
+use crate::runtime::REACTIVE_SYSTEM;
+use crate::snapshot::NodeKind;
+use crate::types::NodeKey;
+
+/// How a write would leave a node of a [`PropagationPlan`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlannedMark {
+    /// The node reads the written signal directly, so it will recompute or
+    /// re-run once it is checked.
+    Dirty,
+    /// The node depends on the signal through others and will only
+    /// recompute or re-run if one of those actually changes.
+    Pending,
+}
+
+/// A node a write would mark, see [`explain_set`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlannedNode {
+    pub node: NodeKey,
+    pub kind: NodeKind,
+    pub mark: PlannedMark,
+    /// Where the node was created as `file:line:col`; `None` in release
+    /// builds, which do not record locations.
+    pub location: Option<String>,
+    /// The dependency path the write takes to reach the node, from the
+    /// written signal to the node itself.
+    pub path: Vec<NodeKey>,
+}
+
+/// What writing a signal would do to the graph, see [`explain_set`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PropagationPlan {
+    /// The signal being written.
+    pub source: NodeKey,
+    /// The nodes that would be marked, in the order propagation reaches
+    /// them. Each node appears at most once.
+    pub marked: Vec<PlannedNode>,
+    /// The effects that would be queued, in the order they would run.
+    pub effects: Vec<NodeKey>,
+}
+
+impl PropagationPlan {
+    /// Returns how `node` would be marked, or `None` if the write would not
+    /// reach it.
+    pub fn get(&self, node: NodeKey) -> Option<&PlannedNode> {
+        self.marked.iter().find(|planned| planned.node == node)
+    }
+}
+
+/// Explains what setting the signal with key `signal` would wake, without
+/// running anything.
+///
+/// This follows the same traversal as a real write, but records the nodes
+/// it would mark and the effects it would queue instead of changing any
+/// flags, so the graph is left exactly as it was.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// let a = signal(1);
+/// let doubled = memo(move || a.get() * 2);
+/// let e = effect(move || {
+///     doubled.get();
+/// });
+///
+/// let plan = explain_set(a.node());
+/// assert_eq!(plan.marked.len(), 2);
+/// assert_eq!(plan.get(e.node()).unwrap().path.len(), 3);
+/// assert_eq!(plan.effects, [e.node()]);
+/// ```
+pub fn explain_set(signal: NodeKey) -> PropagationPlan {
+    REACTIVE_SYSTEM.with(|ctx| ctx.explain_propagate(signal))
+}
@@ -1,5 +1,9 @@
+use serde::de::Error;
+use serde::ser::SerializeSeq;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Cow;
+use std::fmt;
 use std::ops::{BitAnd, BitAndAssign, BitOr, Not};
-use serde::Serialize;
 
 /// Reactive node flags stored as a bitset for efficient operations.
 ///
@@ -7,11 +11,16 @@ use serde::Serialize;
 /// - Bit 0: MUTABLE - Node can be modified (signals)
 /// - Bit 1: WATCHING - Node is an active effect/computed
 /// - Bit 2: RECURSED_CHECK - Node is in recursion check phase
-/// - Bit 3: RECURSED - Node has been visited during propagation
+/// - Bit 3: RECURSED - No longer set; `propagate` recognizes revisits by
+///   PENDING and DIRTY alone
 /// - Bit 4: DIRTY - Node needs recomputation
 /// - Bit 5: PENDING - Node is queued for update
-/// - Bits 6-7: Reserved for future use
-#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq)]
+/// - Bit 6: HAS_WATCHER - Some transitive subscriber is WATCHING
+/// - Bit 7: Reserved for future use
+///
+/// Serializes, displays and debug-prints as the names of the set flags, so
+/// reserved bits are not preserved.
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub struct ReactiveFlags(pub u8);
 
 impl ReactiveFlags {
@@ -22,6 +31,22 @@ impl ReactiveFlags {
     pub const RECURSED: Self = Self(0b0000_1000);
     pub const DIRTY: Self = Self(0b0001_0000);
     pub const PENDING: Self = Self(0b0010_0000);
+    /// Kept up to date by `link` and `unlink` rather than by the node's own
+    /// state changes; `propagate` does not walk past nodes without it.
+    pub const HAS_WATCHER: Self = Self(0b0100_0000);
+
+    /// Replace the node state flags with `flags`, keeping `HAS_WATCHER`,
+    /// which describes the node's subscribers rather than its state.
+    #[inline]
+    pub fn reset(&mut self, flags: Self) {
+        self.0 = (self.0 & Self::HAS_WATCHER.0) | flags.0;
+    }
+
+    /// Whether the node is an effect or something an effect depends on.
+    #[inline]
+    pub fn is_watched(&self) -> bool {
+        self.intersects(Self::WATCHING | Self::HAS_WATCHER)
+    }
 
     #[inline]
     pub fn remove(&mut self, other: Self) {
@@ -42,6 +67,81 @@ impl ReactiveFlags {
     pub fn is_empty(&self) -> bool {
         self.0 == 0
     }
+
+    /// Names of the set flags, lowest bit first.
+    pub fn names(&self) -> Vec<&'static str> {
+        FLAG_NAMES
+            .into_iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| name)
+            .collect()
+    }
+
+    /// The flags named by `names`, as produced by [`names`](Self::names).
+    /// Unknown names are ignored.
+    pub fn from_names<'a>(names: impl IntoIterator<Item = &'a str>) -> Self {
+        names
+            .into_iter()
+            .filter_map(Self::from_name)
+            .fold(Self::NONE, |flags, flag| flags | flag)
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        FLAG_NAMES
+            .iter()
+            .find(|(_, known)| *known == name)
+            .map(|(flag, _)| *flag)
+    }
+}
+
+const FLAG_NAMES: [(ReactiveFlags, &str); 7] = [
+    (ReactiveFlags::MUTABLE, "MUTABLE"),
+    (ReactiveFlags::WATCHING, "WATCHING"),
+    (ReactiveFlags::RECURSED_CHECK, "RECURSED_CHECK"),
+    (ReactiveFlags::RECURSED, "RECURSED"),
+    (ReactiveFlags::DIRTY, "DIRTY"),
+    (ReactiveFlags::PENDING, "PENDING"),
+    (ReactiveFlags::HAS_WATCHER, "HAS_WATCHER"),
+];
+
+impl fmt::Display for ReactiveFlags {
+    /// Writes the set flags as `MUTABLE | DIRTY`, or `NONE`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = self.names();
+        if names.is_empty() {
+            return f.write_str("NONE");
+        }
+        f.write_str(&names.join(" | "))
+    }
+}
+
+impl fmt::Debug for ReactiveFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ReactiveFlags({self})")
+    }
+}
+
+impl Serialize for ReactiveFlags {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let names = self.names();
+        let mut seq = serializer.serialize_seq(Some(names.len()))?;
+        for name in names {
+            seq.serialize_element(name)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for ReactiveFlags {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<Cow<'de, str>>::deserialize(deserializer)?
+            .iter()
+            .try_fold(Self::NONE, |flags, name| {
+                Self::from_name(name)
+                    .map(|flag| flags | flag)
+                    .ok_or_else(|| D::Error::custom(format!("unknown reactive flag {name:?}")))
+            })
+    }
 }
 
 impl BitOr for ReactiveFlags {
@@ -77,3 +177,36 @@ impl Not for ReactiveFlags {
         Self(!self.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flag_names_round_trip() {
+        for bits in 0..1u8 << FLAG_NAMES.len() {
+            let flags = ReactiveFlags(bits);
+            let json = serde_json::to_string(&flags).unwrap();
+            assert_eq!(serde_json::from_str::<ReactiveFlags>(&json).unwrap(), flags);
+            assert_eq!(ReactiveFlags::from_names(flags.names()), flags);
+        }
+    }
+
+    #[test]
+    fn test_flags_format_as_names() {
+        let flags = ReactiveFlags::MUTABLE | ReactiveFlags::DIRTY;
+        assert_eq!(
+            serde_json::to_string(&flags).unwrap(),
+            r#"["MUTABLE","DIRTY"]"#
+        );
+        assert_eq!(flags.to_string(), "MUTABLE | DIRTY");
+        assert_eq!(format!("{flags:?}"), "ReactiveFlags(MUTABLE | DIRTY)");
+        assert_eq!(ReactiveFlags::NONE.to_string(), "NONE");
+    }
+
+    #[test]
+    fn test_unknown_flag_name_is_rejected() {
+        let err = serde_json::from_str::<ReactiveFlags>(r#"["MUTABLE","STALE"]"#).unwrap_err();
+        assert!(err.to_string().contains("STALE"), "{err}");
+    }
+}
@@ -1,53 +1,909 @@
-use crate::{Signal, effect, end_batch, runtime::executor::Executor, signal, start_batch};
-use std::{future::Future, rc::Rc};
+use crate::runtime::REACTIVE_SYSTEM;
+use crate::runtime::executor::{Executor, JoinResult, TaskState, TickReport};
+use crate::scope::{untracked, with_root};
+use crate::suspense::PendingTracker;
+use crate::time::sleep_until;
+use crate::types::caller;
+use crate::{Computed, Effect, Scope, Signal, effect, end_batch, signal, start_batch};
+use futures_util::future::Aborted;
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    convert::Infallible,
+    future::Future,
+    hash::Hash,
+    ops::Deref,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
 
 thread_local! {
     pub static EXECUTOR: Executor = Executor::new();
 }
 
 /// Spawn an async task on the single-threaded executor
-pub fn spawn<F>(future: F)
+///
+/// The task runs in the current scope and is aborted when the scope is
+/// disposed; the scope's [`CancellationToken`](crate::CancellationToken) is
+/// cancelled just before.
+///
+/// # Ordering
+///
+/// Tasks start in the order they were spawned. After that, tasks that are
+/// ready to make progress in the same pass of the executor are polled in
+/// spawn order too, whatever order they were woken in, so their writes and
+/// the effects those run are applied deterministically. The returned handle can abort it earlier or wait for it;
+/// dropping the handle leaves the task running.
+#[track_caller]
+pub fn spawn<F>(future: F) -> TaskHandle
 where
     F: Future<Output = ()> + 'static,
 {
-    EXECUTOR.with(|executor| {
-        executor.spawn(future);
+    // Make the scope's token available to the task as a context
+    REACTIVE_SYSTEM.with(|ctx| ctx.cancellation_token());
+    let location = caller();
+    TaskHandle {
+        state: EXECUTOR.with(|executor| executor.spawn(future, location)),
+    }
+}
+
+/// Creates an effect whose body is an async block.
+///
+/// Each run calls `f` to create a future and spawns it, like
+/// `effect(move || { spawn(f()); })`. Signals read while `f` creates the
+/// future are tracked before anything is awaited, and so are signals read
+/// while the future is polled. When a dependency changes the running future
+/// is aborted and a new one started, so at most one instance runs at a time.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// # tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap().block_on(async {
+/// let query = signal("a");
+/// let results = signal(Vec::new());
+/// effect_async(move || {
+///     let query = query.get();
+///     async move {
+///         results.write().push(query);
+///     }
+/// });
+/// join().await;
+/// query.set("b");
+/// join().await;
+/// assert_eq!(results.get(), vec!["a", "b"]);
+/// # });
+/// ```
+#[track_caller]
+pub fn effect_async<F, Fut>(f: F) -> Effect
+where
+    F: Fn() -> Fut + 'static,
+    Fut: Future<Output = ()> + 'static,
+{
+    Effect::new(
+        move || {
+            spawn(f());
+        },
+        caller(),
+    )
+}
+
+/// Registers async teardown to run when the current scope is cleaned up.
+///
+/// Like [`on_cleanup`](crate::on_cleanup), but `f` returns a future, for
+/// teardown that has to await something such as flushing a buffer over the
+/// network. When the scope is cleaned up, the synchronous cleanups run first
+/// in reverse order of registration, then each `f` is called, again in
+/// reverse order, and its future is spawned under the root scope since the
+/// owning scope is going away. [`join`] waits for these tasks like for any
+/// other, and [`Scope::dispose_async`](crate::Scope::dispose_async) resolves
+/// only once they have finished.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let flushed = signal(false);
+/// let s = scope(move || {
+///     on_cleanup_async(move || async move {
+///         flushed.set(true);
+///     });
+/// });
+/// s.dispose();
+/// assert!(!flushed.get());
+/// join().await;
+/// assert!(flushed.get());
+/// # });
+/// ```
+pub fn on_cleanup_async<F, Fut>(f: F)
+where
+    F: FnOnce() -> Fut + 'static,
+    Fut: Future<Output = ()> + 'static,
+{
+    REACTIVE_SYSTEM.with(|ctx| {
+        ctx.on_cleanup_async(move || {
+            let task = with_root(|| spawn(f()));
+            EXECUTOR.with(|executor| executor.add_cleanup_task(task.state));
+        })
     });
 }
 
+/// A handle to a task started with [`spawn`].
+///
+/// Awaiting the handle waits for the task to finish, yielding `Err(Aborted)`
+/// if it was aborted or panicked instead. Like the task itself, this needs the executor
+/// to be driven by [`join`] or [`poll`].
+pub struct TaskHandle {
+    state: Rc<TaskState>,
+}
+
+impl TaskHandle {
+    /// Aborts the task. It will not be polled again and is dropped the next
+    /// time the executor runs.
+    pub fn abort(&self) {
+        self.state.abort.abort();
+    }
+
+    /// Whether the task has completed or, after being aborted, been dropped.
+    pub fn is_finished(&self) -> bool {
+        self.state.dropped.get()
+    }
+}
+
+impl Future for TaskHandle {
+    type Output = Result<(), Aborted>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(result) = self.state.result.get() {
+            return Poll::Ready(result);
+        }
+        if self.state.dropped.get() {
+            // Dropped without being polled to completion
+            return Poll::Ready(Err(Aborted));
+        }
+        self.state.register(cx);
+        Poll::Pending
+    }
+}
+
 /// Run all pending async tasks
+///
+/// # Panics
+///
+/// If a task panics, the panic is caught so the remaining tasks can run to
+/// completion, and then resumed from `join`. Only the first panic is
+/// resumed.
 pub async fn join() {
     EXECUTOR.with(|executor| executor.join()).await
 }
 
+/// Drive async tasks forever, as they are spawned and woken
+///
+/// # Panics
+///
+/// Resumes the panic of a task once the tasks that were ready alongside it
+/// have been polled.
 pub async fn poll() {
     EXECUTOR.with(|executor| executor.poll()).await
 }
 
-pub struct Resource<T> {
+/// Returns the number of spawned tasks that have neither finished nor been
+/// aborted, including those not polled yet.
+///
+/// Useful to find out why [`join`] does not return.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// spawn(async {});
+/// assert_eq!(pending_tasks(), 1);
+/// join().await;
+/// assert_eq!(pending_tasks(), 0);
+/// # });
+/// ```
+pub fn pending_tasks() -> usize {
+    EXECUTOR.with(|executor| executor.pending().len())
+}
+
+/// Like [`pending_tasks`], but only counts tasks spawned in `scope` or one
+/// of its descendants.
+pub fn pending_tasks_for(scope: Scope) -> usize {
+    let mut scopes = vec![scope.node()];
+    scopes.extend(REACTIVE_SYSTEM.with(|ctx| ctx.descendants(scope.node())));
+    EXECUTOR.with(|executor| {
+        executor
+            .scope_tasks(&scopes)
+            .iter()
+            .filter(|task| !task.abort.is_aborted())
+            .count()
+    })
+}
+
+/// Returns where each of the [`pending_tasks`] was spawned, in spawn order.
+#[cfg(any(debug_assertions, feature = "track-caller"))]
+pub fn pending_task_locations() -> Vec<&'static std::panic::Location<'static>> {
+    EXECUTOR.with(|executor| executor.pending().iter().map(|task| task.caller).collect())
+}
+
+/// Make a bounded amount of progress on async tasks without blocking
+///
+/// Picks up newly spawned tasks and polls the task set at most `max_polls`
+/// times, which suits game and UI loops that drive async work a little every
+/// frame. No async context is needed.
+///
+/// # Panics
+///
+/// Panics if called from inside a task while the executor is being driven by
+/// [`join`] or [`poll`]. A panic from a task is resumed after the pass.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// let done = signal(false);
+/// spawn(async move { done.set(true) });
+///
+/// let report = tick(10);
+/// assert_eq!(report, TickReport { completed: 1, remaining: 0 });
+/// assert!(done.get());
+/// ```
+pub fn tick(max_polls: usize) -> TickReport {
+    EXECUTOR.with(|executor| executor.tick(max_polls))
+}
+
+/// Run pending async tasks like [`join`], but give up after `timeout`
+///
+/// Returns [`JoinResult::TimedOut`] with the number of unfinished tasks if
+/// they did not all complete in time. The tasks are not aborted and keep
+/// running the next time the executor is driven. A panic from a task is
+/// resumed like in [`join`].
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// # use std::time::Duration;
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// spawn(std::future::pending());
+/// let result = join_timeout(Duration::from_millis(10)).await;
+/// assert_eq!(result, JoinResult::TimedOut { pending: 1 });
+/// # });
+/// ```
+pub async fn join_timeout(timeout: Duration) -> JoinResult {
+    let deadline = Instant::now() + timeout;
+    EXECUTOR
+        .with(|executor| executor.join_until(deadline))
+        .await
+}
+
+/// Drive async tasks like [`poll`] for `duration`, then return
+pub async fn poll_for(duration: Duration) {
+    let deadline = Instant::now() + duration;
+    EXECUTOR
+        .with(|executor| executor.poll_until(deadline))
+        .await;
+}
+
+/// Drive the executor from a background tokio task, so spawned tasks make
+/// progress without calling [`join`] or [`poll`].
+///
+/// The driver sleeps until a task is spawned or woken, it does not busy-loop.
+/// It must be called from within a [`LocalSet`](tokio::task::LocalSet) on the
+/// thread that owns the signals, and runs until the returned handle is
+/// aborted or the `LocalSet` is dropped.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// # tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap().block_on(async {
+/// let local = tokio::task::LocalSet::new();
+/// local
+///     .run_until(async {
+///         let driver = drive_on_tokio();
+///         let s = signal(0);
+///         spawn(async move { s.set(1) });
+///         tokio::task::yield_now().await;
+///         assert_eq!(s.get(), 1);
+///         driver.abort();
+///     })
+///     .await;
+/// # });
+/// ```
+#[cfg(feature = "tokio")]
+pub fn drive_on_tokio() -> tokio::task::JoinHandle<()> {
+    tokio::task::spawn_local(poll())
+}
+
+/// Runs CPU-bound `work` on tokio's blocking thread pool and resolves with
+/// its result on the awaiting thread.
+///
+/// Only `work` and its result cross threads; no reactive state is captured
+/// or needed on the worker, so read the signals `work` needs before calling
+/// this and write the result back after awaiting it. A panic in `work` is
+/// resumed in the awaiting task. Must be awaited inside a tokio runtime.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let input = signal(String::from("1,2,3"));
+/// let parsed = signal(Vec::new());
+/// spawn(async move {
+///     let text = input.get();
+///     let numbers = spawn_blocking(move || {
+///         text.split(',').map(|n| n.parse::<u32>().unwrap()).collect::<Vec<_>>()
+///     })
+///     .await;
+///     parsed.set(numbers);
+/// });
+/// join().await;
+/// assert_eq!(parsed.get(), vec![1, 2, 3]);
+/// # });
+/// ```
+#[cfg(feature = "tokio")]
+pub async fn spawn_blocking<F, T>(work: F) -> T
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    match tokio::task::spawn_blocking(work).await {
+        Ok(output) => output,
+        Err(err) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+        Err(err) => panic!("spawn_blocking: {err}"),
+    }
+}
+
+/// The state of an async computation started by [`resource`] or
+/// [`try_resource`].
+///
+/// All fields are signals, so effects and computeds can react to them.
+pub struct Resource<T, E = Infallible> {
+    /// The latest successfully fetched value, `None` until the first one
+    /// arrives.
     pub value: Signal<Option<T>>,
+    /// `true` while there is no value yet and a fetch is in flight.
     pub loading: Signal<bool>,
+    /// `true` while a fetch is in flight after a value has arrived, so a UI
+    /// can show a subtle indicator instead of a spinner.
+    pub refetching: Signal<bool>,
+    /// The error of the latest fetch if it failed, cleared by the next
+    /// successful one.
+    pub error: Signal<Option<E>>,
+    trigger: Signal<()>,
+}
+
+impl<T, E> Clone for Resource<T, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, E> Copy for Resource<T, E> {}
+
+impl<T: 'static, E: 'static> Resource<T, E> {
+    fn new(initial: Option<T>) -> Self {
+        let loading = initial.is_none();
+        Resource {
+            value: signal(initial),
+            loading: signal(loading),
+            refetching: signal(false),
+            error: signal(None),
+            trigger: signal(()),
+        }
+    }
+
+    /// Runs the fetcher again, even if none of its dependencies changed.
+    ///
+    /// An in-flight fetch is aborted in favor of the new one.
+    pub fn refetch(&self) {
+        self.trigger.set(());
+    }
+
+    /// Fetches again after an error. Same as [`refetch`](Resource::refetch).
+    pub fn retry(&self) {
+        self.refetch();
+    }
+
+    /// Derives a value from the resource, `None` until it has a value.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use samara_signals::*;
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let name = resource(|| async { String::from("samara") });
+    /// let len = name.map(|name| name.len());
+    /// assert_eq!(len.get(), None);
+    /// join().await;
+    /// assert_eq!(len.get(), Some(6));
+    /// # });
+    /// ```
+    #[track_caller]
+    pub fn map<U, F>(&self, f: F) -> Computed<Option<U>>
+    where
+        U: 'static,
+        F: Fn(&T) -> U + 'static,
+    {
+        let value = self.value;
+        Computed::new(move |_| value.read().as_ref().map(&f), caller())
+    }
+
+    /// Returns the most recent value: the latest one resolved, or the
+    /// initial value before any has.
+    ///
+    /// Unlike `loading`, this never goes back to `None`: neither a refetch
+    /// nor a failed fetch clears it.
+    #[track_caller]
+    pub fn latest(&self) -> Computed<Option<T>>
+    where
+        T: Clone,
+    {
+        let value = self.value;
+        Computed::new(move |_| value.get(), caller())
+    }
+
+    /// Chains a dependent resource that is created from this resource's
+    /// value.
+    ///
+    /// `f` is called with each new value and returns the resource to follow;
+    /// the previous one is disposed along with its in-flight fetch. The
+    /// returned resource is `loading` until both this resource and the one
+    /// from `f` have resolved, and `refetching` while a new value of this
+    /// resource is being followed through.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use samara_signals::*;
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let user = resource(|| async { 7 });
+    /// let orders = user.and_then(|&id| resource(move || async move { vec![id * 100] }));
+    /// assert!(orders.loading.get());
+    /// join().await;
+    /// assert_eq!(orders.value.get(), Some(vec![700]));
+    /// # });
+    /// ```
+    pub fn and_then<U, F>(&self, f: F) -> Resource<U>
+    where
+        U: Clone + 'static,
+        F: Fn(&T) -> Resource<U> + 'static,
+    {
+        let upstream = *self;
+        let downstream = Resource::new(None);
+        effect(move || {
+            downstream.trigger.track();
+            let Some(inner) = upstream.value.read().as_ref().map(&f) else {
+                downstream.begin_fetch();
+                return;
+            };
+            // Mirrors the inner resource; disposed with it when the upstream
+            // value changes
+            effect(move || {
+                if let Some(value) = inner.value.get() {
+                    downstream.value.set(Some(value));
+                }
+            });
+            effect(move || {
+                let in_flight = inner.loading.get() || inner.refetching.get();
+                if in_flight {
+                    downstream.begin_fetch();
+                } else {
+                    set_if_changed(downstream.loading, false);
+                    set_if_changed(downstream.refetching, false);
+                }
+            });
+        });
+        downstream
+    }
+
+    /// Refetches every `period` until the returned guard is dropped or the
+    /// current scope is disposed.
+    ///
+    /// A tick is skipped while a fetch is still in flight, so a slow fetch
+    /// is never superseded by the timer and results arrive in order.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use samara_signals::*;
+    /// # use std::time::Duration;
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let stats = resource(|| async { "fresh" });
+    /// let polling = stats.poll_every(Duration::from_secs(30));
+    /// join_timeout(Duration::from_millis(1)).await;
+    /// drop(polling);
+    /// # });
+    /// ```
+    #[track_caller]
+    pub fn poll_every(&self, period: Duration) -> PollingGuard {
+        let resource = *self;
+        let task = spawn(async move {
+            let mut deadline = Instant::now();
+            loop {
+                deadline += period;
+                sleep_until(deadline).await;
+                let in_flight = *resource.loading.peek() || *resource.refetching.peek();
+                if !in_flight {
+                    resource.refetch();
+                }
+            }
+        });
+        PollingGuard { task }
+    }
+
+    /// Marks a fetch as started: `loading` if there is no value yet,
+    /// `refetching` otherwise.
+    fn begin_fetch(&self) {
+        let has_value = self.value.peek().is_some();
+        set_if_changed(self.loading, !has_value);
+        set_if_changed(self.refetching, has_value);
+    }
+
+    /// Stores the outcome of the latest fetch and clears the in-flight flags.
+    fn finish(&self, output: Result<T, E>) {
+        start_batch();
+        match output {
+            Ok(output) => {
+                self.value.set(Some(output));
+                let failed = self.error.peek().is_some();
+                if failed {
+                    self.error.set(None);
+                }
+            }
+            Err(err) => self.error.set(Some(err)),
+        }
+        set_if_changed(self.loading, false);
+        set_if_changed(self.refetching, false);
+        end_batch();
+    }
+}
+
+/// Stops the periodic refetching started by [`Resource::poll_every`] when
+/// dropped.
+#[must_use = "polling stops when the guard is dropped"]
+pub struct PollingGuard {
+    task: TaskHandle,
+}
+
+impl Drop for PollingGuard {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
 }
 
+/// Creates a [`Resource`] from an async fetcher.
+///
+/// The fetcher is called inside an effect and the future it returns runs on
+/// the executor. Signals read while creating or polling the future are
+/// tracked, and changing them fetches again. The latest fetch always wins:
+/// one still in flight is aborted and its result discarded.
 pub fn resource<Func, Fut, Output>(func: Func) -> Resource<Output>
 where
     Func: Fn() -> Fut + 'static,
     Fut: Future<Output = Output> + 'static,
     Output: 'static,
 {
-    let func = signal(Rc::new(func));
-    let value = signal(None);
-    let loading = signal(true);
+    try_resource(move || {
+        let future = func();
+        async move { Ok(future.await) }
+    })
+}
+
+/// Creates a [`Resource`] from an async fetcher that can fail.
+///
+/// On `Ok` the value is stored and any previous error is cleared. On `Err`
+/// the previous value is kept and the error is stored in
+/// [`error`](Resource::error). Either way `loading` and `refetching` become
+/// `false`.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let ok = signal(false);
+/// let data = try_resource(move || async move {
+///     if ok.get() { Ok(1) } else { Err("not yet") }
+/// });
+/// join().await;
+/// assert_eq!(data.error.get(), Some("not yet"));
+///
+/// ok.set(true);
+/// join().await;
+/// assert_eq!((data.value.get(), data.error.get()), (Some(1), None));
+/// # });
+/// ```
+pub fn try_resource<Func, Fut, T, E>(func: Func) -> Resource<T, E>
+where
+    Func: Fn() -> Fut + 'static,
+    Fut: Future<Output = Result<T, E>> + 'static,
+    T: 'static,
+    E: 'static,
+{
+    fetch_resource(None, func)
+}
+
+/// Creates a [`Resource`] whose value starts as `Some(initial)`.
+///
+/// The first fetch starts right away, but as there already is a value it
+/// shows as `refetching` rather than `loading`. Like every resource, the
+/// previous value stays in place until a new one arrives.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let todos = resource_with_initial(Vec::new(), || async { vec!["write docs"] });
+/// assert_eq!(todos.value.get(), Some(Vec::new()));
+/// assert!(!todos.loading.get());
+/// join().await;
+/// assert_eq!(todos.value.get(), Some(vec!["write docs"]));
+/// # });
+/// ```
+pub fn resource_with_initial<Func, Fut, Output>(initial: Output, func: Func) -> Resource<Output>
+where
+    Func: Fn() -> Fut + 'static,
+    Fut: Future<Output = Output> + 'static,
+    Output: 'static,
+{
+    fetch_resource(Some(initial), move || {
+        let future = func();
+        async move { Ok(future.await) }
+    })
+}
+
+/// Creates a [`Resource`] that only refetches when `source` changes.
+///
+/// Only the signals read by `source` are tracked; its value is passed to
+/// `fetcher`, which runs untracked, as does the future it returns. A change
+/// of `source` that yields an equal value does not refetch. Unlike
+/// [`resource`], what triggers a refetch does not depend on which reads in
+/// the fetcher happen before or after an await.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let id = signal(1);
+/// let locale = signal("en");
+/// let user = resource_with_source(move || id.get(), move |id| async move {
+///     format!("{id} ({})", locale.get())
+/// });
+/// join().await;
+/// assert_eq!(user.value.get().as_deref(), Some("1 (en)"));
+///
+/// // Not part of the source, so no refetch
+/// locale.set("fr");
+/// join().await;
+/// assert_eq!(user.value.get().as_deref(), Some("1 (en)"));
+/// # });
+/// ```
+#[track_caller]
+pub fn resource_with_source<S, Source, Func, Fut, Output>(
+    source: Source,
+    fetcher: Func,
+) -> Resource<Output>
+where
+    S: PartialEq + Clone + 'static,
+    Source: Fn() -> S + 'static,
+    Func: Fn(S) -> Fut + 'static,
+    Fut: Future<Output = Output> + 'static,
+    Output: 'static,
+{
+    let source = Computed::memo(source, caller());
+    fetch_resource(None, move || {
+        let value = source.get();
+        let future = untracked(|| fetcher(value));
+        Untracked(Box::pin(async move { Ok(future.await) }))
+    })
+}
+
+/// Polls the inner future without tracking the signals it reads
+struct Untracked<F>(Pin<Box<F>>);
+
+impl<F: Future> Future for Untracked<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        untracked(|| self.0.as_mut().poll(cx))
+    }
+}
+
+fn fetch_resource<Func, Fut, T, E>(initial: Option<T>, func: Func) -> Resource<T, E>
+where
+    Func: Fn() -> Fut + 'static,
+    Fut: Future<Output = Result<T, E>> + 'static,
+    T: 'static,
+    E: 'static,
+{
+    let resource = Resource::new(initial);
+    let pending = PendingTracker::current();
+    let generation = Rc::new(Cell::new(0usize));
 
     effect(move || {
+        resource.trigger.track();
+        // Re-running the effect aborts the previous fetch through its scope
+        // cleanup; the generation check additionally guarantees that a
+        // superseded fetch can never overwrite a newer result
+        let current = generation.get().wrapping_add(1);
+        generation.set(current);
+        let generation = generation.clone();
+        let pending = pending.clone();
+        resource.begin_fetch();
+        pending.begin();
+        let future = func();
         spawn(async move {
-            let output = (func.get())().await;
+            let output = future.await;
+            if generation.get() == current {
+                start_batch();
+                pending.end();
+                resource.finish(output);
+                end_batch();
+            }
+        });
+    });
+
+    resource
+}
+
+/// A [`Resource`] that remembers resolved values per key, created by
+/// [`cached_resource`].
+///
+/// Derefs to the underlying [`Resource`], so its signals and `refetch` are
+/// available directly.
+pub struct CachedResource<K, T> {
+    resource: Resource<T>,
+    cache: Rc<RefCell<HashMap<K, T>>>,
+    current: Rc<RefCell<Option<K>>>,
+    revalidate: Rc<Cell<bool>>,
+}
+
+impl<K, T> Clone for CachedResource<K, T> {
+    fn clone(&self) -> Self {
+        CachedResource {
+            resource: self.resource,
+            cache: self.cache.clone(),
+            current: self.current.clone(),
+            revalidate: self.revalidate.clone(),
+        }
+    }
+}
+
+impl<K, T> Deref for CachedResource<K, T> {
+    type Target = Resource<T>;
+
+    fn deref(&self) -> &Resource<T> {
+        &self.resource
+    }
+}
+
+impl<K: Eq + Hash + 'static, T: 'static> CachedResource<K, T> {
+    /// Returns the underlying resource.
+    pub fn resource(&self) -> Resource<T> {
+        self.resource
+    }
+
+    /// Drops the cached value for `key`, so the next time the key is
+    /// selected it is fetched again. If `key` is the current key, it is
+    /// refetched right away.
+    pub fn invalidate_key(&self, key: &K) {
+        self.cache.borrow_mut().remove(key);
+        let is_current = self.current.borrow().as_ref() == Some(key);
+        if is_current {
+            self.resource.refetch();
+        }
+    }
+
+    /// Drops all cached values and refetches the current key.
+    pub fn invalidate_all(&self) {
+        self.cache.borrow_mut().clear();
+        self.resource.refetch();
+    }
+
+    /// Whether a cache hit also fetches the key again in the background.
+    ///
+    /// Off by default. When on, a hit still sets `value` from the cache
+    /// immediately, `refetching` is `true` while the fetch runs, and its
+    /// result replaces both the value and the cache entry.
+    pub fn set_revalidate(&self, revalidate: bool) {
+        self.revalidate.set(revalidate);
+    }
+
+    /// Returns the number of cached keys.
+    pub fn cached_len(&self) -> usize {
+        self.cache.borrow().len()
+    }
+}
+
+/// Creates a [`Resource`] that caches resolved values by key.
+///
+/// `key` is tracked like the fetcher of [`resource`]. When it changes to a
+/// key that was resolved before, `value` is set synchronously from the cache
+/// and `loading` stays `false`; otherwise `fetch` is called with the key and
+/// its result is stored in the cache. The cache lives as long as the
+/// resource's scope.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let id = signal(1);
+/// let user = cached_resource(move || id.get(), |id| async move { format!("user {id}") });
+/// join().await;
+/// id.set(2);
+/// join().await;
+///
+/// id.set(1);
+/// // Served from the cache without waiting for a fetch
+/// assert_eq!(user.value.get().as_deref(), Some("user 1"));
+/// assert!(!user.loading.get());
+/// # });
+/// ```
+pub fn cached_resource<K, T, KeyFn, Func, Fut>(key: KeyFn, fetch: Func) -> CachedResource<K, T>
+where
+    K: Eq + Hash + Clone + 'static,
+    T: Clone + 'static,
+    KeyFn: Fn() -> K + 'static,
+    Func: Fn(K) -> Fut + 'static,
+    Fut: Future<Output = T> + 'static,
+{
+    let cached = CachedResource {
+        resource: Resource::new(None),
+        cache: Rc::new(RefCell::new(HashMap::new())),
+        current: Rc::new(RefCell::new(None)),
+        revalidate: Rc::new(Cell::new(false)),
+    };
+    let pending = PendingTracker::current();
+    let generation = Rc::new(Cell::new(0usize));
+
+    let CachedResource {
+        resource,
+        cache,
+        current,
+        revalidate,
+    } = cached.clone();
+    effect(move || {
+        resource.trigger.track();
+        let key = key();
+        let current_generation = generation.get().wrapping_add(1);
+        generation.set(current_generation);
+        *current.borrow_mut() = Some(key.clone());
+
+        let hit = cache.borrow().get(&key).cloned();
+        if let Some(hit) = hit {
             start_batch();
-            value.set(Some(output));
-            loading.set(false);
+            resource.value.set(Some(hit));
+            set_if_changed(resource.loading, false);
+            set_if_changed(resource.refetching, revalidate.get());
             end_batch();
+            if !revalidate.get() {
+                // A fetch for the previous key was aborted with the re-run
+                pending.end();
+                return;
+            }
+        } else {
+            resource.begin_fetch();
+        }
+        pending.begin();
+
+        let future = fetch(key.clone());
+        let generation = generation.clone();
+        let cache = cache.clone();
+        let pending = pending.clone();
+        spawn(async move {
+            let output = future.await;
+            if generation.get() == current_generation {
+                cache.borrow_mut().insert(key, output.clone());
+                start_batch();
+                pending.end();
+                resource.finish(Ok(output));
+                end_batch();
+            }
         });
     });
 
-    Resource { value, loading }
+    cached
+}
+
+fn set_if_changed(signal: Signal<bool>, value: bool) {
+    let changed = *signal.peek() != value;
+    if changed {
+        signal.set(value);
+    }
 }
@@ -0,0 +1,221 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use crate::computed::{Computed, memo};
+use crate::effect::{effect, end_batch, start_batch};
+use crate::signal::{Signal, signal};
+
+/// A signal whose values a [`History`] records
+trait Tracked {
+    /// Read the value, tracking the signal
+    fn snapshot(&self) -> Box<dyn Any>;
+    fn restore(&self, value: &dyn Any);
+}
+
+impl<T: Clone + 'static> Tracked for Signal<T> {
+    fn snapshot(&self) -> Box<dyn Any> {
+        Box::new(self.get())
+    }
+
+    fn restore(&self, value: &dyn Any) {
+        self.set(value.downcast_ref::<T>().unwrap().clone());
+    }
+}
+
+/// The values of every tracked signal, in the order they were tracked
+type Snapshot = Vec<Box<dyn Any>>;
+
+struct Timeline {
+    states: Vec<Snapshot>,
+    /// The index of the state the signals are in
+    current: usize,
+}
+
+struct HistoryInner {
+    signals: Vec<Box<dyn Tracked>>,
+    timeline: RefCell<Timeline>,
+    depth: usize,
+    /// Set while undoing or redoing, so the next recording is skipped
+    restoring: Cell<bool>,
+    /// `(current, states.len())`, for the `can_undo` and `can_redo` memos
+    position: Signal<(usize, usize)>,
+    can_undo: Computed<bool>,
+    can_redo: Computed<bool>,
+}
+
+/// Builds a [`History`], created with [`history`].
+pub struct HistoryBuilder {
+    signals: Vec<Box<dyn Tracked>>,
+    depth: usize,
+}
+
+/// Starts building a [`History`] that keeps up to 100 undo steps.
+pub fn history() -> HistoryBuilder {
+    HistoryBuilder {
+        signals: Vec::new(),
+        depth: 100,
+    }
+}
+
+impl HistoryBuilder {
+    /// Records the values of `signal`.
+    pub fn track<T: Clone + 'static>(mut self, signal: Signal<T>) -> Self {
+        self.signals.push(Box::new(signal));
+        self
+    }
+
+    /// Keeps at most `depth` undo steps, dropping the oldest first.
+    pub fn depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Creates the history, owned by the current scope, starting from the
+    /// signals' current values.
+    #[track_caller]
+    pub fn build(self) -> History {
+        let position = signal((0, 1));
+        let inner = Rc::new(HistoryInner {
+            signals: self.signals,
+            timeline: RefCell::new(Timeline {
+                states: Vec::new(),
+                current: 0,
+            }),
+            depth: self.depth,
+            restoring: Cell::new(false),
+            position,
+            can_undo: memo(move || position.get().0 > 0),
+            can_redo: memo(move || {
+                let (current, len) = position.get();
+                current + 1 < len
+            }),
+        });
+
+        let recorded = inner.clone();
+        effect(move || {
+            let snapshot: Snapshot = (recorded.signals.iter())
+                .map(|signal| signal.snapshot())
+                .collect();
+            if recorded.restoring.replace(false) {
+                return;
+            }
+            recorded.record(snapshot);
+        });
+        History { inner }
+    }
+}
+
+/// Undo and redo over the values of a group of signals.
+///
+/// A state is recorded after every flush in which a tracked signal changed,
+/// so the writes of one batch are undone together. [`undo`](History::undo)
+/// and [`redo`](History::redo) write every tracked signal in a single batch,
+/// so readers of several of them only see coherent states. Making a change
+/// after undoing drops the states that could have been redone.
+///
+/// The recording effect is owned by the scope the history is built in.
+/// Clones share the same history.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// let text = signal(String::new());
+/// let bold = signal(false);
+/// let edits = history().track(text).track(bold).build();
+///
+/// text.set(String::from("hi"));
+/// bold.set(true);
+/// assert!(edits.undo());
+/// assert_eq!((text.get().as_str(), bold.get()), ("hi", false));
+/// assert!(edits.redo());
+/// assert!(bold.get());
+/// assert!(!edits.can_redo().get());
+/// ```
+pub struct History {
+    inner: Rc<HistoryInner>,
+}
+
+impl Clone for History {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl History {
+    /// Restores the previous state, returning whether there was one.
+    pub fn undo(&self) -> bool {
+        let current = self.inner.timeline.borrow().current;
+        current > 0 && self.inner.restore(current - 1)
+    }
+
+    /// Restores the state undone last, returning whether there was one.
+    pub fn redo(&self) -> bool {
+        let current = self.inner.timeline.borrow().current;
+        self.inner.restore(current + 1)
+    }
+
+    /// A memo of whether there is a state to undo to.
+    pub fn can_undo(&self) -> Computed<bool> {
+        self.inner.can_undo
+    }
+
+    /// A memo of whether there is a state to redo to.
+    pub fn can_redo(&self) -> Computed<bool> {
+        self.inner.can_redo
+    }
+
+    /// Forgets every state but the current one.
+    pub fn clear(&self) {
+        let mut timeline = self.inner.timeline.borrow_mut();
+        let current = timeline.current;
+        timeline.states.drain(current + 1..);
+        timeline.states.drain(..current);
+        timeline.current = 0;
+        drop(timeline);
+        self.inner.publish();
+    }
+}
+
+impl HistoryInner {
+    /// Add `snapshot` after the current state, dropping the redo states and
+    /// the oldest ones beyond the depth
+    fn record(&self, snapshot: Snapshot) {
+        let mut timeline = self.timeline.borrow_mut();
+        let next = (timeline.current + 1).min(timeline.states.len());
+        timeline.states.truncate(next);
+        timeline.states.push(snapshot);
+        let excess = timeline.states.len().saturating_sub(self.depth + 1);
+        timeline.states.drain(..excess);
+        timeline.current = timeline.states.len() - 1;
+        drop(timeline);
+        self.publish();
+    }
+
+    /// Write the state at `index` to the signals in one batch, returning
+    /// whether there is one
+    fn restore(&self, index: usize) -> bool {
+        let timeline = self.timeline.borrow();
+        let Some(state) = timeline.states.get(index) else {
+            return false;
+        };
+        // Writing no signals would leave nothing to skip
+        self.restoring.set(!self.signals.is_empty());
+        start_batch();
+        for (signal, value) in self.signals.iter().zip(state) {
+            signal.restore(value.as_ref());
+        }
+        drop(timeline);
+        self.timeline.borrow_mut().current = index;
+        self.publish();
+        end_batch();
+        true
+    }
+
+    fn publish(&self) {
+        let timeline = self.timeline.borrow();
+        self.position.set((timeline.current, timeline.states.len()));
+    }
+}
@@ -0,0 +1,102 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::rc::Rc;
+
+use crate::computed::{Computed, computed};
+use crate::effect::effect;
+use crate::scope::{Scope, scoped, untracked};
+use crate::signal::{Signal, signal};
+
+/// A key's child scope, the signal holding its item and what `map` made of it
+struct Row<T, U> {
+    scope: Scope,
+    item: Signal<T>,
+    mapped: U,
+}
+
+/// Maps a list to a list of outputs cached by key, the building block for
+/// rendering lists.
+///
+/// An effect re-reads `source` whenever it changes and diffs the keys
+/// against the previous run:
+///
+/// - a new key gets a child scope, a signal holding its item, and the output
+///   of `map` called with that signal inside the scope;
+/// - a retained key keeps its scope and output, and its signal is set to
+///   the new item, so only what `map` derived from it updates;
+/// - a removed key's scope is disposed, running its cleanups.
+///
+/// The child scopes are owned by the scope `map_keyed` is called in, and
+/// `map` runs untracked. Keys must be unique within a list.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// let names = signal(vec!["ada", "grace"]);
+/// let labels = map_keyed(
+///     move || names.get(),
+///     |name| *name,
+///     |name| memo(move || name.get().to_uppercase()),
+/// );
+/// let texts = move || labels.get().iter().map(|label| label.get()).collect::<Vec<_>>();
+/// assert_eq!(texts(), ["ADA", "GRACE"]);
+///
+/// let (nodes, _) = count();
+/// names.set(vec!["grace", "ada"]); // Reordering reuses both rows
+/// assert_eq!(texts(), ["GRACE", "ADA"]);
+/// assert_eq!(count().0, nodes);
+/// ```
+#[track_caller]
+pub fn map_keyed<T, K, U>(
+    source: impl Fn() -> Vec<T> + 'static,
+    key: impl Fn(&T) -> K + 'static,
+    map: impl Fn(Signal<T>) -> U + 'static,
+) -> Computed<Vec<U>>
+where
+    T: 'static,
+    K: Eq + Hash + 'static,
+    U: Clone + 'static,
+{
+    let create_row = scoped(move |item: T| {
+        let item = signal(item);
+        (item, map(item))
+    });
+    let rows: Rc<RefCell<HashMap<K, Row<T, U>>>> = Rc::default();
+    let output = signal(Vec::new());
+
+    effect(move || {
+        let items = source();
+        let mut previous = std::mem::take(&mut *rows.borrow_mut());
+        let mut next = HashMap::with_capacity(items.len());
+        let mut mapped = Vec::with_capacity(items.len());
+        for item in items {
+            let key = key(&item);
+            let row = match previous.remove(&key) {
+                Some(row) => {
+                    row.item.set(item);
+                    row
+                }
+                None => {
+                    let ((item, mapped), scope) = untracked(|| create_row(item));
+                    Row {
+                        scope,
+                        item,
+                        mapped,
+                    }
+                }
+            };
+            mapped.push(row.mapped.clone());
+            if next.insert(key, row).is_some() {
+                panic!("map_keyed: the same key appears twice in the list");
+            }
+        }
+        for row in previous.into_values() {
+            row.scope.dispose();
+        }
+        *rows.borrow_mut() = next;
+        output.set(mapped);
+    });
+
+    computed(move |_| output.get())
+}
@@ -0,0 +1,227 @@
+use std::fmt;
+
+use crate::runtime::REACTIVE_SYSTEM;
+#[cfg(any(debug_assertions, feature = "track-caller"))]
+use crate::snapshot::NodeKind;
+use crate::snapshot::{LinkInfo, NodeInfo};
+
+/// What [`leak_report`] found about nodes that outlive their owners.
+///
+/// Every node belongs to the ownership tree below the root scope until it
+/// is disposed. A node the tree no longer reaches can never be disposed
+/// short of a global [`cleanup`](crate::cleanup), and neither can the links
+/// touching it. Nodes owned directly by the root scope are listed too, since
+/// they are the usual suspects when [`count`](crate::count) does not return
+/// to its baseline.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LeakReport {
+    /// The node and link counts when the report was taken.
+    pub counts: (usize, usize),
+    /// Nodes not reachable from the root scope.
+    pub unreachable: Vec<NodeInfo>,
+    /// Links whose dependency or subscriber is unreachable.
+    pub links: Vec<LinkInfo>,
+    /// Nodes owned directly by the root scope, newest first.
+    pub root_owned: Vec<NodeInfo>,
+}
+
+impl LeakReport {
+    /// Whether every node is reachable from the root scope.
+    pub fn is_clean(&self) -> bool {
+        self.unreachable.is_empty() && self.links.is_empty()
+    }
+}
+
+impl fmt::Display for LeakReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (nodes, links) = self.counts;
+        write!(f, "{nodes} nodes and {links} links")?;
+        if !self.unreachable.is_empty() {
+            write!(f, "\nunreachable from the root scope:")?;
+            for node in &self.unreachable {
+                write_node(f, node)?;
+            }
+        }
+        if !self.links.is_empty() {
+            write!(f, "\nlinks touching unreachable nodes:")?;
+            for link in &self.links {
+                write!(f, "\n  {:?} -> {:?}", link.dep, link.sub)?;
+            }
+        }
+        if !self.root_owned.is_empty() {
+            write!(f, "\nowned by the root scope:")?;
+            for node in &self.root_owned {
+                write_node(f, node)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_node(f: &mut fmt::Formatter<'_>, node: &NodeInfo) -> fmt::Result {
+    write!(f, "\n  {:?}", node.kind)?;
+    if let Some(name) = &node.name {
+        write!(f, " {name:?}")?;
+    }
+    if let Some(location) = &node.location {
+        write!(f, " created at {location}")?;
+    }
+    Ok(())
+}
+
+/// Reports nodes of the current thread's reactive system that are no longer
+/// reachable from the root scope, see [`LeakReport`].
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// let s = scope(|| {
+///     let a = signal(1);
+///     effect(move || {
+///         a.get();
+///     });
+/// });
+/// s.dispose();
+/// assert!(leak_report().is_clean());
+/// ```
+pub fn leak_report() -> LeakReport {
+    REACTIVE_SYSTEM.with(|ctx| ctx.leak_report())
+}
+
+/// Panics with a [`LeakReport`] unless [`count`](crate::count) equals
+/// `baseline`, a count taken before the code under test ran.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// let baseline = count();
+/// let s = scope(|| {
+///     let a = signal(1);
+///     let _doubled = memo(move || a.get() * 2);
+/// });
+/// s.dispose();
+/// assert_no_leaks(baseline);
+/// ```
+#[track_caller]
+pub fn assert_no_leaks(baseline: (usize, usize)) {
+    let report = leak_report();
+    if report.counts != baseline {
+        panic!(
+            "expected {} nodes and {} links, found {report}",
+            baseline.0, baseline.1
+        );
+    }
+}
+
+/// Counts the live nodes of the current thread's reactive system by where
+/// they were created and their kind, most common first.
+///
+/// The root scope is not counted. Take two counts around the code under test
+/// and compare them with [`diff_nodes_by_location`] to see where new nodes
+/// come from.
+///
+/// # Example
+// Doctests always see `debug_assertions`, even against a release build of
+// the crate, so only the feature can tell whether this function exists
+#[cfg_attr(feature = "track-caller", doc = "```rust")]
+#[cfg_attr(not(feature = "track-caller"), doc = "```ignore")]
+/// # use samara_signals::*;
+/// let a = signal(1);
+/// let memos: Vec<_> = (0..3).map(|i| memo(move || a.get() + i)).collect();
+///
+/// let (location, kind, count) = nodes_by_location()[0];
+/// assert_eq!((kind, count), (NodeKind::Computed, 3));
+/// assert_eq!(location.line(), line!() - 4);
+/// ```
+#[cfg(any(debug_assertions, feature = "track-caller"))]
+pub fn nodes_by_location() -> Vec<(&'static std::panic::Location<'static>, NodeKind, usize)> {
+    REACTIVE_SYSTEM.with(|ctx| ctx.nodes_by_location())
+}
+
+/// How the number of live nodes created at one location changed between two
+/// calls to [`nodes_by_location`].
+#[cfg(any(debug_assertions, feature = "track-caller"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NodeCountChange {
+    pub location: &'static std::panic::Location<'static>,
+    pub kind: NodeKind,
+    pub before: usize,
+    pub after: usize,
+}
+
+#[cfg(any(debug_assertions, feature = "track-caller"))]
+impl NodeCountChange {
+    /// The number of nodes gained, negative if nodes were disposed.
+    pub fn delta(&self) -> isize {
+        self.after as isize - self.before as isize
+    }
+}
+
+#[cfg(any(debug_assertions, feature = "track-caller"))]
+impl fmt::Display for NodeCountChange {
+    /// Writes the change as `27 new Computed nodes from src/table.rs:88:20`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let delta = self.delta();
+        let change = if delta < 0 { "fewer" } else { "new" };
+        write!(
+            f,
+            "{} {change} {:?} nodes from {}",
+            delta.unsigned_abs(),
+            self.kind,
+            self.location
+        )
+    }
+}
+
+/// Compares two results of [`nodes_by_location`], returning the locations
+/// whose count changed, largest change first.
+///
+/// # Example
+// Gated like the example of `nodes_by_location`
+#[cfg_attr(feature = "track-caller", doc = "```rust")]
+#[cfg_attr(not(feature = "track-caller"), doc = "```ignore")]
+/// # use samara_signals::*;
+/// let a = signal(1);
+/// let before = nodes_by_location();
+/// let s = scope(move || {
+///     for i in 0..27 {
+///         memo(move || a.get() + i);
+///     }
+/// });
+///
+/// let changes = diff_nodes_by_location(&before, &nodes_by_location());
+/// assert!(changes[0].to_string().starts_with("27 new Computed nodes from "));
+/// s.dispose();
+/// ```
+#[cfg(any(debug_assertions, feature = "track-caller"))]
+pub fn diff_nodes_by_location(
+    before: &[(&'static std::panic::Location<'static>, NodeKind, usize)],
+    after: &[(&'static std::panic::Location<'static>, NodeKind, usize)],
+) -> Vec<NodeCountChange> {
+    let mut changes: Vec<NodeCountChange> = before
+        .iter()
+        .map(|&(location, kind, count)| NodeCountChange {
+            location,
+            kind,
+            before: count,
+            after: 0,
+        })
+        .collect();
+    for &(location, kind, count) in after {
+        match changes
+            .iter_mut()
+            .find(|change| change.location == location && change.kind == kind)
+        {
+            Some(change) => change.after = count,
+            None => changes.push(NodeCountChange {
+                location,
+                kind,
+                before: 0,
+                after: count,
+            }),
+        }
+    }
+    changes.retain(|change| change.before != change.after);
+    changes.sort_by_key(|change| std::cmp::Reverse(change.delta().unsigned_abs()));
+    changes
+}
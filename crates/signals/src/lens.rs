@@ -0,0 +1,130 @@
+use std::rc::Rc;
+
+use crate::computed::{Computed, memo};
+use crate::signal::Signal;
+
+type Getter<T, U> = Rc<dyn Fn(&T) -> &U>;
+type GetterMut<T, U> = Rc<dyn Fn(&mut T) -> &mut U>;
+
+/// A view of one part of the value of a signal, created with
+/// [`Signal::lens`].
+///
+/// Reading through [`read`](Lens::read) only notifies when the focused part
+/// changes, while writing through [`set`](Lens::set) or
+/// [`update`](Lens::update) modifies the root signal in place, notifying
+/// everything that reads it. Lenses focus further with
+/// [`compose`](Lens::compose), or [`lens`](Lens::lens) to read like the
+/// signal's.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// #[derive(Clone)]
+/// struct User {
+///     name: String,
+///     age: u32,
+/// }
+/// #[derive(Clone)]
+/// struct State {
+///     user: User,
+/// }
+///
+/// let state = signal(State {
+///     user: User { name: String::from("ada"), age: 36 },
+/// });
+/// let user = state.lens(|s| &s.user, |s| &mut s.user);
+/// let name = user.lens(|u| &u.name, |u| &mut u.name);
+/// let age = user.lens(|u| &u.age, |u| &mut u.age);
+///
+/// let shown = name.read();
+/// age.set(37);
+/// name.update(|name| name.make_ascii_uppercase());
+/// assert_eq!(shown.get(), "ADA");
+/// assert_eq!(state.with(|s| s.user.age), 37);
+/// ```
+pub struct Lens<T, U> {
+    root: Signal<T>,
+    get: Getter<T, U>,
+    get_mut: GetterMut<T, U>,
+}
+
+impl<T, U> Clone for Lens<T, U> {
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root,
+            get: self.get.clone(),
+            get_mut: self.get_mut.clone(),
+        }
+    }
+}
+
+impl<T: 'static> Signal<T> {
+    /// Focuses on the part of the value that `get` and `get_mut` borrow.
+    pub fn lens<U: 'static>(
+        &self,
+        get: impl Fn(&T) -> &U + 'static,
+        get_mut: impl Fn(&mut T) -> &mut U + 'static,
+    ) -> Lens<T, U> {
+        Lens {
+            root: *self,
+            get: Rc::new(get),
+            get_mut: Rc::new(get_mut),
+        }
+    }
+}
+
+impl<T: 'static, U: 'static> Lens<T, U> {
+    /// The signal this lens looks into.
+    pub fn root(&self) -> Signal<T> {
+        self.root
+    }
+
+    /// Focuses further on the part of this lens's value that `get` and
+    /// `get_mut` borrow.
+    pub fn compose<V: 'static>(
+        &self,
+        get: impl Fn(&U) -> &V + 'static,
+        get_mut: impl Fn(&mut U) -> &mut V + 'static,
+    ) -> Lens<T, V> {
+        let (outer, outer_mut) = (self.get.clone(), self.get_mut.clone());
+        Lens {
+            root: self.root,
+            get: Rc::new(move |root| get(outer(root))),
+            get_mut: Rc::new(move |root| get_mut(outer_mut(root))),
+        }
+    }
+
+    /// Same as [`compose`](Lens::compose), named like [`Signal::lens`] so
+    /// that paths read alike from the signal down.
+    pub fn lens<V: 'static>(
+        &self,
+        get: impl Fn(&U) -> &V + 'static,
+        get_mut: impl Fn(&mut U) -> &mut V + 'static,
+    ) -> Lens<T, V> {
+        self.compose(get, get_mut)
+    }
+}
+
+impl<T: Clone + 'static, U: 'static> Lens<T, U> {
+    /// Replaces the focused part, notifying the root signal's readers.
+    pub fn set(&self, value: U) {
+        self.update(|focused| *focused = value);
+    }
+
+    /// Modifies the focused part in place, notifying the root signal's
+    /// readers.
+    pub fn update(&self, f: impl FnOnce(&mut U)) {
+        let get_mut = self.get_mut.clone();
+        self.root.update_recorded(|root| f(get_mut(root)));
+    }
+}
+
+impl<T: 'static, U: Clone + PartialEq + 'static> Lens<T, U> {
+    /// Creates a memo of the focused part, which re-reads the root signal
+    /// whenever it changes but only notifies when the part itself changes.
+    #[track_caller]
+    pub fn read(&self) -> Computed<U> {
+        let (root, get) = (self.root, self.get.clone());
+        memo(move || root.with(|value| get(value).clone()))
+    }
+}
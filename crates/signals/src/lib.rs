@@ -1,19 +1,101 @@
+mod cancel;
 mod computed;
 mod context;
+mod delta;
+#[cfg(feature = "devtools")]
+mod devtools;
 mod effect;
+mod explain;
 mod flags;
 mod future;
+mod history;
+mod keyed;
+mod leak;
+mod lens;
+#[cfg(feature = "compat-futures-signals")]
+mod mutable;
+mod observer;
+mod persist;
+mod receiver;
 mod runtime;
 mod scope;
 mod signal;
+mod signal_btree_map;
+mod signal_map;
+mod signal_set;
+mod signal_vec;
+mod sink;
+mod snapshot;
+mod suspense;
 mod system;
+mod time;
+mod transaction;
 mod types;
+mod validate;
+#[cfg(feature = "tokio")]
+mod watch;
 
+pub use cancel::{CancellationToken, Cancelled, cancellation_token};
 pub use computed::{Computed, computed, memo};
-pub use context::{has_context, provide_context, use_context};
-pub use effect::{Effect, count, effect, end_batch, on_cleanup, serialize, start_batch, trigger};
-pub use future::{Resource, join, poll, resource, spawn};
-pub use scope::{Scope, cleanup, scope, scoped};
+pub use context::{
+    ContextSnapshot, expect_context, has_context, provide_context, provide_context_dyn,
+    provide_context_keyed, provide_reactive_context, remove_context, use_context, use_context_dyn,
+    use_context_keyed, use_context_or_provide, use_reactive_context,
+};
+pub use delta::{MapDelta, SetDelta, Subscription};
+#[cfg(feature = "devtools")]
+pub use devtools::{DevtoolsEvent, DevtoolsEvents, devtools_events};
+pub use effect::{
+    Effect, HookHandle, OutsideScopeError, auto_batch, batch_depth, capacity_stats, compact_report,
+    count, effect, end_batch, flush_sync, is_batching, last_flush_stats, links_capacity,
+    on_cleanup, on_flush, on_flush_end, on_flush_start, pending_effects, queue_capacity, reserve,
+    serialize, set_auto_batch, shrink_to_fit, start_batch, stats, trigger, try_on_cleanup,
+};
+pub use explain::{PlannedMark, PlannedNode, PropagationPlan, explain_set};
+pub use future::{
+    CachedResource, PollingGuard, Resource, TaskHandle, cached_resource, effect_async, join,
+    join_timeout, on_cleanup_async, pending_tasks, pending_tasks_for, poll, poll_for, resource,
+    resource_with_initial, resource_with_source, spawn, tick, try_resource,
+};
+pub use futures_util::future::Aborted;
+pub use history::{History, HistoryBuilder, history};
+pub use keyed::map_keyed;
+pub use leak::{LeakReport, assert_no_leaks, leak_report};
+pub use lens::Lens;
+#[cfg(feature = "compat-futures-signals")]
+pub use mutable::{from_mutable, to_mutable};
+pub use observer::{RuntimeObserver, remove_observer, set_observer};
+pub use persist::{MemoryBackend, PersistenceBackend, persisted_signal, provide_persistence};
+pub use receiver::{signal_from_receiver, signal_vec_from_receiver};
+pub use samara_signals_macros::Store;
+
+#[cfg(any(debug_assertions, feature = "track-caller"))]
+pub use future::pending_task_locations;
+#[cfg(feature = "tokio")]
+pub use future::{drive_on_tokio, spawn_blocking};
+#[cfg(any(debug_assertions, feature = "track-caller"))]
+pub use leak::{NodeCountChange, diff_nodes_by_location, nodes_by_location};
+#[cfg(feature = "persist-json-file")]
+pub use persist::JsonFileBackend;
+pub use scope::{
+    OwnedScope, Owner, Scope, cleanup, reparent, root_scope, scope, scope_named, scope_owned,
+    scope_with, scoped, scoped_reusable, unowned, with_root,
+};
 pub use signal::{Signal, SignalReadGuard, SignalWriteGuard, signal};
+pub use signal_btree_map::{SignalBTreeMap, signal_btree_map};
+pub use signal_map::{SignalMap, signal_map};
+pub use signal_set::{SignalSet, signal_set};
+pub use signal_vec::{SignalVec, VecDelta, signal_vec};
+pub use sink::Disposed;
+pub use snapshot::{GraphSnapshot, LinkInfo, NodeInfo, NodeKind, restore_topology, snapshot};
+pub use suspense::{pending_count, provide_suspense, use_suspense};
+pub use time::{debounce, interval_signal, sleep, sleep_until, throttle, timeout_signal};
+pub use transaction::transaction;
+#[cfg(feature = "tokio")]
+pub use watch::{signal_from_watch, signal_to_watch};
 
+pub use runtime::executor::{JoinResult, TickReport};
+pub use runtime::timer::Sleep;
+pub use system::{CapacityStats, CompactReport, CountStats, FlushStats, MapCapacity, SlotUsage};
 pub use types::{LinkKey, NodeKey};
+pub use validate::{InvariantViolation, validate};
@@ -0,0 +1,93 @@
+use futures_signals::signal::{Mutable, SignalExt};
+use futures_util::StreamExt;
+
+use crate::effect::effect;
+use crate::future::spawn;
+use crate::runtime::REACTIVE_SYSTEM;
+use crate::signal::{Signal, signal};
+
+/// Creates a signal kept in sync with a futures-signals `Mutable`, both
+/// ways.
+///
+/// The signal starts with the mutable's current value. See [`to_mutable`]
+/// for how the two are kept in sync; the signal is owned by the current
+/// scope like the forwarders.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// use futures_signals::signal::Mutable;
+///
+/// let mutable = Mutable::new(1);
+/// let value = from_mutable(mutable.clone());
+/// mutable.set(2);
+/// tick(10);
+/// assert_eq!(value.get(), 2);
+///
+/// value.set(3);
+/// assert_eq!(mutable.get(), 3);
+/// ```
+#[track_caller]
+pub fn from_mutable<T: Clone + PartialEq + 'static>(mutable: Mutable<T>) -> Signal<T> {
+    let value = signal(mutable.get_cloned());
+    bind(value, mutable);
+    value
+}
+
+/// Creates a futures-signals `Mutable` kept in sync with `signal`, both
+/// ways.
+///
+/// The mutable starts with the signal's current value. An effect sets it
+/// after every flush that changed the signal, and a task spawned on the
+/// executor writes the mutable's changes back into the signal. Each side
+/// skips values equal to the one it already holds, so a value is never
+/// echoed back to where it came from. Like futures-signals' own streams,
+/// the task only sees the latest of a burst of changes.
+///
+/// The forwarders are owned by the current scope: disposing it disposes the
+/// effect and aborts the task, leaving the mutable with its last value.
+/// The task also stops once `signal` is disposed. Until then it keeps
+/// [`join`](crate::join) from returning, so drive it with
+/// [`tick`](crate::tick) instead.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// let value = signal(1);
+/// let mutable = to_mutable(value);
+/// value.set(2);
+/// assert_eq!(mutable.get(), 2);
+///
+/// mutable.set(3);
+/// tick(10);
+/// assert_eq!(value.get(), 3);
+/// ```
+#[track_caller]
+pub fn to_mutable<T: Clone + PartialEq + 'static>(signal: Signal<T>) -> Mutable<T> {
+    let mutable = Mutable::new(signal.get_untracked());
+    bind(signal, mutable.clone());
+    mutable
+}
+
+/// Forward changes between `signal` and `mutable`, which hold equal values
+#[track_caller]
+fn bind<T: Clone + PartialEq + 'static>(signal: Signal<T>, mutable: Mutable<T>) {
+    let mut changes = mutable.signal_cloned().to_stream();
+    effect(move || {
+        let value = signal.get();
+        if *mutable.lock_ref() != value {
+            mutable.set(value);
+        }
+    });
+    spawn(async move {
+        // The stream starts with the current value, which the signal holds
+        while let Some(value) = changes.next().await {
+            if !REACTIVE_SYSTEM.with(|ctx| ctx.contains_node(signal.node())) {
+                break;
+            }
+            if signal.get_untracked() != value {
+                signal.set(value);
+            }
+        }
+    });
+}
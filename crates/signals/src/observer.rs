@@ -0,0 +1,84 @@
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::runtime::REACTIVE_SYSTEM;
+use crate::snapshot::NodeKind;
+use crate::types::NodeKey;
+
+/// Receives lifecycle events of the current thread's reactive graph as they
+/// happen, for devtools that cannot afford to poll [`snapshot`](crate::snapshot).
+///
+/// Every method does nothing by default. Callbacks run in the middle of the
+/// operation that triggered them, so they must not use the reactive system:
+/// reading or writing signals, creating nodes or disposing them from a
+/// callback is a bug, which debug builds catch with a panic. Record the
+/// event and act on it later instead.
+pub trait RuntimeObserver {
+    /// A node was added to the graph. `location` is where it was created,
+    /// `None` in release builds, which do not record locations.
+    fn node_created(
+        &self,
+        node: NodeKey,
+        kind: NodeKind,
+        location: Option<&'static std::panic::Location<'static>>,
+    ) {
+        let _ = (node, kind, location);
+    }
+
+    /// A node was removed from the graph.
+    fn node_disposed(&self, node: NodeKey) {
+        let _ = node;
+    }
+
+    /// `sub` started depending on `dep`.
+    fn link_created(&self, dep: NodeKey, sub: NodeKey) {
+        let _ = (dep, sub);
+    }
+
+    /// `sub` stopped depending on `dep`.
+    fn link_removed(&self, dep: NodeKey, sub: NodeKey) {
+        let _ = (dep, sub);
+    }
+
+    /// An effect ran, including its first run, taking `duration`.
+    fn effect_ran(&self, effect: NodeKey, duration: Duration) {
+        let _ = (effect, duration);
+    }
+
+    /// A signal was written, before its subscribers are notified.
+    fn signal_set(&self, signal: NodeKey) {
+        let _ = signal;
+    }
+}
+
+/// Installs `observer` for the current thread's reactive system, replacing
+/// any previous one.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// # use std::{cell::Cell, rc::Rc};
+/// #[derive(Default)]
+/// struct Counter(Cell<usize>);
+///
+/// impl RuntimeObserver for Counter {
+///     fn node_created(&self, _: NodeKey, _: NodeKind, _: Option<&'static std::panic::Location<'static>>) {
+///         self.0.set(self.0.get() + 1);
+///     }
+/// }
+///
+/// let counter = Rc::new(Counter::default());
+/// set_observer(counter.clone());
+/// let _a = signal(1);
+/// let _b = signal(2);
+/// remove_observer();
+/// assert_eq!(counter.0.get(), 2);
+/// ```
+pub fn set_observer(observer: Rc<dyn RuntimeObserver>) {
+    REACTIVE_SYSTEM.with(|ctx| ctx.set_observer(Some(observer)));
+}
+
+/// Removes the observer installed with [`set_observer`], if any.
+pub fn remove_observer() {
+    REACTIVE_SYSTEM.with(|ctx| ctx.set_observer(None));
+}
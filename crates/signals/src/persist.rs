@@ -0,0 +1,178 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Duration;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::context::{expect_context, provide_context_dyn};
+use crate::effect::{effect, on_cleanup};
+use crate::future::spawn;
+use crate::signal::{Signal, signal};
+use crate::time::sleep;
+
+/// How long a persisted signal must be left unchanged before its value is
+/// written back
+const WRITE_BACK_DELAY: Duration = Duration::from_millis(50);
+
+/// Where [`persisted_signal`]s load their values from and write them back
+/// to, provided with [`provide_persistence`].
+///
+/// Values are passed as serialized JSON.
+pub trait PersistenceBackend {
+    /// The bytes last stored under `key`, if any.
+    fn load(&self, key: &str) -> Option<Vec<u8>>;
+
+    /// Stores `bytes` under `key`, replacing what was there.
+    fn store(&self, key: &str, bytes: &[u8]);
+}
+
+/// Provides `backend` to the [`persisted_signal`]s created in the current
+/// scope and its descendants.
+pub fn provide_persistence(backend: impl PersistenceBackend + 'static) {
+    provide_context_dyn::<dyn PersistenceBackend>(Rc::new(backend));
+}
+
+/// Creates a signal whose value is saved under `key` in the provided
+/// [`PersistenceBackend`].
+///
+/// The signal starts with the value stored under `key`, or `default` if
+/// there is none or it no longer deserializes. After every change an
+/// effect writes the value back once the signal has been left alone for a
+/// moment, so a burst of changes is stored once; a pending write is done
+/// right away when the current scope is disposed.
+///
+/// # Panics
+///
+/// Panics if no backend has been provided with [`provide_persistence`].
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let backend = MemoryBackend::new();
+/// provide_persistence(backend.clone());
+///
+/// let theme = persisted_signal("theme", String::from("light"));
+/// theme.set(String::from("dark"));
+/// join().await;
+/// assert_eq!(backend.load("theme").unwrap(), br#""dark""#);
+/// # });
+/// ```
+#[track_caller]
+pub fn persisted_signal<T>(key: &str, default: T) -> Signal<T>
+where
+    T: Serialize + DeserializeOwned + 'static,
+{
+    let backend = expect_context::<Rc<dyn PersistenceBackend>>();
+    let initial = backend
+        .load(key)
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok());
+    let value = signal(initial.unwrap_or(default));
+
+    let key: Rc<str> = key.into();
+    // The serialized value waiting to be written back
+    let pending: Rc<RefCell<Option<Vec<u8>>>> = Rc::default();
+    let first = Cell::new(true);
+    effect({
+        let (backend, key, pending) = (backend.clone(), key.clone(), pending.clone());
+        move || {
+            let bytes = value.with(serde_json::to_vec);
+            if first.replace(false) {
+                return;
+            }
+            let Ok(bytes) = bytes else {
+                return;
+            };
+            pending.replace(Some(bytes));
+            // Re-running this effect aborts the previous write
+            let (backend, key, pending) = (backend.clone(), key.clone(), pending.clone());
+            spawn(async move {
+                sleep(WRITE_BACK_DELAY).await;
+                if let Some(bytes) = pending.take() {
+                    backend.store(&key, &bytes);
+                }
+            });
+        }
+    });
+    on_cleanup(move || {
+        if let Some(bytes) = pending.take() {
+            backend.store(&key, &bytes);
+        }
+    });
+    value
+}
+
+/// A [`PersistenceBackend`] keeping values in memory, for tests. Clones
+/// share the same values.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryBackend {
+    entries: Rc<RefCell<HashMap<String, Vec<u8>>>>,
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PersistenceBackend for MemoryBackend {
+    fn load(&self, key: &str) -> Option<Vec<u8>> {
+        self.entries.borrow().get(key).cloned()
+    }
+
+    fn store(&self, key: &str, bytes: &[u8]) {
+        self.entries
+            .borrow_mut()
+            .insert(key.to_owned(), bytes.to_vec());
+    }
+}
+
+/// A [`PersistenceBackend`] keeping every value in a single JSON object
+/// file, keyed by the signals' keys.
+///
+/// The file is read once on creation and rewritten whole on every store.
+/// Stores that fail to write the file are dropped, and tried again with the
+/// next change.
+#[cfg(feature = "persist-json-file")]
+pub struct JsonFileBackend {
+    path: std::path::PathBuf,
+    entries: RefCell<serde_json::Map<String, serde_json::Value>>,
+}
+
+#[cfg(feature = "persist-json-file")]
+impl JsonFileBackend {
+    /// Opens the file at `path`, starting empty if it is missing or not a
+    /// JSON object.
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        let path = path.into();
+        let entries = std::fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            entries: RefCell::new(entries),
+        }
+    }
+}
+
+#[cfg(feature = "persist-json-file")]
+impl PersistenceBackend for JsonFileBackend {
+    fn load(&self, key: &str) -> Option<Vec<u8>> {
+        let entries = self.entries.borrow();
+        serde_json::to_vec(entries.get(key)?).ok()
+    }
+
+    fn store(&self, key: &str, bytes: &[u8]) {
+        let Ok(value) = serde_json::from_slice(bytes) else {
+            return;
+        };
+        let mut entries = self.entries.borrow_mut();
+        entries.insert(key.to_owned(), value);
+        if let Ok(file) = serde_json::to_vec_pretty(&*entries) {
+            let _ = std::fs::write(&self.path, file);
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::mpsc::Receiver;
+
+use crate::effect::on_cleanup;
+use crate::future::EXECUTOR;
+use crate::signal::{Signal, signal};
+use crate::signal_vec::SignalVec;
+
+/// Registers `drain` with the executor until the current scope is disposed
+fn drain_with_executor(drain: impl FnMut() + 'static) {
+    let drain = EXECUTOR.with(|executor| executor.add_drain(drain));
+    on_cleanup(move || {
+        let _ = EXECUTOR.try_with(|executor| executor.remove_drain(&drain));
+    });
+}
+
+/// Creates a signal holding the latest message received on `rx`, or `None`
+/// until there is one.
+///
+/// The executor drains `rx` without blocking at the start of every
+/// [`join`](crate::join), [`tick`](crate::tick) and pass of
+/// [`poll`](crate::poll), and writes only the last message it finds, so
+/// messages sent in a burst notify once. Sending does not wake the
+/// executor: messages are picked up by its next pass. The receiver is
+/// dropped when the current scope is disposed.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// let (tx, rx) = std::sync::mpsc::channel();
+/// let latest = signal_from_receiver(rx);
+/// std::thread::spawn(move || {
+///     tx.send(1).unwrap();
+///     tx.send(2).unwrap();
+/// })
+/// .join()
+/// .unwrap();
+/// tick(1);
+/// assert_eq!(latest.get(), Some(2));
+/// ```
+#[track_caller]
+pub fn signal_from_receiver<T: 'static>(rx: Receiver<T>) -> Signal<Option<T>> {
+    let latest = signal(None);
+    drain_with_executor(move || {
+        if let Some(message) = rx.try_iter().last() {
+            latest.set(Some(message));
+        }
+    });
+    latest
+}
+
+/// Creates a [`SignalVec`] of every message received on `rx`, in order.
+///
+/// Like [`signal_from_receiver`], but the messages found by each pass of
+/// the executor are all appended, with a single
+/// [`extend`](SignalVec::extend).
+#[track_caller]
+pub fn signal_vec_from_receiver<T: Clone + 'static>(rx: Receiver<T>) -> SignalVec<T> {
+    let messages = SignalVec::new(Vec::new());
+    let appended = messages.clone();
+    drain_with_executor(move || {
+        let received: Vec<T> = rx.try_iter().collect();
+        if !received.is_empty() {
+            appended.extend(received);
+        }
+    });
+    messages
+}
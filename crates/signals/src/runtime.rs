@@ -1,8 +1,22 @@
-use crate::system::ReactiveSystemRef;
+use crate::cancel::CancellationToken;
+use crate::explain::PropagationPlan;
+use crate::leak::LeakReport;
+use crate::observer::RuntimeObserver;
+use crate::snapshot::GraphSnapshot;
+use crate::system::{
+    BatchContext, CapacityStats, CompactReport, CountStats, EffectQueue, FlushStats,
+    ReactiveSystemRef,
+};
 use crate::types::Location;
+use crate::validate::InvariantViolation;
 use crate::{NodeKey, system::ReactiveSystem};
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::rc::Rc;
 
 pub mod executor;
+pub mod timer;
 
 thread_local! {
     pub static REACTIVE_SYSTEM: ReactiveRuntime = ReactiveRuntime::new();
@@ -25,14 +39,48 @@ impl ReactiveRuntime {
         self.inner.borrow_mut().provide_context(value);
     }
 
+    #[inline]
+    pub fn remove_context<T: 'static>(&self) -> Option<T> {
+        self.inner.borrow_mut().remove_context()
+    }
+
     #[inline]
     pub fn use_context<T: 'static + Clone>(&self) -> Option<T> {
-        self.inner.borrow().use_context()
+        self.inner.borrow_mut().use_context()
+    }
+
+    #[inline]
+    pub fn provide_context_keyed<K: Eq + Hash + 'static, T: 'static>(&self, key: K, value: T) {
+        self.inner.borrow_mut().provide_context_keyed(key, value);
+    }
+
+    #[inline]
+    pub fn use_context_keyed<K: Eq + Hash + 'static, T: 'static + Clone>(
+        &self,
+        key: &K,
+    ) -> Option<T> {
+        self.inner.borrow().use_context_keyed(key)
+    }
+
+    #[inline]
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    pub fn scope_chain(&self) -> Vec<(Option<String>, Location)> {
+        self.inner.borrow().scope_chain()
+    }
+
+    #[inline]
+    pub fn capture_contexts(&self) -> HashMap<TypeId, Rc<dyn Any>> {
+        self.inner.borrow().capture_contexts()
+    }
+
+    #[inline]
+    pub fn provide_contexts(&self, node: NodeKey, contexts: &HashMap<TypeId, Rc<dyn Any>>) {
+        self.inner.borrow_mut().provide_contexts(node, contexts);
     }
 
     #[inline]
     pub fn has_context<T: 'static>(&self) -> bool {
-        self.inner.borrow().has_context::<T>()
+        self.inner.borrow_mut().has_context::<T>()
     }
 
     #[inline]
@@ -41,7 +89,7 @@ impl ReactiveRuntime {
     }
 
     #[inline]
-    pub fn new_scope<F: FnOnce() + 'static>(&self, f: F, caller: Location) -> NodeKey {
+    pub fn new_scope<F: FnOnce() -> T + 'static, T>(&self, f: F, caller: Location) -> (T, NodeKey) {
         ReactiveSystem::new_scope(self.inner.clone(), f, caller)
     }
 
@@ -50,6 +98,77 @@ impl ReactiveRuntime {
         self.inner.borrow_mut().new_child_scope(parent, caller)
     }
 
+    #[inline]
+    pub fn set_node_name(&self, node: NodeKey, name: &str) {
+        self.inner.borrow_mut().set_node_name(node, name);
+    }
+
+    #[inline]
+    pub fn node_name(&self, node: NodeKey) -> Option<String> {
+        self.inner.borrow().node_name(node)
+    }
+
+    #[inline]
+    pub fn scope_children(&self, node: NodeKey) -> Vec<NodeKey> {
+        self.inner.borrow().scope_children(node)
+    }
+
+    #[inline]
+    pub fn descendants(&self, node: NodeKey) -> Vec<NodeKey> {
+        self.inner.borrow().descendants(node)
+    }
+
+    #[inline]
+    pub fn subtree_count(&self, node: NodeKey) -> (usize, usize) {
+        self.inner.borrow().subtree_count(node)
+    }
+
+    #[inline]
+    pub fn snapshot(&self) -> GraphSnapshot {
+        self.inner.borrow().snapshot()
+    }
+
+    #[inline]
+    pub fn explain_propagate(&self, source: NodeKey) -> PropagationPlan {
+        self.inner.borrow().explain_propagate(source)
+    }
+
+    #[inline]
+    pub fn leak_report(&self) -> LeakReport {
+        self.inner.borrow().leak_report()
+    }
+
+    #[inline]
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    pub fn nodes_by_location(&self) -> Vec<(Location, crate::snapshot::NodeKind, usize)> {
+        self.inner.borrow().nodes_by_location()
+    }
+
+    #[inline]
+    pub fn validate(&self) -> Result<(), Vec<InvariantViolation>> {
+        self.inner.borrow().validate()
+    }
+
+    #[inline]
+    pub fn restore_topology(&self, snapshot: &GraphSnapshot) -> HashMap<NodeKey, NodeKey> {
+        self.inner.borrow_mut().restore_topology(snapshot)
+    }
+
+    #[inline]
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.inner.borrow_mut().cancellation_token()
+    }
+
+    #[inline]
+    pub fn cancel_scopes(&self, nodes: &[NodeKey]) {
+        self.inner.borrow().cancel_scopes(nodes);
+    }
+
+    #[inline]
+    pub fn reparent(&self, node: NodeKey, new_parent: NodeKey) {
+        self.inner.borrow_mut().reparent(node, new_parent);
+    }
+
     #[inline]
     pub fn trigger<F: Fn() + 'static>(&self, f: F, caller: Location) {
         ReactiveSystem::trigger(self.inner.clone(), f, caller);
@@ -66,13 +185,23 @@ impl ReactiveRuntime {
     }
 
     #[inline]
-    pub fn dispose_scope(&self, node: NodeKey) {
-        ReactiveSystem::dispose_scope(self.inner.clone(), node);
+    pub fn dispose_node(&self, node: NodeKey, caller: Location) {
+        self.inner.borrow_mut().dispose_node(node, caller);
+    }
+
+    #[inline]
+    pub fn reset_scope(&self, node: NodeKey) {
+        ReactiveSystem::reset_scope(self.inner.clone(), node);
+    }
+
+    #[inline]
+    pub fn dispose_scope(&self, node: NodeKey, caller: Location) {
+        ReactiveSystem::dispose_scope(self.inner.clone(), node, caller);
     }
 
     #[inline]
-    pub fn cleanup(&self) {
-        ReactiveSystem::cleanup(self.inner.clone());
+    pub fn cleanup(&self, caller: Location) {
+        ReactiveSystem::cleanup(self.inner.clone(), caller);
     }
 
     #[inline]
@@ -105,6 +234,11 @@ impl ReactiveRuntime {
         ReactiveSystem::computed_get(self.inner.clone(), node)
     }
 
+    #[inline]
+    pub fn computed_value(&self, node: NodeKey) -> *const dyn std::any::Any {
+        self.inner.borrow().computed_value(node)
+    }
+
     #[inline]
     pub fn signal_new<T: 'static>(&self, initial: T, caller: Location) -> NodeKey {
         self.inner.borrow_mut().signal_new(initial, caller)
@@ -112,7 +246,7 @@ impl ReactiveRuntime {
 
     #[inline]
     pub fn signal_value(&self, node: NodeKey) -> *mut (dyn std::any::Any + 'static) {
-        self.inner.borrow_mut().signal(node).value
+        self.inner.borrow().signal(node).value
     }
 
     #[inline]
@@ -122,7 +256,7 @@ impl ReactiveRuntime {
 
     #[inline]
     pub fn signal_get<T: 'static + Clone>(&self, node: NodeKey) -> T {
-        self.inner.borrow_mut().signal_get(node)
+        self.inner.borrow().signal_get(node)
     }
 
     #[inline]
@@ -131,39 +265,48 @@ impl ReactiveRuntime {
     }
 
     #[inline]
-    pub fn signal_set<T: 'static>(&self, node: NodeKey, value: T) {
-        ReactiveSystem::signal_set(self.inner.clone(), node, value);
+    pub fn signal_set<T: 'static>(&self, node: NodeKey, value: T, caller: Location) {
+        ReactiveSystem::signal_set(self.inner.clone(), node, value, caller);
     }
 
     #[inline]
-    pub fn signal_with<T: 'static, O>(&self, node: NodeKey, f: impl FnOnce(&T) -> O) -> O {
-        let value = { self.inner.borrow_mut().signal(node).value };
-        f(unsafe { &*(value as *const dyn std::any::Any as *const T) })
+    pub fn signal_update<T: 'static>(
+        &self,
+        node: NodeKey,
+        f: impl FnOnce(&mut T),
+        caller: Location,
+    ) {
+        ReactiveSystem::signal_update(self.inner, node, f, caller);
     }
 
     #[inline]
-    pub fn signal_update<T: 'static>(&self, node: NodeKey, f: impl FnOnce(&mut T) -> ()) {
-        ReactiveSystem::signal_update(self.inner.clone(), node, f);
+    pub fn signal_in_place_write_check(&self, node: NodeKey, caller: Location) {
+        self.inner.borrow().in_place_write_check(node, caller);
     }
 
     #[inline]
-    pub fn signal_borrow_read_check(&self, node: NodeKey) {
-        self.inner.borrow_mut().signal_borrow_read_check(node);
+    pub fn signal_record_write<T: Clone + 'static>(&self, node: NodeKey) {
+        ReactiveSystem::record_write::<T>(self.inner, node);
     }
 
     #[inline]
-    pub fn signal_borrow_write_check(&self, node: NodeKey) {
-        self.inner.borrow_mut().signal_borrow_write_check(node);
+    pub fn signal_borrow_read_check(&self, node: NodeKey, caller: Location) {
+        self.inner.borrow().signal_borrow_read_check(node, caller);
+    }
+
+    #[inline]
+    pub fn signal_borrow_write_check(&self, node: NodeKey, caller: Location) {
+        self.inner.borrow().signal_write_check(node, caller);
     }
 
     #[inline]
     pub fn signal_release_read(&self, node: NodeKey) {
-        self.inner.borrow_mut().signal_release_read(node);
+        self.inner.borrow().signal_release_read(node);
     }
 
     #[inline]
     pub fn signal_release_write(&self, node: NodeKey) {
-        self.inner.borrow_mut().signal_release_write(node);
+        self.inner.borrow().signal_release_write(node);
     }
 
     #[inline]
@@ -172,8 +315,106 @@ impl ReactiveRuntime {
     }
 
     #[inline]
-    pub fn end_batch(&self) {
-        ReactiveSystem::end_batch(self.inner.clone());
+    pub fn end_batch(&self, caller: Location) {
+        ReactiveSystem::end_batch(self.inner.clone(), caller);
+    }
+
+    #[inline]
+    pub fn start_transaction(&self) {
+        self.inner.borrow_mut().start_transaction();
+    }
+
+    #[inline]
+    pub fn commit_transaction(&self) {
+        ReactiveSystem::commit_transaction(self.inner);
+    }
+
+    #[inline]
+    pub fn rollback_transaction(&self) {
+        ReactiveSystem::rollback_transaction(self.inner);
+    }
+
+    #[inline]
+    pub fn flush_sync(&self) {
+        ReactiveSystem::flush_sync(self.inner.clone());
+    }
+
+    #[inline]
+    pub fn swap_batch_context(&self, context: BatchContext) -> BatchContext {
+        self.inner.borrow_mut().swap_batch_context(context)
+    }
+
+    #[inline]
+    pub fn requeue_effects(&self, effects: EffectQueue) {
+        ReactiveSystem::requeue_effects(self.inner.clone(), effects);
+    }
+
+    #[inline]
+    pub fn set_auto_batch(&self, enabled: bool) {
+        ReactiveSystem::set_auto_batch(self.inner.clone(), enabled);
+    }
+
+    #[inline]
+    pub fn auto_batch(&self) -> bool {
+        self.inner.borrow().auto_batch
+    }
+
+    #[inline]
+    pub fn flush_pending(&self) {
+        ReactiveSystem::flush_pending(self.inner.clone());
+    }
+
+    #[inline]
+    pub fn queue_capacity(&self) -> usize {
+        self.inner.borrow().queue.capacity()
+    }
+
+    #[inline]
+    pub fn last_flush_stats(&self) -> Option<FlushStats> {
+        self.inner.borrow().last_flush_stats
+    }
+
+    #[inline]
+    pub fn on_flush<F: Fn(&FlushStats) + 'static>(&self, f: F) {
+        self.inner.borrow_mut().on_flush = Some(Rc::new(f));
+    }
+
+    #[inline]
+    pub fn add_flush_start_hook(&self, hook: impl FnMut() + 'static) -> u64 {
+        self.inner.borrow_mut().add_flush_start_hook(hook)
+    }
+
+    #[inline]
+    pub fn add_flush_end_hook(&self, hook: impl FnMut(&FlushStats) + 'static) -> u64 {
+        self.inner.borrow_mut().add_flush_end_hook(hook)
+    }
+
+    #[inline]
+    pub fn remove_flush_hook(&self, id: u64) {
+        self.inner.borrow_mut().remove_flush_hook(id);
+    }
+
+    #[inline]
+    #[cfg(feature = "devtools")]
+    pub fn add_devtools_buffer(&self, buffer: std::rc::Weak<crate::devtools::DevtoolsBuffer>) {
+        let inner = self.inner.borrow_mut();
+        inner.devtools.retain(|buffer| buffer.strong_count() > 0);
+        inner.devtools.push(buffer);
+    }
+
+    #[inline]
+    pub fn set_observer(&self, observer: Option<Rc<dyn RuntimeObserver>>) {
+        self.inner.borrow_mut().observer = observer;
+    }
+
+    #[inline]
+    pub fn batch_depth(&self) -> usize {
+        self.inner.borrow().batch_depth
+    }
+
+    #[inline]
+    pub fn pending_effects(&self) -> usize {
+        self.inner.borrow().pending_effects()
     }
 
     #[inline]
@@ -181,22 +422,68 @@ impl ReactiveRuntime {
         self.inner.borrow().count()
     }
 
+    #[inline]
+    pub fn reserve(&self, nodes: usize, links: usize) {
+        self.inner.borrow_mut().reserve(nodes, links);
+    }
+
+    #[inline]
+    pub fn links_capacity(&self) -> usize {
+        self.inner.borrow().links.capacity()
+    }
+
+    #[inline]
+    pub fn shrink_to_fit(&self) -> bool {
+        self.inner.borrow_mut().shrink_to_fit()
+    }
+
+    #[inline]
+    pub fn capacity_stats(&self) -> CapacityStats {
+        self.inner.borrow().capacity_stats()
+    }
+
+    #[inline]
+    pub fn compact_report(&self) -> CompactReport {
+        self.inner.borrow().compact_report()
+    }
+
+    #[inline]
+    pub fn stats(&self) -> CountStats {
+        self.inner.borrow().stats()
+    }
+
     #[inline]
     // Field accessors for internal use
     pub fn current_scope(&self) -> NodeKey {
         self.inner.borrow().current_scope.get()
     }
 
+    #[inline]
+    pub fn root(&self) -> NodeKey {
+        self.inner.borrow().root
+    }
+
     #[inline]
     pub fn active_sub(&self) -> Option<NodeKey> {
         self.inner.borrow().active_sub.get()
     }
 
+    #[inline]
+    pub fn contains_node(&self, node: NodeKey) -> bool {
+        self.inner.borrow().nodes.contains_key(node)
+    }
+
     #[inline]
     pub fn set_current_scope(&self, scope: NodeKey) {
         self.inner.borrow().current_scope.set(scope);
     }
 
+    #[inline]
+    pub fn in_root_scope(&self) -> bool {
+        let inner = self.inner.borrow();
+        inner.current_scope.get() == inner.root
+    }
+
     #[inline]
     pub fn on_cleanup<F: FnOnce() + 'static>(&self, f: F) {
         let current = self.inner.borrow_mut().current_scope.get();
@@ -209,6 +496,34 @@ impl ReactiveRuntime {
                 .insert(current, vec![Box::new(f)]);
         }
     }
+
+    #[inline]
+    pub fn on_cleanup_async<F: FnOnce() + 'static>(&self, f: F) {
+        let current = self.inner.borrow_mut().current_scope.get();
+        if let Some(cleanups) = self.inner.borrow_mut().async_cleanups.get_mut(current) {
+            cleanups.push(Box::new(f));
+        } else {
+            self.inner
+                .borrow_mut()
+                .async_cleanups
+                .insert(current, vec![Box::new(f)]);
+        }
+    }
+}
+
+impl Drop for ReactiveRuntime {
+    fn drop(&mut self) {
+        // Catch batches left open by early returns. This runs as a
+        // thread-local destructor, where a panic aborts the process, so the
+        // leak is only reported unless aborting was asked for
+        let depth = self.inner.borrow().batch_depth;
+        if depth > 0 && !std::thread::panicking() {
+            eprintln!("samara-signals: thread exited with {depth} unclosed start_batch()");
+            if cfg!(feature = "abort-on-unclosed-batch") {
+                std::process::abort();
+            }
+        }
+    }
 }
 
 // Implement Serialize for ReactiveRuntime by serializing the inner ReactiveSystem
@@ -1,24 +1,134 @@
 use futures_channel::mpsc;
 use futures_util::StreamExt;
-use futures_util::stream::{AbortHandle, Abortable, Aborted, FuturesUnordered};
-use std::cell::RefCell;
+use futures_util::stream::{AbortHandle, Abortable, Aborted};
+use futures_util::task::{ArcWake, AtomicWaker};
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
 use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
 use std::pin::Pin;
 use std::rc::Rc;
-use std::task::{Context, Poll};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::Instant;
 
+use crate::future::EXECUTOR;
 use crate::on_cleanup;
 use crate::runtime::REACTIVE_SYSTEM;
-use crate::types::NodeKey;
+use crate::runtime::timer::Sleep;
+use crate::system::BatchContext;
+use crate::types::{Location, NodeKey};
+
+/// Bookkeeping shared between a spawned task and the executor's scope index
+pub struct TaskState {
+    pub scope: NodeKey,
+    /// Spawn order across the executor; tasks are first polled in this order
+    pub seq: u64,
+    /// Where the task was spawned
+    #[cfg_attr(not(any(debug_assertions, feature = "track-caller")), allow(dead_code))]
+    pub caller: Location,
+    pub abort: AbortHandle,
+    pub dropped: Cell<bool>,
+    /// Set when the task's future returns, `Err` if it was aborted
+    pub result: Cell<Option<Result<(), Aborted>>>,
+    pub waiters: RefCell<Vec<Waker>>,
+    /// How many times the task has been polled
+    pub polls: Cell<u64>,
+    /// The waker of the latest poll, to give the task another turn
+    pub waker: RefCell<Option<Waker>>,
+}
+
+impl TaskState {
+    /// Wake `cx` once the task has been dropped
+    pub fn register(&self, cx: &Context<'_>) {
+        let mut waiters = self.waiters.borrow_mut();
+        if !waiters.iter().any(|waker| waker.will_wake(cx.waker())) {
+            waiters.push(cx.waker().clone());
+        }
+    }
+
+    /// Schedule the task to be polled again
+    pub fn wake(&self) {
+        if let Some(waker) = self.waker.borrow().as_ref() {
+            waker.wake_by_ref();
+        }
+    }
+}
+
+/// Marks the task as dropped once everything it owns has been dropped
+///
+/// Declared as the last field of `ReactiveFuture` so that it drops after the
+/// user's future.
+pub struct TaskDropGuard(pub Rc<TaskState>);
+
+impl Drop for TaskDropGuard {
+    fn drop(&mut self) {
+        self.0.dropped.set(true);
+        let _ = EXECUTOR.try_with(|executor| executor.forget(&self.0));
+        for waker in self.0.waiters.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Resolves once every task in the list has been polled again or dropped
+pub struct TasksPolled {
+    /// Each task with its poll count when waiting started
+    pub tasks: Vec<(Rc<TaskState>, u64)>,
+}
+
+impl Future for TasksPolled {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.tasks
+            .retain(|(task, polls)| !task.dropped.get() && task.polls.get() == *polls);
+        if self.tasks.is_empty() {
+            return Poll::Ready(());
+        }
+        for (task, _) in &self.tasks {
+            task.register(cx);
+        }
+        Poll::Pending
+    }
+}
+
+/// Resolves once every task in the list has been dropped by the executor
+pub struct TasksDropped {
+    pub tasks: Vec<Rc<TaskState>>,
+}
+
+impl Future for TasksDropped {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.tasks.retain(|task| !task.dropped.get());
+        if self.tasks.is_empty() {
+            return Poll::Ready(());
+        }
+        for task in &self.tasks {
+            task.register(cx);
+        }
+        Poll::Pending
+    }
+}
+
+/// The payload of a panic caught while polling a task
+pub type PanicPayload = Box<dyn Any + Send>;
 
 pub struct ReactiveFuture {
     pub scope: NodeKey,
     pub active_sub: Option<NodeKey>,
+    /// The task's own batch depth and queued effects, so a batch held open
+    /// across an await point does not leak into other tasks
+    pub batch: BatchContext,
     pub future: Pin<Box<dyn Future<Output = Result<(), Aborted>> + 'static>>,
+    pub state: TaskDropGuard,
 }
 
 impl ReactiveFuture {
-    pub fn new<F>(future: F) -> Self
+    pub fn new<F>(future: F, seq: u64, caller: Location) -> Self
     where
         F: Future<Output = ()> + 'static,
     {
@@ -28,6 +138,7 @@ impl ReactiveFuture {
         let (abort_handle, abort_registration) = AbortHandle::new_pair();
 
         on_cleanup({
+            let abort_handle = abort_handle.clone();
             move || {
                 abort_handle.abort();
             }
@@ -36,64 +147,231 @@ impl ReactiveFuture {
         Self {
             scope,
             active_sub,
+            batch: BatchContext::default(),
             future: Box::pin(Abortable::new(future, abort_registration)),
+            state: TaskDropGuard(Rc::new(TaskState {
+                scope,
+                seq,
+                caller,
+                abort: abort_handle,
+                dropped: Cell::new(false),
+                result: Cell::new(None),
+                waiters: RefCell::new(Vec::new()),
+                polls: Cell::new(0),
+                waker: RefCell::new(None),
+            })),
         }
     }
 }
 
 impl Future for ReactiveFuture {
-    type Output = Result<(), Aborted>;
+    /// `Err` carries the payload if the task panicked
+    type Output = Result<(), PanicPayload>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         // Restore reactive context before polling
         let scope = self.scope;
         let active_sub = self.active_sub;
 
+        #[cfg(debug_assertions)]
+        if self.state.0.polls.get() == 0 {
+            let seq = self.state.0.seq;
+            let previous = EXECUTOR.with(|executor| executor.last_started.replace(Some(seq)));
+            debug_assert!(
+                previous.is_none_or(|previous| previous < seq),
+                "task {seq} started after task {previous:?}"
+            );
+        }
+
+        let batch = std::mem::take(&mut self.batch);
+        {
+            let mut waker = self.state.0.waker.borrow_mut();
+            if !waker
+                .as_ref()
+                .is_some_and(|waker| waker.will_wake(cx.waker()))
+            {
+                *waker = Some(cx.waker().clone());
+            }
+        }
+
         // Set captured context
-        let (prev_scope, prev_sub) = REACTIVE_SYSTEM.with(|ctx| {
+        let (prev_scope, prev_sub, prev_batch) = REACTIVE_SYSTEM.with(|ctx| {
             let prev_scope = ctx.current_scope();
             let prev_sub = ctx.active_sub();
             ctx.set_current_scope(scope);
             ctx.set_active_sub(active_sub);
-            (prev_scope, prev_sub)
+            let prev_batch = ctx.swap_batch_context(batch);
+            (prev_scope, prev_sub, prev_batch)
         });
 
-        let output = self.future.as_mut().poll(cx);
+        // A panic must not skip restoring the context below
+        let output = panic::catch_unwind(AssertUnwindSafe(|| self.future.as_mut().poll(cx)));
 
         // Restore previous context
-        REACTIVE_SYSTEM.with(|ctx| {
+        let batch = REACTIVE_SYSTEM.with(|ctx| {
             ctx.set_current_scope(prev_scope);
             ctx.restore_acative_sub(prev_sub);
+            ctx.swap_batch_context(prev_batch)
         });
 
+        let output = match output {
+            Ok(Poll::Ready(result)) => {
+                self.state.0.result.set(Some(result));
+                Poll::Ready(Ok(()))
+            }
+            Ok(Poll::Pending) => Poll::Pending,
+            // Left without a result, so its handle resolves as aborted
+            Err(payload) => Poll::Ready(Err(payload)),
+        };
+
+        let state = &self.state.0;
+        state.polls.set(state.polls.get() + 1);
+        if output.is_pending() {
+            for waker in state.waiters.take() {
+                waker.wake();
+            }
+        }
+
+        if output.is_ready() || batch.depth == 0 {
+            // Effects queued outside a batch (auto-batch mode) and those of a
+            // task that finished inside an unclosed batch go back to the
+            // shared queue rather than being stranded
+            REACTIVE_SYSTEM.with(|ctx| ctx.requeue_effects(batch.queued));
+        } else {
+            self.batch = batch;
+        }
+
         output
     }
 }
 
+/// Sequence numbers of woken tasks, shared with their wakers
+#[derive(Default)]
+struct ReadyQueue {
+    ready: Mutex<BTreeSet<u64>>,
+    /// Wakes whoever is driving the task set
+    driver: AtomicWaker,
+}
+
+struct TaskWaker {
+    seq: u64,
+    queue: Arc<ReadyQueue>,
+}
+
+impl ArcWake for TaskWaker {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.queue.ready.lock().unwrap().insert(arc_self.seq);
+        arc_self.queue.driver.wake();
+    }
+}
+
+/// The executor's live tasks, polled in spawn order
+///
+/// Each pass polls every task that is ready at its start once, lowest
+/// sequence number first, regardless of the order in which they were woken.
+/// Tasks woken during a pass wait for the next one, so a task that keeps
+/// waking itself cannot starve the others.
+#[derive(Default)]
+pub struct TaskSet {
+    tasks: BTreeMap<u64, (ReactiveFuture, Waker)>,
+    queue: Arc<ReadyQueue>,
+    /// The rest of the current pass
+    pass: VecDeque<u64>,
+}
+
+impl TaskSet {
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    pub fn push(&mut self, task: ReactiveFuture) {
+        let seq = task.state.0.seq;
+        let waker = futures_util::task::waker(Arc::new(TaskWaker {
+            seq,
+            queue: self.queue.clone(),
+        }));
+        self.tasks.insert(seq, (task, waker));
+        self.queue.ready.lock().unwrap().insert(seq);
+    }
+
+    /// Poll ready tasks until one completes, returning its output
+    ///
+    /// Starts at most one new pass per call. Returns `Pending` once the pass
+    /// is done, waking `cx` right away if tasks were woken in the meantime.
+    pub fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<(), PanicPayload>>> {
+        if self.tasks.is_empty() {
+            return Poll::Ready(None);
+        }
+        self.queue.driver.register(cx.waker());
+        if self.pass.is_empty() {
+            self.pass = std::mem::take(&mut *self.queue.ready.lock().unwrap())
+                .into_iter()
+                .collect();
+        }
+        while let Some(seq) = self.pass.pop_front() {
+            let Some((task, waker)) = self.tasks.get_mut(&seq) else {
+                continue;
+            };
+            let mut task_cx = Context::from_waker(waker);
+            if let Poll::Ready(output) = Pin::new(task).poll(&mut task_cx) {
+                self.tasks.remove(&seq);
+                return Poll::Ready(Some(output));
+            }
+        }
+        if !self.queue.ready.lock().unwrap().is_empty() {
+            cx.waker().wake_by_ref();
+        }
+        Poll::Pending
+    }
+}
+
 pub struct JoinFuture {
     pub stop: bool,
+    /// The first panic caught from a task, resumed once the join settles
+    pub panic: Option<PanicPayload>,
     pub rx: Rc<RefCell<mpsc::UnboundedReceiver<ReactiveFuture>>>,
-    pub tasks: Rc<RefCell<FuturesUnordered<ReactiveFuture>>>,
+    pub tasks: Rc<RefCell<TaskSet>>,
 }
 
 impl Future for JoinFuture {
     type Output = ();
 
-    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let mut rx = self.rx.borrow_mut();
-        let mut tasks = self.tasks.borrow_mut();
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        EXECUTOR.with(Executor::run_drains);
+        let this = &mut *self;
+        let mut rx = this.rx.borrow_mut();
+        let mut tasks = this.tasks.borrow_mut();
         let mut dirty = true;
         while dirty {
             while tasks.len() > 0
-                && let Poll::Ready(_) = tasks.poll_next_unpin(cx)
-            {}
+                && let Poll::Ready(Some(output)) = tasks.poll_next(cx)
+            {
+                if let Err(payload) = output {
+                    this.panic.get_or_insert(payload);
+                }
+            }
+            // Run effects deferred by auto-batch mode; they may spawn tasks
+            REACTIVE_SYSTEM.with(|ctx| ctx.flush_pending());
             dirty = false;
             while let Poll::Ready(Some(task)) = { rx.poll_next_unpin(cx) } {
                 tasks.push(task);
                 dirty = true;
             }
         }
-        if tasks.is_empty() && self.stop {
+        let finished = tasks.is_empty();
+        drop((rx, tasks));
+        // `join` lets the other tasks finish first; `poll` never finishes,
+        // so it reports the panic right away
+        if (finished || !this.stop)
+            && let Some(payload) = this.panic.take()
+        {
+            panic::resume_unwind(payload);
+        }
+        if finished && this.stop {
             Poll::Ready(())
         } else {
             Poll::Pending
@@ -101,10 +379,76 @@ impl Future for JoinFuture {
     }
 }
 
+/// How a [`join_timeout`](crate::join_timeout) ended
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JoinResult {
+    /// Every task ran to completion
+    Finished,
+    /// The deadline passed with `pending` tasks still running
+    TimedOut { pending: usize },
+}
+
+/// A `JoinFuture` that gives up at a deadline
+pub struct TimedJoinFuture {
+    pub join: JoinFuture,
+    pub deadline: Sleep,
+}
+
+impl Future for TimedJoinFuture {
+    type Output = JoinResult;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if Pin::new(&mut self.join).poll(cx).is_ready() {
+            return Poll::Ready(JoinResult::Finished);
+        }
+        if Pin::new(&mut self.deadline).poll(cx).is_ready() {
+            if let Some(payload) = self.join.panic.take() {
+                panic::resume_unwind(payload);
+            }
+            let pending = self.join.tasks.borrow().len();
+            return Poll::Ready(JoinResult::TimedOut { pending });
+        }
+        Poll::Pending
+    }
+}
+
+/// A callback run at the start of every pass of the executor, to pull in
+/// values from outside it
+pub struct Drain {
+    /// Cleared when the drain is removed, in case a pass already holds it
+    pub live: Cell<bool>,
+    pub f: RefCell<Box<dyn FnMut()>>,
+}
+
+/// Progress made by a bounded [`tick`](crate::tick)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TickReport {
+    /// Tasks that finished or were dropped after being aborted
+    pub completed: usize,
+    /// Tasks still waiting to complete
+    pub remaining: usize,
+}
+
+/// Single-threaded executor for reactive tasks
+///
+/// Spawned tasks travel through a FIFO channel into a [`TaskSet`], which
+/// polls the tasks that are ready in the same pass in spawn order. Tasks are
+/// therefore started in spawn order, and completions within a pass are
+/// applied in that order as well. Debug builds assert the start order.
 pub struct Executor {
     pub tx: mpsc::UnboundedSender<ReactiveFuture>,
     pub rx: Rc<RefCell<mpsc::UnboundedReceiver<ReactiveFuture>>>,
-    pub tasks: Rc<RefCell<FuturesUnordered<ReactiveFuture>>>,
+    pub tasks: Rc<RefCell<TaskSet>>,
+    /// Live tasks indexed by the scope they were spawned in
+    pub owned: RefCell<HashMap<NodeKey, Vec<Rc<TaskState>>>>,
+    /// Async cleanup tasks spawned while a `dispose_async` collects them
+    pub cleanup_tasks: RefCell<Option<Vec<Rc<TaskState>>>>,
+    pub next_seq: Cell<u64>,
+    /// Run before each `join`, `poll` and `tick` pass
+    pub drains: RefCell<Vec<Rc<Drain>>>,
+    /// The latest task polled for the first time, to check start order
+    #[cfg(debug_assertions)]
+    pub last_started: Cell<Option<u64>>,
 }
 
 impl Executor {
@@ -114,21 +458,128 @@ impl Executor {
             tx,
             rx: Rc::new(RefCell::new(rx)),
             tasks: Default::default(),
+            owned: Default::default(),
+            cleanup_tasks: Default::default(),
+            next_seq: Cell::new(0),
+            drains: Default::default(),
+            #[cfg(debug_assertions)]
+            last_started: Cell::new(None),
         }
     }
 
     /// Spawn a new task with captured reactive context
-    pub fn spawn<F>(&self, future: F)
+    pub fn spawn<F>(&self, future: F, caller: Location) -> Rc<TaskState>
     where
         F: Future<Output = ()> + 'static,
     {
-        self.tx.unbounded_send(ReactiveFuture::new(future)).unwrap();
+        let seq = self.next_seq.get();
+        self.next_seq.set(seq + 1);
+        let task = ReactiveFuture::new(future, seq, caller);
+        let state = task.state.0.clone();
+        self.owned
+            .borrow_mut()
+            .entry(task.scope)
+            .or_default()
+            .push(state.clone());
+        self.tx.unbounded_send(task).unwrap();
+        state
+    }
+
+    /// Every live task spawned in one of `scopes`
+    pub fn scope_tasks(&self, scopes: &[NodeKey]) -> Vec<Rc<TaskState>> {
+        let owned = self.owned.borrow();
+        scopes
+            .iter()
+            .filter_map(|scope| owned.get(scope))
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    /// Abort every live task spawned in one of `scopes`, returning them so
+    /// the caller can wait for the executor to drop them
+    pub fn abort_scopes(&self, scopes: &[NodeKey]) -> Vec<Rc<TaskState>> {
+        let tasks = self.scope_tasks(scopes);
+        for task in &tasks {
+            task.abort.abort();
+        }
+        tasks
+    }
+
+    /// Live tasks that have not been aborted, in spawn order
+    pub fn pending(&self) -> Vec<Rc<TaskState>> {
+        let mut tasks: Vec<_> = self
+            .owned
+            .borrow()
+            .values()
+            .flatten()
+            .filter(|task| !task.abort.is_aborted())
+            .cloned()
+            .collect();
+        tasks.sort_by_key(|task| task.seq);
+        tasks
+    }
+
+    /// Run `dispose`, returning the async cleanup tasks it spawned
+    pub fn collect_cleanups(&self, dispose: impl FnOnce()) -> Vec<Rc<TaskState>> {
+        let outer = self.cleanup_tasks.replace(Some(Vec::new()));
+        dispose();
+        self.cleanup_tasks.replace(outer).unwrap_or_default()
+    }
+
+    /// Record an async cleanup task for a collecting `dispose_async`
+    pub fn add_cleanup_task(&self, task: Rc<TaskState>) {
+        if let Some(tasks) = self.cleanup_tasks.borrow_mut().as_mut() {
+            tasks.push(task);
+        }
+    }
+
+    /// Remove a dropped task from the scope index
+    pub fn forget(&self, task: &Rc<TaskState>) {
+        let mut owned = self.owned.borrow_mut();
+        if let Some(tasks) = owned.get_mut(&task.scope) {
+            tasks.retain(|other| !Rc::ptr_eq(other, task));
+            if tasks.is_empty() {
+                owned.remove(&task.scope);
+            }
+        }
+    }
+
+    /// Register `f` to run at the start of every pass
+    pub fn add_drain(&self, f: impl FnMut() + 'static) -> Rc<Drain> {
+        let drain = Rc::new(Drain {
+            live: Cell::new(true),
+            f: RefCell::new(Box::new(f)),
+        });
+        self.drains.borrow_mut().push(drain.clone());
+        drain
+    }
+
+    pub fn remove_drain(&self, drain: &Rc<Drain>) {
+        drain.live.set(false);
+        (self.drains.borrow_mut()).retain(|other| !Rc::ptr_eq(other, drain));
+    }
+
+    /// Run every drain
+    ///
+    /// Drains may write signals, running effects that add or remove drains,
+    /// so the list is not borrowed while they run.
+    pub fn run_drains(&self) {
+        let drains = self.drains.borrow().clone();
+        for drain in drains {
+            if drain.live.get()
+                && let Ok(mut f) = drain.f.try_borrow_mut()
+            {
+                f();
+            }
+        }
     }
 
     /// Flush pending tasks to the main task list
     pub fn join(&self) -> JoinFuture {
         JoinFuture {
             stop: true,
+            panic: None,
             rx: self.rx.clone(),
             tasks: self.tasks.clone(),
         }
@@ -137,10 +588,68 @@ impl Executor {
     pub fn poll(&self) -> JoinFuture {
         JoinFuture {
             stop: false,
+            panic: None,
             rx: self.rx.clone(),
             tasks: self.tasks.clone(),
         }
     }
+
+    /// Poll the task set at most `max_polls` times without blocking
+    ///
+    /// Uses a no-op waker: tasks woken in the meantime are polled by the
+    /// next tick, so the caller is expected to tick regularly. A panic from
+    /// a task is resumed after the bounded pass.
+    pub fn tick(&self, max_polls: usize) -> TickReport {
+        self.run_drains();
+        let mut rx = self.rx.borrow_mut();
+        let mut tasks = self.tasks.borrow_mut();
+        let mut cx = Context::from_waker(futures_util::task::noop_waker_ref());
+        let mut report = TickReport::default();
+        let mut panicked = None;
+
+        for _ in 0..max_polls {
+            while let Poll::Ready(Some(task)) = rx.poll_next_unpin(&mut cx) {
+                tasks.push(task);
+            }
+            match tasks.poll_next(&mut cx) {
+                Poll::Ready(Some(output)) => {
+                    report.completed += 1;
+                    if let Err(payload) = output {
+                        panicked.get_or_insert(payload);
+                    }
+                }
+                Poll::Ready(None) => break,
+                Poll::Pending => {}
+            }
+            REACTIVE_SYSTEM.with(|ctx| ctx.flush_pending());
+        }
+
+        while let Poll::Ready(Some(task)) = rx.poll_next_unpin(&mut cx) {
+            tasks.push(task);
+        }
+        report.remaining = tasks.len();
+        drop((rx, tasks));
+        if let Some(payload) = panicked {
+            panic::resume_unwind(payload);
+        }
+        report
+    }
+
+    /// Like `join`, but stops waiting at `deadline`
+    pub fn join_until(&self, deadline: Instant) -> TimedJoinFuture {
+        TimedJoinFuture {
+            join: self.join(),
+            deadline: Sleep::new(deadline),
+        }
+    }
+
+    /// Like `poll`, but stops at `deadline`
+    pub fn poll_until(&self, deadline: Instant) -> TimedJoinFuture {
+        TimedJoinFuture {
+            join: self.poll(),
+            deadline: Sleep::new(deadline),
+        }
+    }
 }
 
 impl Default for Executor {
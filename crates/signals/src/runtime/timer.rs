@@ -0,0 +1,131 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Condvar, Mutex, OnceLock};
+use std::task::{Context, Poll, Waker};
+use std::time::Instant;
+
+/// A pending wake-up, ordered by deadline only
+struct Entry {
+    deadline: Instant,
+    waker: Waker,
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.deadline == other.deadline
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.deadline.cmp(&other.deadline)
+    }
+}
+
+/// A single background thread that wakes sleeping tasks at their deadline
+///
+/// The executor is runtime-agnostic, so timers cannot rely on the caller's
+/// async runtime. Wakers are `Send`, which lets one shared thread serve every
+/// reactive thread.
+pub struct Timer {
+    entries: Mutex<BinaryHeap<Reverse<Entry>>>,
+    changed: Condvar,
+}
+
+impl Timer {
+    pub fn get() -> &'static Timer {
+        static TIMER: OnceLock<Timer> = OnceLock::new();
+        TIMER.get_or_init(|| {
+            std::thread::Builder::new()
+                .name(String::from("samara-timer"))
+                .spawn(|| Timer::get().run())
+                .expect("failed to spawn the timer thread");
+            Timer {
+                entries: Mutex::new(BinaryHeap::new()),
+                changed: Condvar::new(),
+            }
+        })
+    }
+
+    /// Wake `waker` once `deadline` has passed
+    pub fn register(&self, deadline: Instant, waker: Waker) {
+        let mut entries = self.entries.lock().unwrap();
+        let earliest = entries
+            .peek()
+            .is_none_or(|Reverse(first)| deadline < first.deadline);
+        entries.push(Reverse(Entry { deadline, waker }));
+        if earliest {
+            self.changed.notify_one();
+        }
+    }
+
+    fn run(&self) {
+        let mut entries = self.entries.lock().unwrap();
+        loop {
+            let now = Instant::now();
+            while entries
+                .peek()
+                .is_some_and(|Reverse(first)| first.deadline <= now)
+            {
+                let Reverse(entry) = entries.pop().unwrap();
+                entry.waker.wake();
+            }
+            entries = match entries.peek() {
+                Some(Reverse(first)) => {
+                    let timeout = first.deadline - now;
+                    self.changed.wait_timeout(entries, timeout).unwrap().0
+                }
+                None => self.changed.wait(entries).unwrap(),
+            };
+        }
+    }
+}
+
+/// Future returned by [`sleep`](crate::sleep) and [`sleep_until`](crate::sleep_until)
+pub struct Sleep {
+    deadline: Instant,
+    registered: Option<Waker>,
+}
+
+impl Sleep {
+    pub fn new(deadline: Instant) -> Self {
+        Self {
+            deadline,
+            registered: None,
+        }
+    }
+
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if Instant::now() >= self.deadline {
+            return Poll::Ready(());
+        }
+        // Re-register only if polled by a different task than last time
+        let stale = self
+            .registered
+            .as_ref()
+            .is_none_or(|waker| !waker.will_wake(cx.waker()));
+        if stale {
+            Timer::get().register(self.deadline, cx.waker().clone());
+            self.registered = Some(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
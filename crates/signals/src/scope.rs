@@ -1,5 +1,9 @@
+use crate::future::EXECUTOR;
 use crate::runtime::REACTIVE_SYSTEM;
+use crate::runtime::executor::{TasksDropped, TasksPolled};
 use crate::types::{Location, NodeKey, caller};
+use std::future::Future;
+use std::ops::Deref;
 
 #[derive(Clone, Copy)]
 pub struct Scope {
@@ -11,21 +15,425 @@ impl Scope {
         Self { node }
     }
 
+    /// The key of the underlying scope node.
+    pub fn node(&self) -> NodeKey {
+        self.node
+    }
+
     pub fn run<F: FnOnce() + 'static>(f: F, caller: Location) -> Self {
-        let scope = REACTIVE_SYSTEM.with(move |ctx| ctx.new_scope(f, caller));
-        Self { node: scope }
+        Self::run_with(f, caller).1
     }
 
+    pub fn run_with<F: FnOnce() -> T + 'static, T>(f: F, caller: Location) -> (T, Self) {
+        let (output, scope) = REACTIVE_SYSTEM.with(move |ctx| ctx.new_scope(f, caller));
+        (output, Self { node: scope })
+    }
+
+    #[track_caller]
     pub fn dispose(&self) {
+        let caller = caller();
         REACTIVE_SYSTEM.with(|ctx| {
-            ctx.dispose_scope(self.node);
+            ctx.dispose_scope(self.node, caller);
+        });
+    }
+
+    /// Disposes the scope once the async tasks it owns have actually stopped.
+    ///
+    /// [`dispose`](Scope::dispose) aborts tasks spawned in the scope, but an
+    /// aborted task is only dropped the next time the executor polls it. The
+    /// returned future first cancels the [`CancellationToken`]s of this scope
+    /// and its descendants and lets each of their tasks run once more, so
+    /// tasks waiting for cancellation can finish cleanly. It then aborts the
+    /// tasks that are still running, waits until the executor has dropped
+    /// them (and with them everything they hold), and disposes the scope.
+    /// Finally it waits for the teardown registered with
+    /// [`on_cleanup_async`](crate::on_cleanup_async) to finish.
+    ///
+    /// The executor must keep being driven by [`join`](crate::join) or
+    /// [`poll`](crate::poll) while the returned future is awaited.
+    ///
+    /// [`CancellationToken`]: crate::CancellationToken
+    pub fn dispose_async(&self) -> impl Future<Output = ()> + 'static {
+        let scope = *self;
+        async move {
+            let mut scopes = vec![scope.node];
+            scopes.extend(REACTIVE_SYSTEM.with(|ctx| ctx.descendants(scope.node)));
+
+            let tasks = EXECUTOR.with(|executor| executor.scope_tasks(&scopes));
+            REACTIVE_SYSTEM.with(|ctx| ctx.cancel_scopes(&scopes));
+            for task in &tasks {
+                task.wake();
+            }
+            let tasks = tasks
+                .into_iter()
+                .map(|task| {
+                    let polls = task.polls.get();
+                    (task, polls)
+                })
+                .collect();
+            TasksPolled { tasks }.await;
+
+            let tasks = EXECUTOR.with(|executor| executor.abort_scopes(&scopes));
+            TasksDropped { tasks }.await;
+
+            let tasks = EXECUTOR.with(|executor| executor.collect_cleanups(|| scope.dispose()));
+            TasksDropped { tasks }.await;
+        }
+    }
+
+    /// Runs `f` with this scope as the current scope.
+    ///
+    /// Reactive primitives created by `f` become children of this scope and
+    /// contexts provided to it are visible, just as if `f` had been called
+    /// from the scope's own closure. Like in that closure, signals read by
+    /// `f` are not tracked by the effect or computed `run_in` is called
+    /// from. The previous scope is restored afterwards, even if `f` panics.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the scope has already been disposed.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use samara_signals::*;
+    /// let scope = scope(|| provide_context(42));
+    /// let value = scope.run_in(|| use_context::<i32>());
+    /// assert_eq!(value, Some(42));
+    /// scope.dispose();
+    /// ```
+    pub fn run_in<O>(&self, f: impl FnOnce() -> O) -> O {
+        let (prev_scope, prev_sub) = REACTIVE_SYSTEM.with(|ctx| {
+            if !ctx.contains_node(self.node) {
+                panic!("Scope::run_in called on a disposed scope");
+            }
+            let prev_scope = ctx.current_scope();
+            ctx.set_current_scope(self.node);
+            (prev_scope, ctx.set_active_sub(None))
+        });
+        let _restore = RestoreScope(prev_scope);
+        let _restore_sub = RestoreActiveSub(prev_sub);
+        f()
+    }
+
+    /// Labels this scope in snapshots and diagnostics.
+    ///
+    /// Scopes created from the same place, such as one per list item, share
+    /// a creation location; a name tells them apart. Names show up in
+    /// [`serialize`](crate::serialize), in [`expect_context`](crate::expect_context)
+    /// failures and in signal borrow-conflict panics. Setting a name on a
+    /// disposed scope does nothing.
+    pub fn set_name(&self, name: &str) {
+        REACTIVE_SYSTEM.with(|ctx| ctx.set_node_name(self.node, name));
+    }
+
+    /// The name set with [`set_name`](Scope::set_name) or [`scope_named`].
+    pub fn name(&self) -> Option<String> {
+        REACTIVE_SYSTEM.with(|ctx| ctx.node_name(self.node))
+    }
+
+    /// The scopes and effects directly owned by this scope.
+    ///
+    /// Signals and computeds owned by the scope are not included, but are
+    /// counted by [`descendant_count`](Scope::descendant_count). This is an
+    /// untracked read.
+    pub fn children(&self) -> Vec<Scope> {
+        REACTIVE_SYSTEM
+            .with(|ctx| ctx.scope_children(self.node))
+            .into_iter()
+            .map(Scope::new)
+            .collect()
+    }
+
+    /// The number of nodes of any kind owned, directly or indirectly, by
+    /// this scope, excluding the scope itself.
+    pub fn descendant_count(&self) -> usize {
+        REACTIVE_SYSTEM.with(|ctx| ctx.descendants(self.node).len())
+    }
+
+    /// Counts the `(nodes, links)` under this scope, like [`count`](crate::count)
+    /// does for the whole runtime.
+    ///
+    /// The scope node itself is included. A link is counted if its subscriber
+    /// is under this scope. A disposed scope reports `(0, 0)`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use samara_signals::*;
+    /// let s = scope(|| {
+    ///     let a = signal(1);
+    ///     effect(move || {
+    ///         a.get();
+    ///     });
+    /// });
+    /// // scope, signal and effect; the effect depends on the signal and its owner
+    /// assert_eq!(s.node_count(), (3, 2));
+    /// ```
+    pub fn node_count(&self) -> (usize, usize) {
+        REACTIVE_SYSTEM.with(|ctx| ctx.subtree_count(self.node))
+    }
+
+    /// Moves `child` and everything it owns under this scope.
+    ///
+    /// Afterwards `child` is disposed with this scope instead of its previous
+    /// parent, and sees this scope's contexts. See [`reparent`].
+    ///
+    /// # Example
+    /// ```rust
+    /// # use samara_signals::*;
+    /// let (item, old_parent) = scope_with(|| scope(|| {}));
+    /// let new_parent = scope(|| {});
+    /// new_parent.adopt(item);
+    /// old_parent.dispose(); // `item` survives
+    /// new_parent.dispose(); // `item` is disposed here
+    /// ```
+    pub fn adopt(&self, child: Scope) {
+        reparent(child.node, self.node);
+    }
+
+    /// Creates a new, empty child scope of this scope.
+    ///
+    /// The child is disposed together with this scope, even though it was
+    /// created after this scope's closure finished running.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use samara_signals::*;
+    /// let parent = scope(|| {});
+    /// let child = parent.child();
+    /// child.run_in(|| signal(1));
+    /// parent.dispose(); // Also disposes `child` and its signal
+    /// ```
+    #[track_caller]
+    pub fn child(&self) -> Scope {
+        let caller = caller();
+        let node = REACTIVE_SYSTEM.with(|ctx| ctx.new_child_scope(self.node, caller));
+        Scope::new(node)
+    }
+
+    /// Creates a new child scope of this scope and runs `f` inside it,
+    /// returning the closure's output together with the child.
+    #[track_caller]
+    pub fn child_with<O>(&self, f: impl FnOnce() -> O) -> (O, Scope) {
+        let child = self.child();
+        (child.run_in(f), child)
+    }
+}
+
+/// A non-`Copy` scope handle that disposes its scope when dropped.
+///
+/// Dereferences to [`Scope`], so the whole scope API is available. Disposing
+/// explicitly before the drop is fine; the drop then does nothing.
+pub struct OwnedScope {
+    scope: Scope,
+}
+
+impl OwnedScope {
+    /// Gives up ownership without disposing, returning the plain handle.
+    ///
+    /// The scope then lives until it is disposed manually or its parent is.
+    pub fn into_raw(self) -> Scope {
+        let scope = self.scope;
+        std::mem::forget(self);
+        scope
+    }
+
+    /// Same as [`into_raw`](Self::into_raw), for scopes that are meant to
+    /// live as long as their parent.
+    pub fn leak(self) -> Scope {
+        self.into_raw()
+    }
+}
+
+impl Deref for OwnedScope {
+    type Target = Scope;
+
+    fn deref(&self) -> &Scope {
+        &self.scope
+    }
+}
+
+impl Drop for OwnedScope {
+    fn drop(&mut self) {
+        // The runtime may already be gone during thread teardown
+        let caller = caller();
+        let _ = REACTIVE_SYSTEM.try_with(|ctx| ctx.dispose_scope(self.scope.node, caller));
+    }
+}
+
+/// The reactive owner at some point in time, captured for later use.
+///
+/// Async tasks spawned with [`spawn`](crate::spawn) remember the scope they
+/// were created in. `Owner` offers the same for other callback systems, such
+/// as window event handlers or FFI callbacks: capture the owner while setting
+/// up, then create signals and effects under it when the callback fires.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// let (owner, scope) = scope_with(Owner::current);
+///
+/// // Later, from anywhere
+/// let s = owner.with(|| signal(1));
+///
+/// scope.dispose(); // Also disposes `s`
+/// assert!(!owner.is_alive());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Owner {
+    node: NodeKey,
+}
+
+impl Owner {
+    /// Captures the current scope or effect.
+    pub fn current() -> Self {
+        Self {
+            node: REACTIVE_SYSTEM.with(|ctx| ctx.current_scope()),
+        }
+    }
+
+    /// Runs `f` with this owner as the current scope.
+    ///
+    /// Nodes created by `f` are owned by the captured scope and disposed with
+    /// it, and contexts are looked up from there. Dependency tracking is
+    /// suspended while `f` runs, so this is safe to call from inside an
+    /// unrelated effect. The previous state is restored afterwards, even if
+    /// `f` panics.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the owner has been disposed; see [`is_alive`](Owner::is_alive).
+    pub fn with<O>(&self, f: impl FnOnce() -> O) -> O {
+        let (prev_scope, prev_sub) = REACTIVE_SYSTEM.with(|ctx| {
+            if !ctx.contains_node(self.node) {
+                panic!("Owner::with called on a disposed owner");
+            }
+            let prev_scope = ctx.current_scope();
+            ctx.set_current_scope(self.node);
+            (prev_scope, ctx.set_active_sub(None))
         });
+        let _restore = RestoreScope(prev_scope);
+        let _restore_sub = RestoreActiveSub(prev_sub);
+        f()
+    }
+
+    /// Whether the captured scope or effect has not been disposed yet.
+    pub fn is_alive(&self) -> bool {
+        REACTIVE_SYSTEM.with(|ctx| ctx.contains_node(self.node))
+    }
+
+    /// The key of the captured node.
+    pub fn node(&self) -> NodeKey {
+        self.node
     }
 }
 
+/// Restores the current scope when dropped, so it is reset on unwind too
+struct RestoreScope(NodeKey);
+
+impl Drop for RestoreScope {
+    fn drop(&mut self) {
+        REACTIVE_SYSTEM.with(|ctx| ctx.set_current_scope(self.0));
+    }
+}
+
+/// Returns a handle to the root scope.
+///
+/// Everything created outside of any other scope is owned by the root, and
+/// only disposed by a global [`cleanup`].
+pub fn root_scope() -> Scope {
+    Scope::new(REACTIVE_SYSTEM.with(|ctx| ctx.root()))
+}
+
+/// Runs `f` in the root scope, regardless of the current scope.
+///
+/// Signals, effects and contexts created inside `f` belong to the root and
+/// live until a global [`cleanup`], which suits library initialization that
+/// may be triggered from anywhere. Dependency tracking is suspended while `f`
+/// runs, so reads inside it do not subscribe the surrounding effect.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// scope(|| {
+///     with_root(|| provide_context(1i32));
+/// })
+/// .dispose();
+///
+/// scope(|| assert_eq!(use_context::<i32>(), Some(1)));
+/// ```
+pub fn with_root<O>(f: impl FnOnce() -> O) -> O {
+    let (prev_scope, prev_sub) = REACTIVE_SYSTEM.with(|ctx| {
+        let prev_scope = ctx.current_scope();
+        ctx.set_current_scope(ctx.root());
+        (prev_scope, ctx.set_active_sub(None))
+    });
+    let _restore = RestoreScope(prev_scope);
+    let _restore_sub = RestoreActiveSub(prev_sub);
+    f()
+}
+
+/// Runs `f` so that nodes it creates are not owned by the current scope or
+/// effect.
+///
+/// An effect disposes everything it created when it re-runs. A signal created
+/// through `unowned` is owned by the root instead, so it survives re-runs and
+/// can be cached across them; dispose it explicitly with
+/// [`Signal::dispose`](crate::Signal::dispose) when done, or it lives until a
+/// global [`cleanup`]. Like [`with_root`], dependency tracking is suspended
+/// while `f` runs.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// let trigger = signal(0);
+/// let cache = signal(None);
+/// effect(move || {
+///     trigger.get();
+///     if cache.get_untracked().is_none() {
+///         cache.set(Some(unowned(|| signal(0))));
+///     }
+/// });
+/// let cached = cache.get_untracked().unwrap();
+/// trigger.set(1);
+/// cached.set(1); // Still alive after the re-run
+/// cached.dispose();
+/// ```
+pub fn unowned<O>(f: impl FnOnce() -> O) -> O {
+    with_root(f)
+}
+
+/// Runs `f` without subscribing the active effect or computed to the
+/// signals it reads.
+pub(crate) fn untracked<O>(f: impl FnOnce() -> O) -> O {
+    let prev_sub = REACTIVE_SYSTEM.with(|ctx| ctx.set_active_sub(None));
+    let _restore_sub = RestoreActiveSub(prev_sub);
+    f()
+}
+
+struct RestoreActiveSub(Option<NodeKey>);
+
+impl Drop for RestoreActiveSub {
+    fn drop(&mut self) {
+        REACTIVE_SYSTEM.with(|ctx| ctx.restore_acative_sub(self.0));
+    }
+}
+
+/// Moves `node` (a scope, effect, signal or computed) and its subtree under
+/// `new_parent`, so that it is disposed with `new_parent` from now on.
+///
+/// # Panics
+///
+/// Panics if either node has been disposed, if `node` is the root scope, or
+/// if `new_parent` is `node` itself or one of its descendants.
+pub fn reparent(node: NodeKey, new_parent: NodeKey) {
+    REACTIVE_SYSTEM.with(|ctx| ctx.reparent(node, new_parent));
+}
+
+#[track_caller]
 pub fn cleanup() {
+    let caller = caller();
     REACTIVE_SYSTEM.with(|ctx| {
-        ctx.cleanup();
+        ctx.cleanup(caller);
     })
 }
 
@@ -49,6 +457,68 @@ pub fn scope<F: FnOnce() + 'static>(f: F) -> Scope {
     Scope::run(f, caller())
 }
 
+/// Creates a new named scope and executes a function within it.
+///
+/// Same as [`scope`], but the scope is labelled with `name` before `f` runs,
+/// see [`Scope::set_name`].
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// let sidebar = scope_named("sidebar", || {});
+/// assert_eq!(sidebar.name().as_deref(), Some("sidebar"));
+/// ```
+#[track_caller]
+pub fn scope_named<F: FnOnce() + 'static>(name: &str, f: F) -> Scope {
+    let name = name.to_owned();
+    Scope::run(
+        move || {
+            REACTIVE_SYSTEM.with(|ctx| ctx.set_node_name(ctx.current_scope(), &name));
+            f();
+        },
+        caller(),
+    )
+}
+
+/// Creates a new scope, executes a function within it, and returns the
+/// function's output together with the scope.
+///
+/// This behaves exactly like [`scope`], but lets values created inside the
+/// scope (signals, handles, computed results) be passed out directly.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// let (count, scope) = scope_with(|| signal(42));
+/// assert_eq!(count.get(), 42);
+/// scope.dispose(); // `count` is disposed together with the scope
+/// ```
+#[track_caller]
+pub fn scope_with<T, F: FnOnce() -> T + 'static>(f: F) -> (T, Scope) {
+    Scope::run_with(f, caller())
+}
+
+/// Creates a new scope like [`scope`], but returns an [`OwnedScope`] that
+/// disposes the scope when it goes out of scope.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// let before = count();
+/// {
+///     let _scope = scope_owned(|| {
+///         let _s = signal(42);
+///     });
+/// } // Disposed here
+/// assert_eq!(count(), before);
+/// ```
+#[track_caller]
+pub fn scope_owned<F: FnOnce() + 'static>(f: F) -> OwnedScope {
+    OwnedScope {
+        scope: Scope::run(f, caller()),
+    }
+}
+
 /// Creates a closure that executes a function within a new child scope.
 ///
 /// The parent scope is captured when this function is called, not when the
@@ -98,3 +568,53 @@ where
         })
     }
 }
+
+/// Creates a closure that re-runs a function in place inside one child scope.
+///
+/// Where [`scoped`] creates a fresh child scope on every call, this creates a
+/// single child scope on the first call and reuses it afterwards. Before each
+/// later call the scope is reset the way an effect is before re-running: its
+/// cleanups run and everything created by the previous call is disposed.
+/// Contexts provided into the scope persist across calls.
+///
+/// Like [`scoped`], the parent scope is captured when this function is
+/// called. If the child scope is disposed along with its parent, the next
+/// call creates a new one.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// let mut render = scoped_reusable(|n: i32| {
+///     let doubled = signal(n * 2);
+///     doubled.get()
+/// });
+/// assert_eq!(render(1), 2);
+/// let (nodes, _) = count();
+/// assert_eq!(render(2), 4); // The signal from the first call is gone
+/// assert_eq!(count().0, nodes);
+/// ```
+#[track_caller]
+pub fn scoped_reusable<T, U>(mut f: impl FnMut(T) -> U + 'static) -> impl FnMut(T) -> U
+where
+    T: 'static,
+{
+    let caller = caller();
+    let parent_scope = REACTIVE_SYSTEM.with(|ctx| ctx.current_scope());
+    let mut scope_node: Option<NodeKey> = None;
+
+    move |t| {
+        let node = REACTIVE_SYSTEM.with(|ctx| match scope_node {
+            Some(node) if ctx.contains_node(node) => {
+                ctx.reset_scope(node);
+                node
+            }
+            _ => ctx.new_child_scope(parent_scope, caller),
+        });
+        scope_node = Some(node);
+
+        let prev_scope = REACTIVE_SYSTEM.with(|ctx| ctx.current_scope());
+        REACTIVE_SYSTEM.with(|ctx| ctx.set_current_scope(node));
+        let _restore = RestoreScope(prev_scope);
+        f(t)
+    }
+}
@@ -1,3 +1,4 @@
+use crate::computed::{Computed, memo};
 use crate::runtime::REACTIVE_SYSTEM;
 use crate::types::{Location, NodeKey, caller};
 use std::fmt::Debug;
@@ -31,7 +32,7 @@ impl<T> Copy for Signal<T> {}
 
 impl AddAssign<i32> for Signal<i32> {
     fn add_assign(&mut self, rhs: i32) {
-        self.update(|value| *value += rhs);
+        self.update_recorded(|value| *value += rhs);
     }
 }
 
@@ -46,6 +47,24 @@ impl<T: 'static + Clone> Signal<T> {
     pub fn get_untracked(&self) -> T {
         REACTIVE_SYSTEM.with(|ctx| ctx.signal_get::<T>(self.node))
     }
+
+    /// Same as [`write`](Self::write), but keeps a copy of the value to
+    /// restore if it is taken inside a transaction that is rolled back.
+    #[track_caller]
+    pub(crate) fn write_recorded(&self) -> SignalWriteGuard<'_, T> {
+        SignalWriteGuard::new_recorded(self.node)
+    }
+
+    /// Same as [`update`](Self::update), but keeps a copy of the value to
+    /// restore if it is called inside a transaction that is rolled back.
+    #[track_caller]
+    pub(crate) fn update_recorded(&self, f: impl FnOnce(&mut T)) {
+        let caller = caller();
+        REACTIVE_SYSTEM.with(|ctx| {
+            ctx.signal_record_write::<T>(self.node);
+            ctx.signal_update(self.node, f, caller);
+        });
+    }
 }
 
 impl<T: 'static> Signal<T> {
@@ -57,8 +76,15 @@ impl<T: 'static> Signal<T> {
         }
     }
 
+    /// The key of the underlying signal node.
+    pub fn node(&self) -> NodeKey {
+        self.node
+    }
+
+    #[track_caller]
     pub fn set(&self, value: T) {
-        REACTIVE_SYSTEM.with(move |ctx| ctx.signal_set::<T>(self.node, value));
+        let caller = caller();
+        REACTIVE_SYSTEM.with(move |ctx| ctx.signal_set::<T>(self.node, value, caller));
     }
 
     pub fn track(&self) {
@@ -68,55 +94,152 @@ impl<T: 'static> Signal<T> {
         });
     }
 
+    #[track_caller]
     pub fn peek(&self) -> SignalReadGuard<'_, T> {
         let node = self.node;
-        REACTIVE_SYSTEM.with(|ctx| {
+        let caller = caller();
+        let value = REACTIVE_SYSTEM.with(|ctx| {
             // Check borrow but don't track dependencies
-            ctx.signal_borrow_read_check(node);
+            ctx.signal_borrow_read_check(node, caller);
+            ctx.signal_value(node)
         });
         SignalReadGuard {
             node,
+            value: value as *const T,
             _marker: PhantomData,
         }
     }
 
+    #[track_caller]
     pub fn read(&self) -> SignalReadGuard<'_, T> {
         SignalReadGuard::new(self.node)
     }
 
+    /// Borrows the value for writing, notifying readers when the guard is
+    /// dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics inside a [`transaction`](crate::transaction) that has not
+    /// replaced the value with [`set`](Self::set) yet, as the transaction
+    /// would have nothing to restore.
+    #[track_caller]
     pub fn write(&self) -> SignalWriteGuard<'_, T> {
         SignalWriteGuard::new(self.node)
     }
 
+    /// Calls `f` with a reference to the value, tracking the signal like
+    /// [`get`](Self::get) does without cloning it.
+    ///
+    /// The signal is borrowed for reading while `f` runs: `f` may read any
+    /// signal, this one included, but writing this one panics.
+    #[track_caller]
     pub fn with<O>(&self, f: impl FnOnce(&T) -> O) -> O {
-        REACTIVE_SYSTEM.with(|ctx| ctx.signal_with(self.node, f))
+        f(&SignalReadGuard::new(self.node))
     }
 
+    /// Modifies the value in place and notifies readers.
+    ///
+    /// # Panics
+    ///
+    /// Panics inside a [`transaction`](crate::transaction) that has not
+    /// replaced the value with [`set`](Self::set) yet, as the transaction
+    /// would have nothing to restore.
+    #[track_caller]
     pub fn update(&self, f: impl FnOnce(&mut T)) {
+        let caller = caller();
         REACTIVE_SYSTEM.with(|ctx| {
-            ctx.signal_update(self.node, f);
+            ctx.signal_update(self.node, f, caller);
         });
     }
+
+    /// Disposes the signal before its owner is, dropping its value.
+    ///
+    /// Computeds and effects that read it lose the dependency. Using the
+    /// signal afterwards panics; disposing it again does nothing.
+    #[track_caller]
+    pub fn dispose(&self) {
+        let caller = caller();
+        REACTIVE_SYSTEM.with(|ctx| ctx.dispose_node(self.node, caller));
+    }
 }
 
 impl Signal<bool> {
     pub fn toggle(&self) {
-        self.update(|value| *value = !*value);
+        self.update_recorded(|value| *value = !*value);
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> Signal<Vec<T>> {
+    /// Creates a memo of the element at `index`, `None` while the vector is
+    /// shorter.
+    ///
+    /// The memo re-reads the vector whenever it changes, but only notifies
+    /// its readers when the element itself changes, so effects watching
+    /// different indices do not re-run for each other's writes.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use samara_signals::*;
+    /// let rows = signal(vec![1, 2]);
+    /// let third = rows.at(2);
+    /// assert_eq!(third.get(), None);
+    /// rows.update(|rows| rows.push(3));
+    /// assert_eq!(third.get(), Some(3));
+    /// ```
+    #[track_caller]
+    pub fn at(&self, index: usize) -> Computed<Option<T>> {
+        let this = *self;
+        memo(move || this.with(|items| items.get(index).cloned()))
+    }
+
+    /// Replaces the element at `index` through [`update`](Self::update).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set_at(&self, index: usize, value: T) {
+        self.update_recorded(|items| items[index] = value);
     }
 }
 
 pub struct SignalWriteGuard<'a, T> {
     node: NodeKey,
+    /// Resolved once the borrow is taken; the signal cannot be disposed
+    /// while it is held
+    value: *mut T,
     _marker: PhantomData<&'a mut T>,
 }
 
 impl<T> SignalWriteGuard<'_, T> {
+    #[track_caller]
     pub fn new(node: NodeKey) -> Self {
-        REACTIVE_SYSTEM.with(|ctx| {
-            ctx.signal_borrow_write_check(node);
+        let caller = caller();
+        let value = REACTIVE_SYSTEM.with(|ctx| {
+            ctx.signal_in_place_write_check(node, caller);
+            ctx.signal_borrow_write_check(node, caller);
+            ctx.signal_value(node)
         });
         Self {
             node,
+            value: value as *mut T,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Clone + 'static> SignalWriteGuard<'_, T> {
+    #[track_caller]
+    fn new_recorded(node: NodeKey) -> Self {
+        let caller = caller();
+        let value = REACTIVE_SYSTEM.with(|ctx| {
+            ctx.signal_borrow_write_check(node, caller);
+            ctx.signal_record_write::<T>(node);
+            ctx.signal_value(node)
+        });
+        Self {
+            node,
+            value: value as *mut T,
             _marker: PhantomData,
         }
     }
@@ -141,36 +264,39 @@ impl<T> Deref for SignalWriteGuard<'_, T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        // Check validity on every deref
-        let value = REACTIVE_SYSTEM.with(|ctx| ctx.signal_value(self.node));
-        unsafe { &*(value as *const T) }
+        unsafe { &*self.value }
     }
 }
 
 impl<T> DerefMut for SignalWriteGuard<'_, T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        // Check validity on every deref
-        let value = REACTIVE_SYSTEM.with(|ctx| ctx.signal_value(self.node));
-        unsafe { &mut *(value as *mut T) }
+        unsafe { &mut *self.value }
     }
 }
 
 pub struct SignalReadGuard<'a, T> {
     node: NodeKey,
+    /// Resolved once the borrow is taken; the signal cannot be disposed
+    /// while it is held
+    value: *const T,
     _marker: PhantomData<&'a T>,
 }
 
 impl<T> SignalReadGuard<'_, T> {
+    #[track_caller]
     pub fn new(node: NodeKey) -> Self {
-        REACTIVE_SYSTEM.with(|ctx| {
+        let caller = caller();
+        let value = REACTIVE_SYSTEM.with(|ctx| {
             // Check borrow state
-            ctx.signal_borrow_read_check(node);
+            ctx.signal_borrow_read_check(node, caller);
 
             // Track dependencies
             ctx.signal_track(node);
+            ctx.signal_value(node)
         });
         Self {
             node,
+            value: value as *const T,
             _marker: PhantomData,
         }
     }
@@ -188,8 +314,7 @@ impl<T> Deref for SignalReadGuard<'_, T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        let value = REACTIVE_SYSTEM.with(|ctx| ctx.signal_value(self.node));
-        unsafe { &*(value as *const T) }
+        unsafe { &*self.value }
     }
 }
 
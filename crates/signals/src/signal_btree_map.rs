@@ -0,0 +1,235 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::rc::Rc;
+
+use crate::computed::{Computed, computed};
+use crate::delta::{DeltaChannel, MapDelta, Subscription};
+use crate::effect::{end_batch, start_batch};
+use crate::scope::Owner;
+use crate::signal::{Signal, signal};
+
+struct BTreeInner<K, V> {
+    /// The scope the map was created in, which owns every subscription
+    owner: Owner,
+    entries: Rc<RefCell<BTreeMap<K, V>>>,
+    /// Each subscribed range and the signal notified when a key in it
+    /// changes
+    ranges: RefCell<Vec<(Range<K>, Signal<()>)>>,
+    /// Notified when the first entry changes
+    first_changed: Signal<()>,
+    /// Notified when the last entry changes
+    last_changed: Signal<()>,
+    first: Computed<Option<(K, V)>>,
+    last: Computed<Option<(K, V)>>,
+    deltas: DeltaChannel<MapDelta<K, V>>,
+}
+
+/// A reactive `BTreeMap` whose readers subscribe to ranges of keys.
+///
+/// A [`range_signal`](SignalBTreeMap::range_signal) only recomputes when a
+/// key inside its range is inserted or removed, which suits buffers of time
+/// series and other ordered data read a window at a time.
+/// [`first_signal`](SignalBTreeMap::first_signal) and
+/// [`last_signal`](SignalBTreeMap::last_signal) only notify when the entry at
+/// their end changes.
+///
+/// Every subscription is owned by the scope the map is created in, and kept
+/// until it is disposed, so create range subscriptions up front rather than
+/// on every run of an effect. Clones share the same map.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// let samples = signal_btree_map();
+/// let morning = samples.range_signal(6..12);
+/// samples.insert(8, 1.5);
+/// samples.insert(20, 3.0);
+/// assert_eq!(morning.get(), vec![(8, 1.5)]);
+/// assert_eq!(samples.last_signal().get(), Some((20, 3.0)));
+/// ```
+pub struct SignalBTreeMap<K, V> {
+    inner: Rc<BTreeInner<K, V>>,
+}
+
+impl<K, V> Clone for SignalBTreeMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+/// Creates an empty [`SignalBTreeMap`], owned by the current scope.
+#[track_caller]
+pub fn signal_btree_map<K, V>() -> SignalBTreeMap<K, V>
+where
+    K: Ord + Clone + 'static,
+    V: Clone + 'static,
+{
+    SignalBTreeMap::new()
+}
+
+impl<K, V> Default for SignalBTreeMap<K, V>
+where
+    K: Ord + Clone + 'static,
+    V: Clone + 'static,
+{
+    #[track_caller]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> SignalBTreeMap<K, V>
+where
+    K: Ord + Clone + 'static,
+    V: Clone + 'static,
+{
+    #[track_caller]
+    pub fn new() -> Self {
+        let entries = Rc::new(RefCell::new(BTreeMap::<K, V>::new()));
+        let (first_changed, last_changed) = (signal(()), signal(()));
+        let end = |last: bool| {
+            let entries = entries.clone();
+            let changed = if last { last_changed } else { first_changed };
+            computed(move |_| {
+                changed.track();
+                let entries = entries.borrow();
+                let entry = if last {
+                    entries.last_key_value()
+                } else {
+                    entries.first_key_value()
+                };
+                entry.map(|(key, value)| (key.clone(), value.clone()))
+            })
+        };
+        let (first, last) = (end(false), end(true));
+        Self {
+            inner: Rc::new(BTreeInner {
+                owner: Owner::current(),
+                entries,
+                ranges: RefCell::new(Vec::new()),
+                first_changed,
+                last_changed,
+                first,
+                last,
+                deltas: DeltaChannel::new(),
+            }),
+        }
+    }
+
+    /// Inserts `value` under `key`, returning the value it replaces.
+    ///
+    /// Notifies the ranges containing `key`, and either end if `key` is
+    /// there afterwards, in a single flush.
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        self.inner.deltas.record(|| MapDelta::Insert {
+            key: key.clone(),
+            value: value.clone(),
+        });
+        let old = self.inner.entries.borrow_mut().insert(key.clone(), value);
+        let ends = self.ends(&key);
+        self.notify(&key, ends);
+        old
+    }
+
+    /// Removes `key`, returning its value if it was present.
+    ///
+    /// Notifies the ranges containing `key`, and either end if `key` was
+    /// there, in a single flush.
+    pub fn remove(&self, key: &K) -> Option<V> {
+        let ends = self.ends(key);
+        let old = self.inner.entries.borrow_mut().remove(key);
+        if old.is_some() {
+            self.inner
+                .deltas
+                .record(|| MapDelta::Remove { key: key.clone() });
+            self.notify(key, ends);
+        }
+        old
+    }
+
+    /// The value under `key`, without tracking.
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.inner.entries.borrow().get(key).cloned()
+    }
+
+    /// A computed of the entries with keys in `range`, in order, which only
+    /// recomputes when a key inside `range` is inserted or removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the map's scope has been disposed.
+    pub fn range_signal(&self, range: Range<K>) -> Computed<Vec<(K, V)>> {
+        let inner = &self.inner;
+        let (changed, entries) = inner.owner.with(|| {
+            let changed = signal(());
+            let entries = inner.entries.clone();
+            let watched = range.clone();
+            let entries = computed(move |_| {
+                changed.track();
+                entries
+                    .borrow()
+                    .range(watched.clone())
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect()
+            });
+            (changed, entries)
+        });
+        inner.ranges.borrow_mut().push((range, changed));
+        entries
+    }
+
+    /// A computed of the entry with the smallest key.
+    pub fn first_signal(&self) -> Computed<Option<(K, V)>> {
+        self.inner.first
+    }
+
+    /// A computed of the entry with the largest key.
+    pub fn last_signal(&self) -> Computed<Option<(K, V)>> {
+        self.inner.last
+    }
+
+    /// Calls `f` after every flush with the insertions and removals made
+    /// since it was last called, in the order they were made.
+    ///
+    /// Changes made before subscribing, including earlier in the same
+    /// flush, are not delivered.
+    pub fn subscribe_deltas(&self, f: impl FnMut(&[MapDelta<K, V>]) + 'static) -> Subscription {
+        self.inner.deltas.subscribe(f)
+    }
+
+    /// Whether `key` is the first key, and whether it is the last
+    fn ends(&self, key: &K) -> (bool, bool) {
+        let entries = self.inner.entries.borrow();
+        (
+            entries
+                .first_key_value()
+                .is_some_and(|(first, _)| first == key),
+            entries
+                .last_key_value()
+                .is_some_and(|(last, _)| last == key),
+        )
+    }
+
+    /// Notify the subscriptions a write to `key` concerns
+    fn notify(&self, key: &K, (first, last): (bool, bool)) {
+        let changed: Vec<_> = (self.inner.ranges.borrow().iter())
+            .filter(|(range, _)| range.contains(key))
+            .map(|(_, changed)| *changed)
+            .collect();
+        start_batch();
+        for changed in changed {
+            changed.set(());
+        }
+        if first {
+            self.inner.first_changed.set(());
+        }
+        if last {
+            self.inner.last_changed.set(());
+        }
+        self.inner.deltas.notify();
+        end_batch();
+    }
+}
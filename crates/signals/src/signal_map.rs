@@ -0,0 +1,251 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::rc::Rc;
+
+use crate::computed::{Computed, computed, memo};
+use crate::delta::{DeltaChannel, MapDelta, Subscription};
+use crate::effect::{end_batch, start_batch};
+use crate::scope::Owner;
+use crate::signal::{Signal, signal};
+
+/// The reactive nodes watching one key of a [`SignalMap`]
+struct KeyView<V> {
+    /// Notified whenever the key is inserted, replaced or removed
+    changed: Signal<()>,
+    value: Computed<Option<V>>,
+    contains: Option<Computed<bool>>,
+}
+
+struct MapInner<K, V> {
+    /// The scope the map was created in, which owns every per-key node
+    owner: Owner,
+    entries: Rc<RefCell<HashMap<K, V>>>,
+    /// Notified only when a key is added or removed
+    key_set: Signal<()>,
+    keys: Computed<Vec<K>>,
+    len: Computed<usize>,
+    views: RefCell<HashMap<K, KeyView<V>>>,
+    deltas: DeltaChannel<MapDelta<K, V>>,
+}
+
+/// A reactive `HashMap` whose readers subscribe to single keys.
+///
+/// Reading a key through [`get_signal`](SignalMap::get_signal) or
+/// [`contains_key_signal`](SignalMap::contains_key_signal) depends on that
+/// key alone, so writing other keys never re-runs it, and
+/// [`keys_signal`](SignalMap::keys_signal) only notifies when keys come and
+/// go. The per-key nodes are created the first time a key is watched and
+/// kept for the map's lifetime, even after the key is removed, so it can be
+/// watched again.
+///
+/// All nodes are owned by the scope the map is created in, and disposed with
+/// it. Clones share the same map.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// let users = signal_map();
+/// users.insert(1, "ada");
+/// let first = users.get_signal(&1);
+/// let second = users.get_signal(&2);
+///
+/// users.insert(2, "grace");
+/// assert_eq!(first.get(), Some("ada"));
+/// assert_eq!(second.get(), Some("grace"));
+/// assert_eq!(users.remove(&1), Some("ada"));
+/// assert_eq!(first.get(), None);
+/// ```
+pub struct SignalMap<K, V> {
+    inner: Rc<MapInner<K, V>>,
+}
+
+impl<K, V> Clone for SignalMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+/// Creates an empty [`SignalMap`], owned by the current scope.
+#[track_caller]
+pub fn signal_map<K, V>() -> SignalMap<K, V>
+where
+    K: Eq + Hash + Clone + 'static,
+    V: Clone + 'static,
+{
+    SignalMap::new()
+}
+
+impl<K, V> Default for SignalMap<K, V>
+where
+    K: Eq + Hash + Clone + 'static,
+    V: Clone + 'static,
+{
+    #[track_caller]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> SignalMap<K, V>
+where
+    K: Eq + Hash + Clone + 'static,
+    V: Clone + 'static,
+{
+    #[track_caller]
+    pub fn new() -> Self {
+        let entries = Rc::new(RefCell::new(HashMap::new()));
+        let key_set = signal(());
+        let keys = computed({
+            let entries = entries.clone();
+            move |_| {
+                key_set.track();
+                entries.borrow().keys().cloned().collect()
+            }
+        });
+        let len = memo({
+            let entries = entries.clone();
+            move || {
+                key_set.track();
+                entries.borrow().len()
+            }
+        });
+        Self {
+            inner: Rc::new(MapInner {
+                owner: Owner::current(),
+                entries,
+                key_set,
+                keys,
+                len,
+                views: RefCell::new(HashMap::new()),
+                deltas: DeltaChannel::new(),
+            }),
+        }
+    }
+
+    /// Inserts `value` under `key`, returning the value it replaces.
+    ///
+    /// Notifies the readers of `key`, and those of the key set if `key` is
+    /// new, in a single flush.
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        let deltas = &self.inner.deltas;
+        deltas.record(|| MapDelta::Insert {
+            key: key.clone(),
+            value: value.clone(),
+        });
+        let old = self.inner.entries.borrow_mut().insert(key.clone(), value);
+        start_batch();
+        if old.is_none() {
+            self.inner.key_set.set(());
+        }
+        self.notify(&key);
+        deltas.notify();
+        end_batch();
+        old
+    }
+
+    /// Removes `key`, returning its value if it was present.
+    ///
+    /// Notifies the readers of `key` and of the key set, unless the key was
+    /// missing.
+    pub fn remove(&self, key: &K) -> Option<V> {
+        let old = self.inner.entries.borrow_mut().remove(key);
+        if old.is_some() {
+            let deltas = &self.inner.deltas;
+            deltas.record(|| MapDelta::Remove { key: key.clone() });
+            start_batch();
+            self.inner.key_set.set(());
+            self.notify(key);
+            deltas.notify();
+            end_batch();
+        }
+        old
+    }
+
+    /// Whether `key` is present, without tracking.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.inner.entries.borrow().contains_key(key)
+    }
+
+    /// A computed of the value under `key`, depending on that key alone.
+    ///
+    /// Created the first time `key` is asked for and cached afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the map's scope has been disposed.
+    pub fn get_signal(&self, key: &K) -> Computed<Option<V>> {
+        self.with_view(key, |_, view| view.value)
+    }
+
+    /// A computed of whether `key` is present, which only notifies when that
+    /// changes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the map's scope has been disposed.
+    pub fn contains_key_signal(&self, key: &K) -> Computed<bool> {
+        self.with_view(key, |owner, view| {
+            let value = view.value;
+            *view
+                .contains
+                .get_or_insert_with(|| owner.with(|| memo(move || value.read().is_some())))
+        })
+    }
+
+    /// A computed of the keys, in no particular order, which only notifies
+    /// when a key is added or removed.
+    pub fn keys_signal(&self) -> Computed<Vec<K>> {
+        self.inner.keys
+    }
+
+    /// A computed of the number of entries, which only notifies when it
+    /// changes.
+    pub fn len_signal(&self) -> Computed<usize> {
+        self.inner.len
+    }
+
+    /// Calls `f` after every flush with the insertions and removals made
+    /// since it was last called, in the order they were made.
+    ///
+    /// Changes made before subscribing, including earlier in the same
+    /// flush, are not delivered.
+    pub fn subscribe_deltas(&self, f: impl FnMut(&[MapDelta<K, V>]) + 'static) -> Subscription {
+        self.inner.deltas.subscribe(f)
+    }
+
+    /// Calls `f` with the view of `key`, creating it under the map's owner
+    /// the first time
+    fn with_view<O>(&self, key: &K, f: impl FnOnce(&Owner, &mut KeyView<V>) -> O) -> O {
+        let inner = &self.inner;
+        let mut views = inner.views.borrow_mut();
+        if !views.contains_key(key) {
+            let view = inner.owner.with(|| {
+                let changed = signal(());
+                let entries = inner.entries.clone();
+                let watched = key.clone();
+                let value = computed(move |_| {
+                    changed.track();
+                    entries.borrow().get(&watched).cloned()
+                });
+                KeyView {
+                    changed,
+                    value,
+                    contains: None,
+                }
+            });
+            views.insert(key.clone(), view);
+        }
+        f(&inner.owner, views.get_mut(key).unwrap())
+    }
+
+    /// Notify the readers of `key`, if anyone watches it
+    fn notify(&self, key: &K) {
+        let changed = self.inner.views.borrow().get(key).map(|view| view.changed);
+        if let Some(changed) = changed {
+            changed.set(());
+        }
+    }
+}
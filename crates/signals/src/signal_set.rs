@@ -0,0 +1,113 @@
+use std::hash::Hash;
+
+use crate::computed::Computed;
+use crate::delta::{MapDelta, SetDelta, Subscription};
+use crate::signal_map::SignalMap;
+
+/// A reactive `HashSet` whose readers subscribe to single members.
+///
+/// [`contains_signal`](SignalSet::contains_signal) only notifies when the
+/// membership of its member changes, so toggling one member re-runs only
+/// the readers of that member. It is a [`SignalMap`] without values: the
+/// per-member nodes are created the first time a member is watched, cached,
+/// and owned by the scope the set is created in. Clones share the same set.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// let selected = signal_set();
+/// let first = selected.contains_signal(&1);
+/// assert!(selected.toggle(1));
+/// assert!(first.get());
+/// assert_eq!(selected.len_signal().get(), 1);
+/// ```
+pub struct SignalSet<T> {
+    members: SignalMap<T, ()>,
+}
+
+impl<T> Clone for SignalSet<T> {
+    fn clone(&self) -> Self {
+        Self {
+            members: self.members.clone(),
+        }
+    }
+}
+
+/// Creates an empty [`SignalSet`], owned by the current scope.
+#[track_caller]
+pub fn signal_set<T: Eq + Hash + Clone + 'static>() -> SignalSet<T> {
+    SignalSet::new()
+}
+
+impl<T: Eq + Hash + Clone + 'static> Default for SignalSet<T> {
+    #[track_caller]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Eq + Hash + Clone + 'static> SignalSet<T> {
+    #[track_caller]
+    pub fn new() -> Self {
+        Self {
+            members: SignalMap::new(),
+        }
+    }
+
+    /// Adds `member`, returning whether it was missing.
+    pub fn insert(&self, member: T) -> bool {
+        if self.contains(&member) {
+            return false;
+        }
+        self.members.insert(member, ());
+        true
+    }
+
+    /// Removes `member`, returning whether it was present.
+    pub fn remove(&self, member: &T) -> bool {
+        self.members.remove(member).is_some()
+    }
+
+    /// Adds `member` if it is missing and removes it otherwise, returning
+    /// whether it is present afterwards.
+    pub fn toggle(&self, member: T) -> bool {
+        !self.remove(&member) && self.insert(member)
+    }
+
+    /// Whether `member` is present, without tracking.
+    pub fn contains(&self, member: &T) -> bool {
+        self.members.contains_key(member)
+    }
+
+    /// A memo of whether `member` is present, depending on that member alone.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the set's scope has been disposed.
+    pub fn contains_signal(&self, member: &T) -> Computed<bool> {
+        self.members.contains_key_signal(member)
+    }
+
+    /// Calls `f` after every flush with the members added and removed since
+    /// it was last called, in the order they were.
+    ///
+    /// Changes made before subscribing, including earlier in the same
+    /// flush, are not delivered.
+    pub fn subscribe_deltas(&self, mut f: impl FnMut(&[SetDelta<T>]) + 'static) -> Subscription {
+        self.members.subscribe_deltas(move |deltas| {
+            let deltas: Vec<_> = (deltas.iter())
+                .map(|delta| match delta {
+                    MapDelta::Insert { key, .. } => SetDelta::Insert(key.clone()),
+                    MapDelta::Remove { key } => SetDelta::Remove(key.clone()),
+                })
+                .collect();
+            f(&deltas);
+        })
+    }
+
+    /// A computed of the number of members, which only notifies when it
+    /// changes.
+    pub fn len_signal(&self) -> Computed<usize> {
+        self.members.len_signal()
+    }
+}
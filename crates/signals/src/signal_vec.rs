@@ -0,0 +1,326 @@
+use std::ops::Range;
+
+use crate::computed::{Computed, memo};
+use crate::delta::{DeltaChannel, Subscription};
+use crate::signal::{Signal, signal};
+
+/// A change made to a [`SignalVec`], as delivered to the callbacks passed to
+/// [`subscribe_deltas`](SignalVec::subscribe_deltas).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VecDelta<T> {
+    /// `value` was inserted at `index`, shifting later items up.
+    Insert { index: usize, value: T },
+    /// The item at `index` was removed, shifting later items down.
+    Remove { index: usize },
+    /// The item at `index` was replaced by `value`.
+    Set { index: usize, value: T },
+    /// `removed` items starting at `index` were replaced by `inserted`.
+    Splice {
+        index: usize,
+        removed: usize,
+        inserted: Vec<T>,
+    },
+    /// Every item was removed.
+    Clear,
+}
+
+/// A reactive vector that reports what changed instead of only that
+/// something did.
+///
+/// The items live in a single signal written in place, so changing one does
+/// not clone the others. Readers that care about the whole vector track it
+/// with [`with`](SignalVec::with) or [`iter_tracked`](SignalVec::iter_tracked);
+/// [`len_signal`](SignalVec::len_signal) only notifies when the length
+/// changes; and callbacks passed to
+/// [`subscribe_deltas`](SignalVec::subscribe_deltas)
+/// receive the individual operations, batched per flush, to patch their own
+/// state, e.g. a keyed list renderer.
+///
+/// The vector's nodes, including the effect delivering deltas, are owned by
+/// the scope it is created in. Clones share the same vector.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// # use std::{cell::RefCell, rc::Rc};
+/// let todos = signal_vec(vec!["write"]);
+/// let seen = Rc::new(RefCell::new(Vec::new()));
+/// todos.subscribe_deltas({
+///     let seen = seen.clone();
+///     move |deltas| seen.borrow_mut().extend_from_slice(deltas)
+/// });
+///
+/// start_batch();
+/// todos.push("test");
+/// todos.set(0, "review");
+/// end_batch();
+///
+/// assert_eq!(
+///     *seen.borrow(),
+///     vec![
+///         VecDelta::Insert { index: 1, value: "test" },
+///         VecDelta::Set { index: 0, value: "review" },
+///     ]
+/// );
+/// assert_eq!(todos.len_signal().get(), 2);
+/// ```
+pub struct SignalVec<T> {
+    items: Signal<Vec<T>>,
+    len: Computed<usize>,
+    deltas: DeltaChannel<VecDelta<T>>,
+}
+
+impl<T> Clone for SignalVec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            items: self.items,
+            len: self.len,
+            deltas: self.deltas.clone(),
+        }
+    }
+}
+
+/// Creates a [`SignalVec`] holding `initial`, owned by the current scope.
+#[track_caller]
+pub fn signal_vec<T: Clone + 'static>(initial: Vec<T>) -> SignalVec<T> {
+    SignalVec::new(initial)
+}
+
+impl<T: Clone + 'static> SignalVec<T> {
+    #[track_caller]
+    pub fn new(initial: Vec<T>) -> Self {
+        let items = signal(initial);
+        let len = memo(move || items.with(Vec::len));
+        let deltas = DeltaChannel::new();
+        Self { items, len, deltas }
+    }
+
+    /// Appends `value` to the end of the vector.
+    #[track_caller]
+    pub fn push(&self, value: T) {
+        {
+            let mut items = self.items.write_recorded();
+            items.push(value);
+            let index = items.len() - 1;
+            self.record(|| VecDelta::Insert {
+                index,
+                value: items[index].clone(),
+            });
+        }
+        self.deltas.notify();
+    }
+
+    /// Inserts `value` at `index`, shifting later items up.
+    ///
+    /// Panics if `index` is greater than the length.
+    #[track_caller]
+    pub fn insert(&self, index: usize, value: T) {
+        {
+            let mut items = self.items.write_recorded();
+            items.insert(index, value);
+            self.record(|| VecDelta::Insert {
+                index,
+                value: items[index].clone(),
+            });
+        }
+        self.deltas.notify();
+    }
+
+    /// Removes and returns the item at `index`, shifting later items down.
+    ///
+    /// Panics if `index` is out of bounds.
+    #[track_caller]
+    pub fn remove(&self, index: usize) -> T {
+        let removed = {
+            let mut items = self.items.write_recorded();
+            let removed = items.remove(index);
+            self.record(|| VecDelta::Remove { index });
+            removed
+        };
+        self.deltas.notify();
+        removed
+    }
+
+    /// Replaces the item at `index` with `value`, returning the old one.
+    ///
+    /// Panics if `index` is out of bounds.
+    #[track_caller]
+    pub fn set(&self, index: usize, value: T) -> T {
+        let old = {
+            let mut items = self.items.write_recorded();
+            let old = std::mem::replace(&mut items[index], value);
+            self.record(|| VecDelta::Set {
+                index,
+                value: items[index].clone(),
+            });
+            old
+        };
+        self.deltas.notify();
+        old
+    }
+
+    /// Appends every item of `items`, notifying once and recording a single
+    /// [`Splice`](VecDelta::Splice).
+    #[track_caller]
+    pub fn extend(&self, items: impl IntoIterator<Item = T>) {
+        let len = self.items.peek().len();
+        self.splice(len..len, items);
+    }
+
+    /// Removes the items for which `keep` returns false, notifying once.
+    ///
+    /// Each run of removed items is recorded as one delta, from the last
+    /// run to the first, so every index refers to the vector as it was.
+    #[track_caller]
+    pub fn retain(&self, mut keep: impl FnMut(&T) -> bool) {
+        {
+            let mut items = self.items.write_recorded();
+            let kept: Vec<bool> = items.iter().map(&mut keep).collect();
+            let mut kept_iter = kept.iter();
+            items.retain(|_| *kept_iter.next().unwrap());
+
+            let mut end = kept.len();
+            while let Some(last) = kept[..end].iter().rposition(|kept| !kept) {
+                let first = kept[..last]
+                    .iter()
+                    .rposition(|kept| *kept)
+                    .map_or(0, |kept| kept + 1);
+                self.record(|| match last - first {
+                    0 => VecDelta::Remove { index: first },
+                    run => VecDelta::Splice {
+                        index: first,
+                        removed: run + 1,
+                        inserted: Vec::new(),
+                    },
+                });
+                end = first;
+            }
+        }
+        self.deltas.notify();
+    }
+
+    /// Replaces the items in `range` with `replace_with`, returning the
+    /// removed ones, notifying once and recording a single
+    /// [`Splice`](VecDelta::Splice).
+    ///
+    /// Panics if `range` is out of bounds.
+    #[track_caller]
+    pub fn splice(&self, range: Range<usize>, replace_with: impl IntoIterator<Item = T>) -> Vec<T> {
+        let removed = {
+            let mut items = self.items.write_recorded();
+            let (index, len) = (range.start, items.len());
+            let removed: Vec<T> = items.splice(range, replace_with).collect();
+            let inserted = items.len() + removed.len() - len;
+            if removed.len() + inserted > 0 {
+                self.record(|| VecDelta::Splice {
+                    index,
+                    removed: removed.len(),
+                    inserted: items[index..index + inserted].to_vec(),
+                });
+            }
+            removed
+        };
+        self.deltas.notify();
+        removed
+    }
+
+    /// Removes every item.
+    #[track_caller]
+    pub fn clear(&self) {
+        {
+            let mut items = self.items.write_recorded();
+            items.clear();
+            self.record(|| VecDelta::Clear);
+        }
+        self.deltas.notify();
+    }
+
+    /// A computed of the length, which only notifies when the length changes.
+    pub fn len_signal(&self) -> Computed<usize> {
+        self.len
+    }
+
+    /// Calls `f` with the items, tracking every change to them.
+    #[track_caller]
+    pub fn with<O>(&self, f: impl FnOnce(&[T]) -> O) -> O {
+        self.items.with(|items| f(items))
+    }
+
+    /// Tracks every change to the vector and iterates over its items.
+    ///
+    /// Items are cloned one at a time as the iterator advances, and reflect
+    /// the vector at that moment.
+    pub fn iter_tracked(&self) -> impl Iterator<Item = T> + use<T> {
+        let items = self.items;
+        items.track();
+        (0..).map_while(move |index| items.peek().get(index).cloned())
+    }
+
+    /// Calls `f` after every flush with the changes made since it was last
+    /// called, in the order they were made.
+    ///
+    /// Changes made before subscribing, including earlier in the same
+    /// flush, are not delivered.
+    pub fn subscribe_deltas(&self, f: impl FnMut(&[VecDelta<T>]) + 'static) -> Subscription {
+        self.deltas.subscribe(f)
+    }
+
+    /// Creates a vector of `f` applied to every item, kept in sync one
+    /// delta at a time.
+    ///
+    /// `f` runs once per item up front, then only for inserted and replaced
+    /// items. Replacing an item whose mapped value is unchanged does not
+    /// touch the output. The output is owned by the current scope and
+    /// catches up once per flush, when the deltas are delivered.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use samara_signals::*;
+    /// let words = signal_vec(vec!["a", "bb"]);
+    /// let lengths = words.map_memo(|word| word.len());
+    /// words.push("ccc");
+    /// words.set(0, "d"); // Same length: the output is left alone
+    /// assert_eq!(lengths.with(|lengths| lengths.to_vec()), vec![1, 2, 3]);
+    /// ```
+    #[track_caller]
+    pub fn map_memo<U: Clone + PartialEq + 'static>(
+        &self,
+        f: impl Fn(&T) -> U + 'static,
+    ) -> SignalVec<U> {
+        let output = SignalVec::new(self.items.peek().iter().map(&f).collect());
+        let target = output.clone();
+        self.subscribe_deltas(move |deltas| {
+            for delta in deltas {
+                match delta {
+                    VecDelta::Insert { index, value } => target.insert(*index, f(value)),
+                    VecDelta::Remove { index } => {
+                        target.remove(*index);
+                    }
+                    VecDelta::Set { index, value } => {
+                        let mapped = f(value);
+                        if target.items.peek()[*index] != mapped {
+                            target.set(*index, mapped);
+                        }
+                    }
+                    VecDelta::Splice {
+                        index,
+                        removed,
+                        inserted,
+                    } => {
+                        target.splice(*index..index + removed, inserted.iter().map(&f));
+                    }
+                    VecDelta::Clear => target.clear(),
+                }
+            }
+        });
+        output
+    }
+
+    /// Queue a delta for the subscribers, if there are any
+    ///
+    /// Called while the items are still borrowed for writing, to build the
+    /// delta from them; delivery is only scheduled once they are released.
+    fn record(&self, delta: impl FnOnce() -> VecDelta<T>) {
+        self.deltas.record(delta);
+    }
+}
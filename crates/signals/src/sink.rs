@@ -0,0 +1,87 @@
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_util::Sink;
+
+use crate::runtime::REACTIVE_SYSTEM;
+use crate::signal::Signal;
+
+/// Error returned by [`Signal::sink`] once the signal has been disposed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Disposed;
+
+impl fmt::Display for Disposed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("signal disposed")
+    }
+}
+
+impl std::error::Error for Disposed {}
+
+struct SignalSink<T> {
+    signal: Signal<T>,
+}
+
+impl<T: 'static> SignalSink<T> {
+    fn ready(&self) -> Result<(), Disposed> {
+        let node = self.signal.node();
+        if REACTIVE_SYSTEM.with(|ctx| ctx.contains_node(node)) {
+            Ok(())
+        } else {
+            Err(Disposed)
+        }
+    }
+}
+
+impl<T: 'static> Sink<T> for SignalSink<T> {
+    type Error = Disposed;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Disposed>> {
+        Poll::Ready(self.ready())
+    }
+
+    fn start_send(self: Pin<&mut Self>, value: T) -> Result<(), Disposed> {
+        self.ready()?;
+        self.signal.set(value);
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Disposed>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Disposed>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T: 'static> Signal<T> {
+    /// A [`Sink`] that sets the signal to every value sent into it, so
+    /// streams can be forwarded into signals with `futures_util`
+    /// combinators.
+    ///
+    /// The sink is always ready, and each value is set as it is sent, like
+    /// [`set`](Signal::set). Once the signal is disposed, sending yields
+    /// [`Disposed`] instead of panicking. Like the signal itself, the sink
+    /// is bound to the thread that created it, so drive it with a task
+    /// [`spawn`](crate::spawn)ed on the same thread.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use samara_signals::*;
+    /// # use futures_util::StreamExt;
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let latest = signal(0);
+    /// spawn(async move {
+    ///     let values = futures_util::stream::iter([1, 2, 3]).map(Ok);
+    ///     values.forward(latest.sink()).await.unwrap();
+    /// });
+    /// join().await;
+    /// assert_eq!(latest.get(), 3);
+    /// # });
+    /// ```
+    pub fn sink(&self) -> impl Sink<T, Error = Disposed> + use<T> {
+        SignalSink { signal: *self }
+    }
+}
@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::runtime::REACTIVE_SYSTEM;
+use crate::types::{LinkKey, NodeKey};
+
+/// The kind of a node in a [`GraphSnapshot`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NodeKind {
+    /// A scope, including the root scope.
+    Scope,
+    Signal,
+    Computed,
+    Effect,
+}
+
+/// A node of a [`GraphSnapshot`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeInfo {
+    pub key: NodeKey,
+    pub kind: NodeKind,
+    /// Names of the node's set flag bits, such as `"DIRTY"`.
+    pub flags: Vec<String>,
+    /// Where the node was created as `file:line:col`; `None` in release
+    /// builds, which do not record locations.
+    pub location: Option<String>,
+    pub name: Option<String>,
+    /// The owning scope or effect; `None` for the root scope.
+    pub parent: Option<NodeKey>,
+    /// Number of nodes depending on this one.
+    pub subscribers: usize,
+    /// Number of nodes this one depends on.
+    pub dependencies: usize,
+}
+
+/// A dependency edge of a [`GraphSnapshot`]: `sub` depends on `dep`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkInfo {
+    pub key: LinkKey,
+    pub dep: NodeKey,
+    pub sub: NodeKey,
+}
+
+/// A self-describing copy of the reactive graph's topology, for tooling.
+///
+/// Unlike [`serialize`](crate::serialize), which dumps the internal
+/// structures as they are, every node and link here is a plain record with
+/// named fields. Values and closures are not included.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphSnapshot {
+    /// The root scope.
+    pub root: NodeKey,
+    pub nodes: Vec<NodeInfo>,
+    pub links: Vec<LinkInfo>,
+}
+
+impl GraphSnapshot {
+    /// Returns the node with the given key.
+    pub fn node(&self, key: NodeKey) -> Option<&NodeInfo> {
+        self.nodes.iter().find(|node| node.key == key)
+    }
+}
+
+/// Takes a [`GraphSnapshot`] of the current thread's reactive graph.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// let a = signal(1);
+/// let doubled = memo(move || a.get() * 2);
+/// doubled.get();
+///
+/// let snapshot = snapshot();
+/// let link = &snapshot.links[0];
+/// assert_eq!(snapshot.node(link.dep).unwrap().kind, NodeKind::Signal);
+/// assert_eq!(snapshot.node(link.sub).unwrap().kind, NodeKind::Computed);
+/// println!("{}", serde_json::to_string_pretty(&snapshot).unwrap());
+/// ```
+pub fn snapshot() -> GraphSnapshot {
+    REACTIVE_SYSTEM.with(|ctx| ctx.snapshot())
+}
+
+/// Rebuilds the topology of `snapshot` in the current thread's reactive
+/// system, for replaying graphs captured elsewhere in tests and tooling.
+///
+/// Values and closures cannot be restored, so every node except the root
+/// becomes an inert placeholder scope; [`snapshot`] still reports the kind,
+/// flags, name and location it was restored from. Nodes and links are added
+/// under the current root with new keys: the returned map gives the new key
+/// of each node by its key in `snapshot`.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// let a = signal(1);
+/// let doubled = memo(move || a.get() * 2);
+/// doubled.get();
+/// let captured = snapshot();
+///
+/// std::thread::spawn(move || {
+///     let keys = restore_topology(&captured);
+///     let restored = snapshot();
+///     assert_eq!(restored.node(keys[&captured.links[0].sub]).unwrap().kind, NodeKind::Computed);
+/// })
+/// .join()
+/// .unwrap();
+/// ```
+pub fn restore_topology(snapshot: &GraphSnapshot) -> HashMap<NodeKey, NodeKey> {
+    REACTIVE_SYSTEM.with(|ctx| ctx.restore_topology(snapshot))
+}
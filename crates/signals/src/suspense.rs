@@ -0,0 +1,108 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::computed::{Computed, computed};
+use crate::context::{provide_context, use_context};
+use crate::effect::on_cleanup;
+use crate::signal::{Signal, signal};
+
+/// The pending counters of the enclosing suspense boundaries, innermost
+/// first.
+#[derive(Clone)]
+struct SuspenseBoundaries(Rc<[Signal<usize>]>);
+
+/// Installs a suspense boundary in the current scope.
+///
+/// Resources created beneath it count as pending while they fetch, see
+/// [`pending_count`] and [`use_suspense`]. Boundaries nest: a resource
+/// counts toward every boundary it is inside of, not just the innermost.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// scope(|| {
+///     provide_suspense();
+///     let pending = use_suspense();
+///     let _data = resource(|| async { 1 });
+///     assert!(pending.get());
+/// });
+/// # });
+/// ```
+pub fn provide_suspense() {
+    let counter = signal(0usize);
+    let boundaries = match use_context::<SuspenseBoundaries>() {
+        Some(SuspenseBoundaries(outer)) => std::iter::once(counter)
+            .chain(outer.iter().copied())
+            .collect(),
+        None => Rc::from([counter]),
+    };
+    provide_context(SuspenseBoundaries(boundaries));
+}
+
+/// Returns the number of resources fetching under the nearest suspense
+/// boundary, always `0` outside of one.
+#[track_caller]
+pub fn pending_count() -> Computed<usize> {
+    let counter = use_context::<SuspenseBoundaries>().map(|boundaries| boundaries.0[0]);
+    computed(move |_| counter.map_or(0, |counter| counter.get()))
+}
+
+/// Returns whether any resource under the nearest suspense boundary is
+/// fetching.
+#[track_caller]
+pub fn use_suspense() -> Computed<bool> {
+    let count = pending_count();
+    computed(move |_| count.get() > 0)
+}
+
+/// Counts a single resource toward the suspense boundaries it was created
+/// under.
+pub(crate) struct PendingTracker {
+    boundaries: Option<SuspenseBoundaries>,
+    counted: Cell<bool>,
+}
+
+impl PendingTracker {
+    /// Creates a tracker for the current scope. A fetch still pending when
+    /// the scope is disposed stops counting.
+    pub(crate) fn current() -> Rc<Self> {
+        let tracker = Rc::new(PendingTracker {
+            boundaries: use_context::<SuspenseBoundaries>(),
+            counted: Cell::new(false),
+        });
+        if tracker.boundaries.is_some() {
+            let tracker = tracker.clone();
+            on_cleanup(move || tracker.end());
+        }
+        tracker
+    }
+
+    /// Marks the resource as pending; a no-op if it already is.
+    pub(crate) fn begin(&self) {
+        self.adjust(true);
+    }
+
+    /// Marks the resource as settled; a no-op if it already is.
+    pub(crate) fn end(&self) {
+        self.adjust(false);
+    }
+
+    fn adjust(&self, pending: bool) {
+        let Some(SuspenseBoundaries(boundaries)) = &self.boundaries else {
+            return;
+        };
+        if self.counted.replace(pending) == pending {
+            return;
+        }
+        for counter in boundaries.iter() {
+            counter.update_recorded(|count| {
+                if pending {
+                    *count += 1;
+                } else {
+                    *count -= 1;
+                }
+            });
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use crate::cancel::CancellationToken;
+use crate::observer::RuntimeObserver;
 use crate::types::{
     Link, LinkKey, NodeInner, NodeKey, ReactiveFlags, ReactiveNode, UnsafeBox, UnsafeSlotMap,
     caller,
@@ -7,27 +9,85 @@ use slotmap::SparseSecondaryMap;
 use std::{cell::Cell, collections::HashMap, rc::Rc};
 
 mod batching;
+mod cancel;
 mod computed;
 mod context;
+#[cfg(debug_assertions)]
+mod diagnostics;
 mod effect;
 mod lifecycle;
 mod links;
+mod observer;
 mod propagation;
+mod queue;
 mod signal;
+mod snapshot;
+#[cfg(debug_assertions)]
+mod tombstone;
+mod transaction;
+mod validate;
+
+pub use batching::{
+    BatchContext, CapacityStats, CompactReport, CountStats, FlushCallback, FlushHooks, FlushStats,
+    MapCapacity, SlotUsage,
+};
+pub use context::{ContextCache, KeyedContexts};
+pub use queue::{EffectQueue, LinkStack};
+pub use snapshot::Placeholder;
+#[cfg(debug_assertions)]
+pub use tombstone::{Disposal, Tombstones};
+pub use transaction::TransactionLog;
 
 type NodeMap = UnsafeSlotMap<NodeKey, ReactiveNode>;
 type LinkMap = UnsafeSlotMap<LinkKey, Link>;
 
 #[derive(Default, Serialize)]
 pub struct ReactiveSystem {
-    pub cycle: usize,
+    /// Bumped by every computed and effect run, to tell the links it
+    /// tracks apart from those left over from earlier runs
+    pub cycle: u32,
+    /// Bumped by every `propagate`, so unwatched computeds can tell whether
+    /// anything was written since they last checked their dependencies
+    pub propagations: u64,
+    /// Bumped once per propagation; nodes store it when reached, so a
+    /// propagation tells the nodes it already visited by comparing it
+    pub epoch: u64,
     pub batch_depth: usize,
-    pub notify_index: usize,
-    pub queued_length: usize,
     #[serde(skip)]
-    pub queued: Vec<NodeKey>,
+    pub flushing: bool,
+    #[serde(skip)]
+    pub auto_batch: bool,
+    #[serde(skip)]
+    pub flush_stats: FlushStats,
+    #[serde(skip)]
+    pub last_flush_stats: Option<FlushStats>,
+    #[serde(skip)]
+    pub on_flush: Option<FlushCallback>,
+    #[serde(skip)]
+    pub flush_hooks: FlushHooks,
+    #[serde(skip)]
+    pub observer: Option<Rc<dyn RuntimeObserver>>,
+    /// Buffers of the streams returned by `devtools_events`
+    #[cfg(feature = "devtools")]
+    #[serde(skip)]
+    pub devtools: Vec<std::rc::Weak<crate::devtools::DevtoolsBuffer>>,
+    /// Set while an observer callback runs, to catch callbacks that re-enter
+    #[cfg(debug_assertions)]
+    #[serde(skip)]
+    pub observing: Cell<bool>,
+    #[serde(skip)]
+    pub queue: EffectQueue,
+    #[serde(skip)]
+    pub stack: LinkStack,
+    /// Computeds whose getters are running, outermost first
+    #[cfg(debug_assertions)]
+    #[serde(skip)]
+    pub evaluating: Vec<NodeKey>,
+    /// Nodes `check_dirty` has descended into, innermost last; nested
+    /// checks stack theirs on top, so the allocation is reused
+    #[cfg(debug_assertions)]
     #[serde(skip)]
-    pub stack: Vec<LinkKey>,
+    pub checking: Vec<NodeKey>,
     pub root: NodeKey,
     #[serde(skip)]
     pub active_sub: Cell<Option<NodeKey>>,
@@ -37,14 +97,51 @@ pub struct ReactiveSystem {
     pub links: LinkMap,
     #[serde(skip)]
     pub cleanups: SparseSecondaryMap<NodeKey, Vec<Box<dyn FnOnce()>>>,
+    /// Cleanups that spawn async teardown, run after the synchronous ones
+    #[serde(skip)]
+    pub async_cleanups: SparseSecondaryMap<NodeKey, Vec<Box<dyn FnOnce()>>>,
+    #[serde(skip)]
+    pub transactions: Vec<TransactionLog>,
     #[serde(skip)]
     pub contexts: SparseSecondaryMap<NodeKey, HashMap<std::any::TypeId, Rc<dyn std::any::Any>>>,
+    /// Cached `use_context` lookups by the scope they were made from
+    #[serde(skip)]
+    pub context_cache: SparseSecondaryMap<NodeKey, ContextCache>,
+    /// Bumped whenever a context is provided or a scope moves,
+    /// invalidating `context_cache`
+    #[serde(skip)]
+    pub context_generation: u64,
+    #[serde(skip)]
+    pub keyed_contexts: SparseSecondaryMap<NodeKey, KeyedContexts>,
+    #[serde(skip)]
+    pub cancel_tokens: SparseSecondaryMap<NodeKey, CancellationToken>,
+    /// The scope being disposed, to attribute removed nodes to
+    #[cfg(debug_assertions)]
+    #[serde(skip)]
+    pub disposal: Option<Disposal>,
+    /// Recently removed nodes, to explain uses of stale handles
+    #[cfg(debug_assertions)]
+    #[serde(skip)]
+    pub tombstones: Tombstones,
+    /// The `propagations` count at which each unwatched computed last
+    /// checked its dependencies
+    #[serde(skip)]
+    pub checked_at: SparseSecondaryMap<NodeKey, u64>,
+    /// Kinds and locations of nodes rebuilt by `restore_topology`
+    #[serde(skip)]
+    pub placeholders: SparseSecondaryMap<NodeKey, Placeholder>,
 }
 
 impl ReactiveSystem {
     pub fn new() -> Self {
-        let mut nodes: NodeMap = Default::default();
-        let links: LinkMap = Default::default();
+        Self::with_capacity(0, 0)
+    }
+
+    /// Create a system with room for `nodes` nodes, besides the root, and
+    /// `links` links before it has to grow
+    pub fn with_capacity(nodes: usize, links: usize) -> Self {
+        let mut nodes = NodeMap::with_capacity(nodes + 1);
+        let links = LinkMap::with_capacity(links);
         let cleanups = SparseSecondaryMap::new();
         let contexts = SparseSecondaryMap::new();
 
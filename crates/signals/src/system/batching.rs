@@ -1,15 +1,243 @@
-use crate::system::ReactiveSystemRef;
+use crate::system::{EffectQueue, ReactiveSystemRef};
+use crate::types::{Location, NodeInner, format_location};
+use slotmap::{Key, SparseSecondaryMap};
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+pub type FlushCallback = Rc<dyn Fn(&FlushStats)>;
+type FlushStartHook = Rc<RefCell<dyn FnMut()>>;
+type FlushEndHook = Rc<RefCell<dyn FnMut(&FlushStats)>>;
+
+/// Callbacks registered with `on_flush_start` and `on_flush_end`, by id
+#[derive(Default)]
+pub struct FlushHooks {
+    next_id: u64,
+    start: Vec<(u64, FlushStartHook)>,
+    end: Vec<(u64, FlushEndHook)>,
+}
+
+/// What a single flush of the effect queue did
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FlushStats {
+    /// Effects taken off the queue
+    pub queued: usize,
+    /// Effects that were dirty and actually ran
+    pub ran: usize,
+    /// Effects that were queued but turned out clean, so did not run
+    pub skipped_clean: usize,
+    /// Wall time spent draining the queue
+    pub duration: Duration,
+}
+
+/// Node and link counts broken down by node kind, see `stats()`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CountStats {
+    pub signals: usize,
+    pub computeds: usize,
+    pub effects: usize,
+    /// Scopes other than the root
+    pub scopes: usize,
+    pub links: usize,
+    /// Every node except the root scope
+    pub total_nodes_excluding_root: usize,
+    /// The most links the propagation stack has held at once, a measure of
+    /// how deep and branchy the traversals of this thread have been
+    pub max_stack_depth: usize,
+}
+
+/// How many entries one of the runtime's maps holds and has room for
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MapCapacity {
+    pub len: usize,
+    pub capacity: usize,
+}
+
+/// Entries and allocated room of the runtime's largest maps
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CapacityStats {
+    pub nodes: MapCapacity,
+    pub links: MapCapacity,
+    /// Scopes with registered cleanups
+    pub cleanups: MapCapacity,
+    /// Scopes with provided contexts
+    pub contexts: MapCapacity,
+}
+
+/// Where the live entries of a slot map sit among its slots
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SlotUsage {
+    /// Live entries
+    pub live: usize,
+    /// Slots up to and including the highest live one
+    pub span: usize,
+    /// Slots the map has room for
+    pub capacity: usize,
+}
+
+impl SlotUsage {
+    /// Free slots between live ones, which stay allocated for as long as
+    /// any entry above them lives
+    pub fn holes(&self) -> usize {
+        self.span - self.live
+    }
+
+    /// Free slots above the highest live one
+    pub fn trailing(&self) -> usize {
+        self.capacity.saturating_sub(self.span)
+    }
+}
+
+/// How fragmented the node and link maps are, see `compact_report`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CompactReport {
+    pub nodes: SlotUsage,
+    pub links: SlotUsage,
+}
+
+impl fmt::Display for CompactReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, usage) in [("nodes", self.nodes), ("links", self.links)] {
+            writeln!(
+                f,
+                "{name}: {} live in {} slots, {} free among them and {} free above",
+                usage.live,
+                usage.capacity,
+                usage.holes(),
+                usage.trailing()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Batch depth and queued effects that belong to one async task
+#[derive(Default)]
+pub struct BatchContext {
+    pub depth: usize,
+    pub queued: EffectQueue,
+}
 
 impl super::ReactiveSystem {
     /// Flush all queued effects
+    ///
+    /// Flushes started by effects of a running flush drain the same queue, so
+    /// their work is accounted to the outermost one.
     pub fn flush(this: ReactiveSystemRef<Self>) {
-        while this.borrow().notify_index < this.borrow().queued_length {
-            let effect = this.borrow().queued[this.borrow().notify_index];
-            this.borrow_mut().notify_index += 1;
-            Self::run(this.clone(), effect);
+        let prev_flushing = std::mem::replace(&mut this.borrow_mut().flushing, true);
+        let start = if prev_flushing {
+            None
+        } else {
+            this.borrow_mut().flush_stats = FlushStats::default();
+            Some(Instant::now())
+        };
+        #[cfg(feature = "tracing")]
+        let _span = start.map(|_| {
+            tracing::debug_span!("flush", queued = this.borrow().pending_effects()).entered()
+        });
+        if start.is_some() && !this.borrow().queue.is_empty() {
+            let hooks: Vec<_> = this
+                .borrow()
+                .flush_hooks
+                .start
+                .iter()
+                .map(|(_, hook)| hook.clone())
+                .collect();
+            Self::run_flush_hooks(this.clone(), || {
+                for hook in hooks {
+                    (hook.borrow_mut())();
+                }
+            });
+        }
+
+        while let Some(effect) = this.borrow_mut().queue.pop() {
+            this.borrow_mut().flush_stats.queued += 1;
+            match Self::run(this.clone(), effect) {
+                Some(true) => this.borrow_mut().flush_stats.ran += 1,
+                Some(false) => this.borrow_mut().flush_stats.skipped_clean += 1,
+                None => {}
+            }
+        }
+        this.borrow_mut().flushing = prev_flushing;
+
+        #[cfg(all(debug_assertions, feature = "validate"))]
+        if start.is_some()
+            && let Err(violations) = this.borrow().validate()
+        {
+            let violations: Vec<String> = violations.iter().map(ToString::to_string).collect();
+            panic!(
+                "reactive graph invariants violated after flush:\n  {}",
+                violations.join("\n  ")
+            );
+        }
+
+        if let Some(start) = start {
+            let mut stats = this.borrow().flush_stats;
+            if stats.queued == 0 {
+                return;
+            }
+            stats.duration = start.elapsed();
+            this.borrow_mut().last_flush_stats = Some(stats);
+            let on_flush = this.borrow().on_flush.clone();
+            if let Some(on_flush) = on_flush {
+                on_flush(&stats);
+            }
+            let hooks: Vec<_> = this
+                .borrow()
+                .flush_hooks
+                .end
+                .iter()
+                .map(|(_, hook)| hook.clone())
+                .collect();
+            Self::run_flush_hooks(this, || {
+                for hook in hooks {
+                    (hook.borrow_mut())(&stats);
+                }
+            });
+        }
+    }
+
+    /// Run flush hooks inside a batch, so their writes cannot start a nested
+    /// flush
+    fn run_flush_hooks(this: ReactiveSystemRef<Self>, run: impl FnOnce()) {
+        this.borrow_mut().batch_depth += 1;
+        run();
+        this.borrow_mut().batch_depth -= 1;
+    }
+
+    /// Register a callback to run before each flush drains the queue
+    pub fn add_flush_start_hook(&mut self, hook: impl FnMut() + 'static) -> u64 {
+        let id = self.flush_hooks.next_id;
+        self.flush_hooks.next_id += 1;
+        self.flush_hooks
+            .start
+            .push((id, Rc::new(RefCell::new(hook))));
+        id
+    }
+
+    /// Register a callback to run after each flush has drained the queue
+    pub fn add_flush_end_hook(&mut self, hook: impl FnMut(&FlushStats) + 'static) -> u64 {
+        let id = self.flush_hooks.next_id;
+        self.flush_hooks.next_id += 1;
+        self.flush_hooks.end.push((id, Rc::new(RefCell::new(hook))));
+        id
+    }
+
+    /// Unregister a flush hook by the id it was registered under
+    pub fn remove_flush_hook(&mut self, id: u64) {
+        self.flush_hooks.start.retain(|(hook, _)| *hook != id);
+        self.flush_hooks.end.retain(|(hook, _)| *hook != id);
+    }
+
+    /// Run every queued effect right now, even inside an open batch
+    pub fn flush_sync(this: ReactiveSystemRef<Self>) {
+        if this.borrow().flushing {
+            panic!("flush_sync() called while effects are already being flushed");
+        }
+        if !this.borrow().queue.is_empty() {
+            Self::flush(this);
         }
-        this.borrow_mut().notify_index = 0;
-        this.borrow_mut().queued_length = 0;
     }
 
     /// Start a new batch
@@ -18,15 +246,179 @@ impl super::ReactiveSystem {
     }
 
     /// End the current batch and flush if needed
-    pub fn end_batch(this: ReactiveSystemRef<Self>) {
+    ///
+    /// Panics without touching the depth if no batch is open.
+    pub fn end_batch(this: ReactiveSystemRef<Self>, caller: Location) {
+        if this.borrow().batch_depth == 0 {
+            panic!(
+                "end_batch() called at {} without a matching start_batch()",
+                format_location(&caller)
+            );
+        }
         this.borrow_mut().batch_depth -= 1;
-        if this.borrow_mut().batch_depth == 0 {
+        if this.borrow().flushes_immediately() {
+            Self::flush(this);
+        }
+    }
+
+    /// Install `context` as the active batch state, returning the previous one
+    ///
+    /// The queues trade places rather than being copied, so a task's poll
+    /// does not allocate. The queue only holds effects that have not run
+    /// yet, so this is safe to call while a flush is in progress.
+    pub fn swap_batch_context(&mut self, mut context: BatchContext) -> BatchContext {
+        std::mem::swap(&mut self.batch_depth, &mut context.depth);
+        std::mem::swap(&mut self.queue, &mut context.queued);
+        context
+    }
+
+    /// Queue effects left over by a finished task, flushing if no batch is open
+    pub fn requeue_effects(this: ReactiveSystemRef<Self>, effects: EffectQueue) {
+        if effects.is_empty() {
+            return;
+        }
+        this.borrow_mut().queue.append(effects);
+        if this.borrow().flushes_immediately() && !this.borrow().flushing {
+            Self::flush(this);
+        }
+    }
+
+    /// Whether a write outside a batch flushes right away, rather than
+    /// leaving it to the next executor poll in auto-batch mode
+    #[inline]
+    pub fn flushes_immediately(&self) -> bool {
+        self.batch_depth == 0 && !self.auto_batch
+    }
+
+    /// Enable or disable deferring flushes to the next executor poll
+    ///
+    /// Disabling flushes whatever was deferred so far.
+    pub fn set_auto_batch(this: ReactiveSystemRef<Self>, enabled: bool) {
+        this.borrow_mut().auto_batch = enabled;
+        if !enabled {
+            Self::flush_pending(this);
+        }
+    }
+
+    /// Flush queued effects if no batch is open and no flush is running
+    pub fn flush_pending(this: ReactiveSystemRef<Self>) {
+        let ready = {
+            let this = this.borrow();
+            this.batch_depth == 0 && !this.flushing && !this.queue.is_empty()
+        };
+        if ready {
             Self::flush(this);
         }
     }
 
+    /// Number of effects queued but not yet run by the current flush
+    pub fn pending_effects(&self) -> usize {
+        self.queue.len()
+    }
+
     /// Count the number of nodes and links
     pub fn count(&self) -> (usize, usize) {
         (self.nodes.len(), self.links.len())
     }
+
+    /// Make room for `nodes` more nodes and `links` more links
+    pub fn reserve(&mut self, nodes: usize, links: usize) {
+        self.nodes.reserve(nodes);
+        self.links.reserve(links);
+    }
+
+    /// Give back the memory that maps and buffers hold beyond their entries
+    ///
+    /// Side maps are rebuilt at their current size. Slot maps can neither
+    /// move live entries nor drop trailing slots, so the node map keeps its
+    /// allocation; the link map is only replaced once it has no live links,
+    /// when no key into it is in use. Does nothing and returns false during
+    /// a batch, flush or tracked read, which may hold keys or buffers that
+    /// are being rebuilt.
+    pub fn shrink_to_fit(&mut self) -> bool {
+        if self.batch_depth > 0 || self.flushing || self.active_sub.get().is_some() {
+            return false;
+        }
+        if self.links.is_empty() {
+            self.links = Default::default();
+        }
+        shrink_sparse(&mut self.cleanups);
+        shrink_sparse(&mut self.async_cleanups);
+        shrink_sparse(&mut self.contexts);
+        shrink_sparse(&mut self.context_cache);
+        shrink_sparse(&mut self.keyed_contexts);
+        shrink_sparse(&mut self.cancel_tokens);
+        shrink_sparse(&mut self.checked_at);
+        shrink_sparse(&mut self.placeholders);
+        self.queue.shrink_to_fit();
+        self.stack.shrink_to_fit();
+        self.transactions.shrink_to_fit();
+        true
+    }
+
+    /// Entries and allocated room of the node, link, cleanup and context maps
+    pub fn capacity_stats(&self) -> CapacityStats {
+        CapacityStats {
+            nodes: MapCapacity {
+                len: self.nodes.len(),
+                capacity: self.nodes.capacity(),
+            },
+            links: MapCapacity {
+                len: self.links.len(),
+                capacity: self.links.capacity(),
+            },
+            cleanups: MapCapacity {
+                len: self.cleanups.len(),
+                capacity: self.cleanups.capacity(),
+            },
+            contexts: MapCapacity {
+                len: self.contexts.len(),
+                capacity: self.contexts.capacity(),
+            },
+        }
+    }
+
+    /// Where the live nodes and links sit among the slots of their maps
+    pub fn compact_report(&self) -> CompactReport {
+        CompactReport {
+            nodes: SlotUsage {
+                live: self.nodes.len(),
+                span: self.nodes.highest_slot(),
+                capacity: self.nodes.capacity(),
+            },
+            links: SlotUsage {
+                live: self.links.len(),
+                span: self.links.highest_slot(),
+                capacity: self.links.capacity(),
+            },
+        }
+    }
+
+    /// Count the nodes of each kind and the links
+    pub fn stats(&self) -> CountStats {
+        let mut stats = CountStats {
+            links: self.links.len(),
+            total_nodes_excluding_root: self.nodes.len() - 1,
+            max_stack_depth: self.stack.high_water(),
+            ..Default::default()
+        };
+        for (key, node) in self.nodes.iter() {
+            match node.inner {
+                NodeInner::Signal(_) => stats.signals += 1,
+                NodeInner::Computed(_) => stats.computeds += 1,
+                NodeInner::Effect(_) => stats.effects += 1,
+                NodeInner::None if key != self.root => stats.scopes += 1,
+                NodeInner::None => {}
+            }
+        }
+        stats
+    }
+}
+
+/// Rebuild a side map with room for just its entries; its keys belong to the
+/// node map, so they stay valid
+fn shrink_sparse<K: Key, V>(map: &mut SparseSecondaryMap<K, V>) {
+    if map.capacity() > map.len() {
+        *map = std::mem::take(map).into_iter().collect();
+    }
 }
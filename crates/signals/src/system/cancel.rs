@@ -0,0 +1,53 @@
+use std::{any::Any, any::TypeId, rc::Rc};
+
+use super::ReactiveSystem;
+use crate::cancel::CancellationToken;
+use crate::types::NodeKey;
+
+impl ReactiveSystem {
+    /// Get the current scope's cancellation token, creating it as a child of
+    /// the nearest ancestor's token and providing it as a context.
+    pub fn cancellation_token(&mut self) -> CancellationToken {
+        let current = self.current_scope.get();
+        if let Some(token) = self.cancel_tokens.get(current) {
+            return token.clone();
+        }
+
+        let mut ancestor = self.nodes[current].parent;
+        let parent = loop {
+            match ancestor {
+                Some(node) => match self.cancel_tokens.get(node) {
+                    Some(token) => break Some(token),
+                    None => ancestor = self.nodes[node].parent,
+                },
+                None => break None,
+            }
+        };
+        let token = parent.map_or_else(CancellationToken::new, CancellationToken::child_token);
+
+        self.cancel_tokens.insert(current, token.clone());
+        // Replaces the token cancelled by a previous run of an effect
+        self.contexts.entry(current).unwrap().or_default().insert(
+            TypeId::of::<CancellationToken>(),
+            Rc::new(token.clone()) as Rc<dyn Any>,
+        );
+        self.context_generation += 1;
+        token
+    }
+
+    /// Cancel the tokens of `nodes` without cleaning them up.
+    pub fn cancel_scopes(&self, nodes: &[NodeKey]) {
+        for &node in nodes {
+            if let Some(token) = self.cancel_tokens.get(node) {
+                token.cancel();
+            }
+        }
+    }
+
+    /// Remove and cancel the token of a scope that is being cleaned up.
+    pub fn cancel_scope(&mut self, node: NodeKey) {
+        if let Some(token) = self.cancel_tokens.remove(node) {
+            token.cancel();
+        }
+    }
+}
@@ -1,9 +1,8 @@
 use crate::system::ReactiveSystemRef;
+use crate::types::Location;
 use crate::types::{
-    ComputedNodeInner, ComputedOps, MemoNodeInner, NodeInner, NodeKey, ReactiveFlags, ReactiveNode,
+    ComputedNode, ComputedNodeInner, MemoNodeInner, NodeInner, NodeKey, ReactiveFlags, ReactiveNode,
 };
-use crate::types::{Location, RefCell};
-use std::rc::Rc;
 
 impl super::ReactiveSystem {
     /// Create a new memo node (with equality check)
@@ -12,10 +11,10 @@ impl super::ReactiveSystem {
         F: Fn() -> T + 'static,
         T: PartialEq + 'static,
     {
-        let inner = Rc::new(RefCell::new(MemoNodeInner::new(Box::new(getter))));
-
-        let node = self.nodes.insert(ReactiveNode::new(
-            NodeInner::Computed(inner),
+        let node = self.insert_node(ReactiveNode::new(
+            NodeInner::Computed(ComputedNode {
+                ops: Some(Box::new(MemoNodeInner::new(getter))),
+            }),
             ReactiveFlags::NONE,
             Some(self.current_scope.get()),
             caller,
@@ -30,10 +29,10 @@ impl super::ReactiveSystem {
         F: Fn(Option<T>) -> T + 'static,
         T: 'static,
     {
-        let inner = Rc::new(RefCell::new(ComputedNodeInner::new(Box::new(getter))));
-
-        let node = self.nodes.insert(ReactiveNode::new(
-            NodeInner::Computed(inner as Rc<RefCell<dyn ComputedOps>>),
+        let node = self.insert_node(ReactiveNode::new(
+            NodeInner::Computed(ComputedNode {
+                ops: Some(Box::new(ComputedNodeInner::new(getter))),
+            }),
             ReactiveFlags::NONE,
             Some(self.current_scope.get()),
             caller,
@@ -44,13 +43,28 @@ impl super::ReactiveSystem {
 
     /// Track a computed access for reactive dependencies
     pub fn computed_track(this: ReactiveSystemRef<Self>, node: NodeKey) {
+        this.borrow().check_not_observing();
         let ReactiveNode {
             flags, deps, subs, ..
         } = this.borrow().nodes[node];
 
+        // Only a computed reading itself, directly or through others, sees
+        // its own evaluation in progress
+        #[cfg(debug_assertions)]
+        if flags.contains(ReactiveFlags::MUTABLE | ReactiveFlags::RECURSED_CHECK) {
+            let cycle = this.borrow().evaluation_cycle(&[], node);
+            this.borrow().cycle_detected(&cycle);
+        }
+
+        // An unwatched computed is never marked pending, so it checks its
+        // dependencies whenever something was written since it last did
+        let propagations = this.borrow().propagations;
+        let unwatched = Self::may_be_stale(flags, deps);
+        let unchecked = unwatched && this.borrow().checked_at.get(node) != Some(&propagations);
+
         // Check if dirty or pending
         if flags.contains(ReactiveFlags::DIRTY)
-            || (flags.contains(ReactiveFlags::PENDING)
+            || ((flags.contains(ReactiveFlags::PENDING) || unchecked)
                 && (Self::check_dirty(this.clone(), deps.unwrap(), node))
                 || {
                     this.borrow_mut().nodes[node]
@@ -67,6 +81,9 @@ impl super::ReactiveSystem {
         } else if flags.is_empty() {
             Self::update_computed_inner(this.clone(), node);
         }
+        if unwatched {
+            this.borrow_mut().checked_at.insert(node, propagations);
+        }
 
         let sub = this.borrow_mut().active_sub.get();
         if let Some(sub) = sub {
@@ -81,8 +98,14 @@ impl super::ReactiveSystem {
         T: Clone + 'static,
     {
         Self::computed_track(this.clone(), node);
-        if let NodeInner::Computed(inner) = &this.borrow().nodes[node].inner {
-            unsafe { &*(inner.borrow().as_any() as *const dyn std::any::Any as *const T) }.clone()
+        unsafe { &*(this.borrow().computed_value(node) as *const T) }.clone()
+    }
+
+    /// Get a pointer to a computed's current value, without tracking
+    #[inline]
+    pub fn computed_value(&self, node: NodeKey) -> *const dyn std::any::Any {
+        if let NodeInner::Computed(inner) = &self.nodes[node].inner {
+            inner.value()
         } else {
             panic!("Node is not a Computed");
         }
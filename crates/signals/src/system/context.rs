@@ -1,9 +1,33 @@
 use std::{
     any::{Any, TypeId},
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
     rc::Rc,
 };
 
 use super::ReactiveSystem;
+use crate::types::NodeKey;
+
+/// Per-scope storage for contexts provided under an explicit key.
+///
+/// Entries are bucketed by the key type, the value type and the key's hash;
+/// colliding keys are told apart by comparing the stored key.
+pub type KeyedContexts = HashMap<(TypeId, TypeId, u64), Vec<(Box<dyn Any>, Rc<dyn Any>)>>;
+
+/// Type-keyed lookups made from one scope, including those that found
+/// nothing, valid while `generation` matches the system's
+/// `context_generation`
+#[derive(Default)]
+pub struct ContextCache {
+    generation: u64,
+    lookups: HashMap<TypeId, Option<Rc<dyn Any>>>,
+}
+
+fn keyed_slot<K: Hash + 'static, T: 'static>(key: &K) -> (TypeId, TypeId, u64) {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    (TypeId::of::<K>(), TypeId::of::<T>(), hasher.finish())
+}
 
 impl ReactiveSystem {
     /// Provide a context value in the current scope.
@@ -30,6 +54,33 @@ impl ReactiveSystem {
             .unwrap()
             .or_default()
             .insert(TypeId::of::<T>(), Rc::new(value) as Rc<dyn Any>);
+        self.context_generation += 1;
+    }
+
+    /// Remove a context value from the current scope.
+    ///
+    /// Only the current scope's entry is removed; a value of the same type
+    /// provided by a parent becomes visible again. Returns the removed value
+    /// if nothing else still holds a reference to it.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use samara_signals::{provide_context, remove_context, scope, use_context};
+    /// scope(|| {
+    ///     provide_context(1i32);
+    ///     assert_eq!(remove_context::<i32>(), Some(1));
+    ///     assert_eq!(use_context::<i32>(), None);
+    /// });
+    /// ```
+    pub fn remove_context<T: 'static>(&mut self) -> Option<T> {
+        let current = self.current_scope.get();
+        let value = self.contexts.get_mut(current)?.remove(&TypeId::of::<T>())?;
+        // Cached lookups share the value, which would keep it from being
+        // returned
+        self.context_cache.clear();
+        Rc::downcast::<T>(value)
+            .ok()
+            .and_then(|value| Rc::try_unwrap(value).ok())
     }
 
     /// Use a context value from the current or any parent scope.
@@ -55,24 +106,9 @@ impl ReactiveSystem {
     ///     });
     /// });
     /// ```
-    pub fn use_context<T: 'static + Clone>(&self) -> Option<T> {
-        let type_id = TypeId::of::<T>();
-
-        // Walk parent chain to find the context
-        let mut current = self.current_scope.get();
-        loop {
-            if let Some(value) = self
-                .contexts
-                .get(current)
-                .and_then(|contexts| contexts.get(&type_id))
-            {
-                return value.downcast_ref::<T>().cloned();
-            }
-            match self.nodes[current].parent {
-                Some(parent) => current = parent,
-                None => return None,
-            }
-        }
+    pub fn use_context<T: 'static + Clone>(&mut self) -> Option<T> {
+        self.lookup_context(TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref::<T>().cloned())
     }
 
     /// Check if a context of the given type exists in the current or any parent scope.
@@ -91,20 +127,145 @@ impl ReactiveSystem {
     ///     assert!(has_context::<Theme>());
     /// });
     /// ```
-    pub fn has_context<T: 'static>(&self) -> bool {
-        let type_id = TypeId::of::<T>();
+    pub fn has_context<T: 'static>(&mut self) -> bool {
+        self.lookup_context(TypeId::of::<T>()).is_some()
+    }
+
+    /// Find the nearest context of type `type_id` from the current scope
+    ///
+    /// Results are cached per scope, so hot effects deep in the tree do not
+    /// walk the whole parent chain on every run. Providing a context
+    /// anywhere or moving a scope bumps `context_generation` and with it
+    /// invalidates every cached lookup; removing one drops them all.
+    fn lookup_context(&mut self, type_id: TypeId) -> Option<Rc<dyn Any>> {
+        let scope = self.current_scope.get();
+        let generation = self.context_generation;
+        if let Some(cache) = self.context_cache.get(scope)
+            && cache.generation == generation
+            && let Some(value) = cache.lookups.get(&type_id)
+        {
+            return value.clone();
+        }
+
+        // Walk parent chain to find the context
+        let mut current = scope;
+        let value = loop {
+            if let Some(value) = self
+                .contexts
+                .get(current)
+                .and_then(|contexts| contexts.get(&type_id))
+            {
+                break Some(value.clone());
+            }
+            match self.nodes[current].parent {
+                Some(parent) => current = parent,
+                None => break None,
+            }
+        };
+
+        if let Some(entry) = self.context_cache.entry(scope) {
+            let cache = entry.or_default();
+            if cache.generation != generation {
+                cache.generation = generation;
+                cache.lookups.clear();
+            }
+            cache.lookups.insert(type_id, value.clone());
+        }
+        value
+    }
+
+    /// Every type-keyed context visible from the current scope, with values
+    /// from inner scopes shadowing those of outer ones
+    pub fn capture_contexts(&self) -> HashMap<TypeId, Rc<dyn Any>> {
+        let mut captured = HashMap::new();
+        let mut current = Some(self.current_scope.get());
+        while let Some(node) = current {
+            if let Some(contexts) = self.contexts.get(node) {
+                for (type_id, value) in contexts {
+                    captured.entry(*type_id).or_insert_with(|| value.clone());
+                }
+            }
+            current = self.nodes[node].parent;
+        }
+        captured
+    }
+
+    /// Provide previously captured contexts in `node`, overwriting values of
+    /// the same types
+    pub fn provide_contexts(&mut self, node: NodeKey, contexts: &HashMap<TypeId, Rc<dyn Any>>) {
+        let Some(entry) = self.contexts.entry(node) else {
+            return;
+        };
+        let map = entry.or_default();
+        for (type_id, value) in contexts {
+            map.insert(*type_id, value.clone());
+        }
+        self.context_generation += 1;
+    }
+
+    /// Names and creation locations of the scopes a context lookup walks,
+    /// from the current scope up to the root
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    pub fn scope_chain(&self) -> Vec<(Option<String>, crate::types::Location)> {
+        let mut chain = Vec::new();
+        let mut current = Some(self.current_scope.get());
+        while let Some(node) = current {
+            chain.push((self.node_name(node), self.nodes[node].caller));
+            current = self.nodes[node].parent;
+        }
+        chain
+    }
+
+    /// Provide a context value under `key` in the current scope.
+    ///
+    /// Keyed contexts live alongside the type-keyed ones, so several values of
+    /// the same type can be provided as long as their keys differ.
+    pub fn provide_context_keyed<K: Eq + Hash + 'static, T: 'static>(&mut self, key: K, value: T) {
+        let current = self.current_scope.get();
+        let slot = keyed_slot::<K, T>(&key);
+        let entries = self
+            .keyed_contexts
+            .entry(current)
+            .unwrap()
+            .or_default()
+            .entry(slot)
+            .or_default();
+        let value = Rc::new(value) as Rc<dyn Any>;
+        match entries
+            .iter_mut()
+            .find(|(existing, _)| existing.downcast_ref::<K>() == Some(&key))
+        {
+            Some((_, existing)) => *existing = value,
+            None => entries.push((Box::new(key), value)),
+        }
+    }
+
+    /// Use a keyed context value from the current or any parent scope.
+    ///
+    /// Walks the parent chain exactly like `use_context`.
+    pub fn use_context_keyed<K: Eq + Hash + 'static, T: 'static + Clone>(
+        &self,
+        key: &K,
+    ) -> Option<T> {
+        let slot = keyed_slot::<K, T>(key);
 
-        // Walk parent chain to check for context existence
         let mut current = self.current_scope.get();
         loop {
-            if let Some(contexts) = self.contexts.get(current)
-                && contexts.contains_key(&type_id)
+            if let Some((_, value)) = self
+                .keyed_contexts
+                .get(current)
+                .and_then(|contexts| contexts.get(&slot))
+                .and_then(|entries| {
+                    entries
+                        .iter()
+                        .find(|(existing, _)| existing.downcast_ref::<K>() == Some(key))
+                })
             {
-                return true;
+                return value.downcast_ref::<T>().cloned();
             }
             match self.nodes[current].parent {
                 Some(parent) => current = parent,
-                None => return false,
+                None => return None,
             }
         }
     }
@@ -0,0 +1,38 @@
+use super::ReactiveSystem;
+use super::snapshot::node_kind;
+use crate::types::{NodeKey, format_location};
+
+impl ReactiveSystem {
+    /// Panic with the kinds and creation locations of the nodes along a
+    /// dependency cycle, given in order with the first node repeated last
+    #[cold]
+    pub fn cycle_detected(&self, cycle: &[NodeKey]) -> ! {
+        let mut message = String::from("dependency cycle detected:");
+        for (i, &node) in cycle.iter().enumerate() {
+            let info = &self.nodes[node];
+            message.push_str(if i == 0 { "\n  " } else { "\n  -> " });
+            message.push_str(&format!("{:?}", node_kind(info)));
+            if let Some(name) = &info.name {
+                message.push_str(&format!(" {name:?}"));
+            }
+            message.push_str(&format!(" created at {}", format_location(&info.caller)));
+        }
+        panic!("{message}")
+    }
+
+    /// The cycle closed by reading `node` while it is being evaluated,
+    /// `path` being the nodes checked on the way from the reader to `node`
+    pub fn evaluation_cycle(&self, path: &[NodeKey], node: NodeKey) -> Vec<NodeKey> {
+        let start = self
+            .evaluating
+            .iter()
+            .rposition(|&key| key == node)
+            .unwrap_or(self.evaluating.len());
+        self.evaluating[start..]
+            .iter()
+            .chain(path)
+            .copied()
+            .chain(std::iter::once(node))
+            .collect()
+    }
+}
@@ -1,7 +1,7 @@
 use crate::system::ReactiveSystemRef;
+use crate::types::Location;
 use crate::types::{EffectNode, Link, NodeInner, NodeKey, ReactiveFlags, ReactiveNode};
-use crate::types::{Location, RefCell};
-use std::rc::Rc;
+use std::time::Instant;
 
 impl super::ReactiveSystem {
     /// Create a new effect node
@@ -10,17 +10,15 @@ impl super::ReactiveSystem {
         effect: F,
         caller: Location,
     ) -> NodeKey {
-        let effect = Rc::new(RefCell::new(effect));
+        let mut effect = effect;
 
         let (prev_scope, prev_sub, node) = {
             let this = this.borrow_mut();
             let parent_scope = this.current_scope.get();
 
             // Create ONE node that is both the effect AND its scope
-            let node = this.nodes.insert(ReactiveNode::new(
-                NodeInner::Effect(EffectNode {
-                    effect: effect.clone(),
-                }),
+            let node = this.insert_node(ReactiveNode::new(
+                NodeInner::Effect(EffectNode { effect: None }),
                 ReactiveFlags::WATCHING | ReactiveFlags::RECURSED_CHECK,
                 Some(parent_scope),
                 caller,
@@ -41,28 +39,35 @@ impl super::ReactiveSystem {
             (prev_scope, prev_sub, node)
         };
 
-        (effect.borrow_mut())();
+        let start = this.borrow().is_observed().then(Instant::now);
+        effect();
 
         let this = this.borrow_mut();
+        this.restore_effect(node, Box::new(effect));
+        if let Some(start) = start {
+            let duration = start.elapsed();
+            this.observe(|observer| observer.effect_ran(node, duration));
+        }
 
         // Restore parent scope
         this.current_scope.set(prev_scope);
         this.active_sub.set(prev_sub);
         this.nodes[node].flags.remove(ReactiveFlags::RECURSED_CHECK);
+        this.record_run(node);
         node
     }
 
-    /// Create a new scope node
-    pub fn new_scope<F: FnOnce() + 'static>(
+    /// Create a new scope node, returning the closure's output with it
+    pub fn new_scope<F: FnOnce() -> T + 'static, T>(
         this: ReactiveSystemRef<Self>,
         f: F,
         caller: Location,
-    ) -> NodeKey {
+    ) -> (T, NodeKey) {
         let (prev_sub, prev_scope, scope_node) = {
             let mut this = this.borrow_mut();
             let parent = this.current_scope.get();
             // Create scope node (effect without execution)
-            let scope_node = this.nodes.insert(ReactiveNode::new(
+            let scope_node = this.insert_node(ReactiveNode::new(
                 NodeInner::None,
                 ReactiveFlags::NONE,
                 Some(parent),
@@ -80,20 +85,20 @@ impl super::ReactiveSystem {
             (prev_sub, prev_scope, scope_node)
         };
 
-        f();
+        let output = f();
 
         let this = this.borrow();
 
         this.set_active_sub(prev_sub);
         this.current_scope.set(prev_scope);
 
-        scope_node
+        (output, scope_node)
     }
 
     /// Create a new child scope node with an explicit parent scope
     pub fn new_child_scope(&mut self, parent: NodeKey, caller: Location) -> NodeKey {
         // Create scope node with explicit parent
-        let scope_node = self.nodes.insert(ReactiveNode::new(
+        let scope_node = self.insert_node(ReactiveNode::new(
             NodeInner::None,
             ReactiveFlags::NONE,
             Some(parent),
@@ -107,35 +112,45 @@ impl super::ReactiveSystem {
     }
 
     /// Run an effect
-    pub fn run(this: ReactiveSystemRef<Self>, node: NodeKey) {
-        let Some((flags, deps)) = this
+    ///
+    /// Returns whether the effect was dirty and ran, or `None` if the node
+    /// has been disposed since it was queued.
+    pub fn run(this: ReactiveSystemRef<Self>, node: NodeKey) -> Option<bool> {
+        let (flags, deps) = this
             .borrow()
             .nodes
             .get(node)
-            .map(|item| (item.flags, item.deps))
-        else {
-            return;
-        };
+            .map(|item| (item.flags, item.deps))?;
         if flags.contains(ReactiveFlags::DIRTY)
             || (flags.contains(ReactiveFlags::PENDING)
                 && Self::check_dirty(this.clone(), deps.unwrap(), node))
         {
-            this.borrow_mut().cycle += 1;
+            #[cfg(feature = "tracing")]
+            let _span = {
+                let item = &this.borrow().nodes[node];
+                tracing::debug_span!(
+                    "effect_run",
+                    node = ?node,
+                    location = %crate::types::format_location(&item.caller),
+                    name = item.name.as_deref(),
+                )
+                .entered()
+            };
+            let system = this.borrow_mut();
+            system.cycle = system.cycle.wrapping_add(1);
             this.borrow_mut().nodes[node].deps_tail = None;
-            this.borrow_mut().nodes[node].flags =
-                ReactiveFlags::WATCHING | ReactiveFlags::RECURSED_CHECK;
+            this.borrow_mut().nodes[node]
+                .flags
+                .reset(ReactiveFlags::WATCHING | ReactiveFlags::RECURSED_CHECK);
             Self::cleanup_scope(this.clone(), node);
 
             // Clean up children from previous execution
             // This prevents memory leaks when effects run multiple times
             this.borrow_mut().purge_child(node);
 
-            let effect = if let NodeInner::Effect(EffectNode { effect }) =
-                &this.borrow_mut().nodes[node].inner
-            {
-                Some(effect.clone())
-            } else {
-                None
+            let effect = match &mut this.borrow_mut().nodes[node].inner {
+                NodeInner::Effect(EffectNode { effect }) => effect.take(),
+                _ => None,
             };
 
             let prev_sub = this.borrow_mut().set_active_sub(Some(node));
@@ -143,20 +158,36 @@ impl super::ReactiveSystem {
             let prev_scope = this.borrow_mut().current_scope.get();
             this.borrow_mut().current_scope.set(node);
 
-            if let Some(effect) = effect {
-                (effect.borrow_mut())();
+            let start = this.borrow().is_observed().then(Instant::now);
+            if let Some(mut effect) = effect {
+                effect();
+                this.borrow_mut().restore_effect(node, effect);
+            }
+            if let Some(start) = start {
+                let duration = start.elapsed();
+                this.borrow()
+                    .observe(|observer| observer.effect_ran(node, duration));
             }
 
             // Restore previous scope
             this.borrow_mut().current_scope.set(prev_scope);
             this.borrow_mut().active_sub.set(prev_sub);
 
+            // The effect disposed itself
+            if !this.borrow().nodes.contains_key(node) {
+                return Some(true);
+            }
             this.borrow_mut().nodes[node]
                 .flags
                 .remove(ReactiveFlags::RECURSED_CHECK);
             this.borrow_mut().purge_deps(node, false);
+            this.borrow_mut().record_run(node);
+            Some(true)
         } else {
-            this.borrow_mut().nodes[node].flags = ReactiveFlags::WATCHING;
+            this.borrow_mut().nodes[node]
+                .flags
+                .reset(ReactiveFlags::WATCHING);
+            Some(false)
         }
     }
 
@@ -164,7 +195,7 @@ impl super::ReactiveSystem {
     pub fn trigger<F: Fn() + 'static>(this: ReactiveSystemRef<Self>, f: F, caller: Location) {
         // Create a temporary subscriber node
         let parent = this.borrow().current_scope.get();
-        let sub = this.borrow_mut().nodes.insert(ReactiveNode::new(
+        let sub = this.borrow_mut().insert_node(ReactiveNode::new(
             NodeInner::None,
             ReactiveFlags::WATCHING,
             Some(parent),
@@ -174,28 +205,41 @@ impl super::ReactiveSystem {
         let prev_sub = this.borrow_mut().set_active_sub(Some(sub));
         f();
         this.borrow_mut().active_sub.set(prev_sub);
+        this.borrow_mut().clear_watching(sub);
 
         // Unlink all dependencies
         let mut current = this.borrow().nodes[sub].deps;
         while let Some(link_key) = current {
-            let Link { dep, next_sub, .. } = this.borrow().links[link_key];
-            current = next_sub;
+            let Link { dep, next_dep, .. } = this.borrow().links[link_key];
+            current = next_dep;
             this.borrow_mut().unlink(link_key);
 
             let subs = this.borrow().nodes[dep].subs;
             if let Some(subs) = subs {
                 this.borrow_mut().nodes[sub].flags = ReactiveFlags::NONE;
-                this.borrow_mut().propagate(subs);
-                this.borrow_mut().shallow_propagate(subs);
+                if !this.borrow_mut().propagate_write(subs) {
+                    this.borrow_mut().shallow_propagate(subs);
+                }
             }
         }
 
-        if this.borrow().batch_depth == 0 {
+        // Remove the temporary node before flushing, so effects never see it
+        this.borrow_mut().remove_node(sub);
+
+        if this.borrow().flushes_immediately() {
             Self::flush(this.clone());
         }
+    }
 
-        // Remove the temporary node
-        this.borrow_mut().nodes.remove(sub);
+    /// Put an effect's closure back after a run, unless the run disposed it
+    fn restore_effect(&mut self, node: NodeKey, effect: Box<dyn FnMut()>) {
+        if let Some(ReactiveNode {
+            inner: NodeInner::Effect(item),
+            ..
+        }) = self.nodes.get_mut(node)
+        {
+            item.effect = Some(effect);
+        }
     }
 
     /// Set the active subscriber
@@ -1,10 +1,52 @@
 use crate::system::ReactiveSystemRef;
 use crate::{
     flags::ReactiveFlags,
-    types::{NodeInner, NodeKey},
+    types::{ComputedNode, ComputedOps, Location, NodeInner, NodeKey, ReactiveNode},
 };
 
 impl super::ReactiveSystem {
+    /// Add a node to the graph
+    pub fn insert_node(&mut self, node: ReactiveNode) -> NodeKey {
+        self.check_not_observing();
+        let key = self.nodes.insert(node);
+        let node = &self.nodes[key];
+        self.observe(|observer| {
+            observer.node_created(
+                key,
+                super::snapshot::node_kind(node),
+                crate::types::panic_location(&node.caller),
+            )
+        });
+        #[cfg(feature = "tracing")]
+        tracing::trace!(
+            node = ?key,
+            kind = ?super::snapshot::node_kind(node),
+            location = %crate::types::format_location(&node.caller),
+            "node_created"
+        );
+        key
+    }
+
+    /// Remove a node from the graph, once it has been unlinked
+    pub fn remove_node(&mut self, node: NodeKey) {
+        self.check_not_observing();
+        // Guards point straight at the value, so it must outlive them
+        if let Some(NodeInner::Signal(signal)) = self.nodes.get(node).map(|node| &node.inner)
+            && let Some(held_at) = signal.held_at()
+        {
+            self.disposed_while_borrowed(node, held_at);
+        }
+        #[cfg(debug_assertions)]
+        self.bury(node);
+        self.checked_at.remove(node);
+        self.context_cache.remove(node);
+        if self.nodes.remove(node).is_some() {
+            self.observe(|observer| observer.node_disposed(node));
+            #[cfg(feature = "tracing")]
+            tracing::trace!(node = ?node, "node_disposed");
+        }
+    }
+
     /// Link a child node to its parent's children list
     pub fn link_child(&mut self, child: NodeKey) {
         let parent = match self.nodes[child].parent {
@@ -45,6 +87,130 @@ impl super::ReactiveSystem {
         self.nodes[child].next = None;
     }
 
+    /// Label a node for diagnostics and snapshots
+    pub fn set_node_name(&mut self, node: NodeKey, name: &str) {
+        if let Some(node) = self.nodes.get_mut(node) {
+            node.name = Some(Box::new(name.to_owned()));
+        }
+    }
+
+    /// The label set with `set_node_name`, if any
+    pub fn node_name(&self, node: NodeKey) -> Option<String> {
+        self.nodes.get(node)?.name.as_deref().cloned()
+    }
+
+    /// Names of the named nodes from the root down to `node`, formatted for
+    /// appending to a panic message; empty when none of them are named
+    pub fn describe_owners(&self, node: NodeKey) -> String {
+        let mut names = Vec::new();
+        let mut current = Some(node);
+        while let Some(key) = current {
+            let Some(node) = self.nodes.get(key) else {
+                break;
+            };
+            if let Some(name) = &node.name {
+                names.push(format!("{name:?}"));
+            }
+            current = node.parent;
+        }
+        if names.is_empty() {
+            return String::new();
+        }
+        names.reverse();
+        format!(" (in {})", names.join(" > "))
+    }
+
+    /// Direct children of `node` that own other nodes (scopes and effects)
+    pub fn scope_children(&self, node: NodeKey) -> Vec<NodeKey> {
+        let mut children = Vec::new();
+        let mut child = self.nodes.get(node).and_then(|node| node.child);
+        while let Some(key) = child {
+            let node = &self.nodes[key];
+            if matches!(node.inner, NodeInner::None | NodeInner::Effect(_)) {
+                children.push(key);
+            }
+            child = node.next;
+        }
+        children
+    }
+
+    /// All nodes owned, directly or indirectly, by `node`
+    pub fn descendants(&self, node: NodeKey) -> Vec<NodeKey> {
+        let mut descendants = Vec::new();
+        let mut pending: Vec<NodeKey> = self
+            .nodes
+            .get(node)
+            .and_then(|node| node.child)
+            .into_iter()
+            .collect();
+        while let Some(key) = pending.pop() {
+            descendants.push(key);
+            let node = &self.nodes[key];
+            pending.extend(node.next);
+            pending.extend(node.child);
+        }
+        descendants
+    }
+
+    /// Number of nodes and links under `node`, the node itself included
+    ///
+    /// Links are attributed to their subscriber, so dependencies on nodes
+    /// outside the subtree are counted but subscriptions from outside are not.
+    pub fn subtree_count(&self, node: NodeKey) -> (usize, usize) {
+        if !self.nodes.contains_key(node) {
+            return (0, 0);
+        }
+        let mut nodes = 0;
+        let mut links = 0;
+        for key in std::iter::once(node).chain(self.descendants(node)) {
+            nodes += 1;
+            let mut link = self.nodes[key].deps;
+            while let Some(key) = link {
+                links += 1;
+                link = self.links[key].next_dep;
+            }
+        }
+        (nodes, links)
+    }
+
+    /// Move a node and its subtree under a different parent
+    ///
+    /// Context lookups walk the parent chain, so the moved subtree inherits
+    /// from its new ancestors from now on.
+    pub fn reparent(&mut self, node: NodeKey, new_parent: NodeKey) {
+        if !self.nodes.contains_key(node) || !self.nodes.contains_key(new_parent) {
+            panic!("Cannot reparent a disposed node");
+        }
+        if self.nodes[node].parent.is_none() {
+            panic!("Cannot reparent the root scope");
+        }
+        let mut ancestor = Some(new_parent);
+        while let Some(current) = ancestor {
+            if current == node {
+                panic!("Cannot move a node under its own descendant");
+            }
+            ancestor = self.nodes[current].parent;
+        }
+
+        self.unlink_child(node);
+        self.nodes[node].parent = Some(new_parent);
+        self.link_child(node);
+        self.context_generation += 1;
+
+        // An effect is also a dependency of the node that was running when
+        // it was created, and is purged once that owner lets go of it; move
+        // the link too, so disposing the old parent leaves it alone. The
+        // new link goes in first, as the effect would be purged the moment
+        // it had no owner left.
+        if matches!(self.nodes[node].inner, NodeInner::Effect(_))
+            && let Some(owner_link) = self.nodes[node].subs
+            && self.links[owner_link].sub != new_parent
+        {
+            self.link(node, new_parent, 0);
+            self.unlink(owner_link);
+        }
+    }
+
     /// Update a computed node and return whether it changed
     pub fn update_computed(this: ReactiveSystemRef<Self>, node: NodeKey) -> bool {
         this.borrow_mut().nodes[node].deps_tail = None;
@@ -53,22 +219,30 @@ impl super::ReactiveSystem {
     }
 
     pub fn update_computed_inner(this: ReactiveSystemRef<Self>, node: NodeKey) -> bool {
-        this.borrow_mut().cycle += 1;
-        this.borrow_mut().nodes[node].flags =
-            ReactiveFlags::MUTABLE | ReactiveFlags::RECURSED_CHECK;
+        let system = this.borrow_mut();
+        system.cycle = system.cycle.wrapping_add(1);
+        this.borrow_mut().nodes[node]
+            .flags
+            .reset(ReactiveFlags::MUTABLE | ReactiveFlags::RECURSED_CHECK);
         let prev_sub = this.borrow_mut().set_active_sub(Some(node));
+        #[cfg(debug_assertions)]
+        this.borrow_mut().evaluating.push(node);
 
-        let inner = if let NodeInner::Computed(inner) = &this.borrow_mut().nodes[node].inner {
-            Some(inner.clone())
-        } else {
-            None
+        let ops = match &mut this.borrow_mut().nodes[node].inner {
+            NodeInner::Computed(ComputedNode { ops }) => ops.take(),
+            _ => None,
         };
-        let dirty = if let Some(inner) = inner {
-            inner.borrow_mut().update()
-        } else {
-            false
+        let dirty = match ops {
+            Some(mut ops) => {
+                let dirty = ops.update();
+                this.borrow_mut().restore_computed(node, ops);
+                dirty
+            }
+            None => false,
         };
 
+        #[cfg(debug_assertions)]
+        this.borrow_mut().evaluating.pop();
         this.borrow_mut().nodes[node]
             .flags
             .remove(ReactiveFlags::RECURSED_CHECK);
@@ -78,10 +252,22 @@ impl super::ReactiveSystem {
         dirty
     }
 
+    /// Put the getter and value of a computed back after an update, unless
+    /// the getter disposed it
+    fn restore_computed(&mut self, node: NodeKey, ops: Box<dyn ComputedOps>) {
+        if let Some(ReactiveNode {
+            inner: NodeInner::Computed(computed),
+            ..
+        }) = self.nodes.get_mut(node)
+        {
+            computed.ops = Some(ops);
+        }
+    }
+
     /// Mark a signal node as mutable
     #[inline]
     pub fn update_signal(&mut self, s: NodeKey) {
-        self.nodes[s].flags = ReactiveFlags::MUTABLE;
+        self.nodes[s].flags.reset(ReactiveFlags::MUTABLE);
     }
 
     /// Update a node (computed or signal) and return whether it changed
@@ -95,51 +281,94 @@ impl super::ReactiveSystem {
         }
     }
 
+    /// Run the cleanups of a scope and of the scopes and effects under it,
+    /// innermost first
+    ///
+    /// Walks the tree with an explicit stack rather than recursing, so
+    /// deeply nested scopes cannot overflow the thread's stack.
     pub fn cleanup_scope(this: ReactiveSystemRef<Self>, node: NodeKey) {
-        let mut current = this.borrow().nodes[node].child;
-        while let Some(child) = current {
-            current = this.borrow().nodes[child].next;
-            if match this.borrow().nodes[child].inner {
-                NodeInner::Effect(_) | NodeInner::None => true,
-                _ => false,
-            } {
-                Self::cleanup_scope(this.clone(), child)
+        // Each scope being cleaned up with the next of its children to visit
+        let mut stack = vec![(node, this.borrow().nodes[node].child)];
+        while let Some(&(scope, current)) = stack.last() {
+            let Some(child) = current else {
+                stack.pop();
+                Self::run_cleanups(this.clone(), scope);
+                continue;
+            };
+            let (next, inner_scope) = {
+                let child = &this.borrow().nodes[child];
+                (
+                    child.next,
+                    matches!(child.inner, NodeInner::Effect(_) | NodeInner::None),
+                )
+            };
+            stack.last_mut().unwrap().1 = next;
+            if inner_scope {
+                let first = this.borrow().nodes[child].child;
+                stack.push((child, first));
             }
         }
+    }
+
+    /// Run the cleanups registered on `node` itself, last registered first
+    fn run_cleanups(this: ReactiveSystemRef<Self>, node: NodeKey) {
+        // Cancel first so cleanups that abort tasks see a cancelled token
+        this.borrow_mut().cancel_scope(node);
         if let Some(cleanups) = { this.borrow_mut().cleanups.remove(node) } {
             for cleanup in cleanups.into_iter().rev() {
                 cleanup();
             }
         }
+        if let Some(cleanups) = { this.borrow_mut().async_cleanups.remove(node) } {
+            for cleanup in cleanups.into_iter().rev() {
+                cleanup();
+            }
+        }
     }
 
     /// Cleanup children of a node
+    ///
+    /// Child scopes are purged depth first with an explicit stack, like
+    /// `cleanup_scope`.
     pub fn purge_child(&mut self, node: NodeKey) {
-        let mut current = self.nodes[node].child;
-        while let Some(child) = current {
-            current = self.nodes[child].next;
+        // Each scope being purged with the next of its children to visit
+        let mut stack = vec![(node, self.nodes[node].child)];
+        while let Some(&(scope, current)) = stack.last() {
+            let Some(child) = current else {
+                stack.pop();
+                self.nodes[scope].child = None;
+                // The scope is itself a child being purged
+                if !stack.is_empty() {
+                    self.release_scope(scope);
+                    self.remove_node(scope);
+                }
+                continue;
+            };
+            stack.last_mut().unwrap().1 = self.nodes[child].next;
 
             match self.nodes[child].inner {
                 NodeInner::Effect(_) | NodeInner::None => {
-                    self.purge_scope(child);
+                    stack.push((child, self.nodes[child].child));
                 }
                 NodeInner::Computed(_) | NodeInner::Signal(_) => {
                     self.purge_node(child);
+                    self.remove_node(child);
                 }
             }
-
-            self.nodes.remove(child);
         }
-
-        self.nodes[node].child = None;
     }
 
     /// Cleanup an scope node
     pub fn purge_scope(&mut self, node: NodeKey) {
         self.purge_child(node);
+        self.release_scope(node);
+    }
 
+    /// Drop the links of a scope whose children are already purged
+    fn release_scope(&mut self, node: NodeKey) {
         self.nodes[node].deps_tail = None;
-        self.nodes[node].flags = ReactiveFlags::NONE;
+        self.clear_watching(node);
+        self.nodes[node].flags.reset(ReactiveFlags::NONE);
         self.purge_deps(node, false);
 
         let subs = self.nodes[node].subs;
@@ -158,22 +387,61 @@ impl super::ReactiveSystem {
         self.purge_subs(node);
     }
 
-    /// Fully dispose a node (cleanup and remove)
-    pub fn dispose_scope(this: ReactiveSystemRef<Self>, node: NodeKey) {
+    /// Fully dispose a node (cleanup and remove), as requested at `caller`
+    #[allow(unused_variables)]
+    pub fn dispose_scope(this: ReactiveSystemRef<Self>, node: NodeKey, caller: Location) {
         if !this.borrow().nodes.contains_key(node) {
             return;
         }
+        #[cfg(debug_assertions)]
+        let prev = this.borrow_mut().begin_disposal(node, Some(caller));
         Self::cleanup_scope(this.clone(), node);
         this.borrow_mut().purge_scope(node);
         this.borrow_mut().unlink_child(node);
         this.borrow_mut().contexts.remove(node);
-        this.borrow_mut().nodes.remove(node);
+        this.borrow_mut().keyed_contexts.remove(node);
+        this.borrow_mut().remove_node(node);
+        #[cfg(debug_assertions)]
+        this.borrow_mut().end_disposal(prev);
+    }
+
+    /// Dispose a single signal or computed node, detaching it from its owner
+    /// and from every node that depends on it
+    #[allow(unused_variables)]
+    pub fn dispose_node(&mut self, node: NodeKey, caller: Location) {
+        if !self.nodes.contains_key(node) {
+            return;
+        }
+        #[cfg(debug_assertions)]
+        let prev = self.begin_disposal(node, Some(caller));
+        self.purge_node(node);
+        self.unlink_child(node);
+        self.remove_node(node);
+        #[cfg(debug_assertions)]
+        self.end_disposal(prev);
+    }
+
+    /// Run cleanups and drop the children of a scope, keeping the scope itself
+    ///
+    /// This is what an effect does before re-running; the scope's contexts
+    /// and position in the tree are preserved.
+    pub fn reset_scope(this: ReactiveSystemRef<Self>, node: NodeKey) {
+        if !this.borrow().nodes.contains_key(node) {
+            return;
+        }
+        Self::cleanup_scope(this.clone(), node);
+        this.borrow_mut().purge_child(node);
     }
 
-    pub fn cleanup(this: ReactiveSystemRef<Self>) {
+    #[allow(unused_variables)]
+    pub fn cleanup(this: ReactiveSystemRef<Self>, caller: Location) {
         let node = this.borrow().root;
+        #[cfg(debug_assertions)]
+        let prev = this.borrow_mut().begin_disposal(node, Some(caller));
         Self::cleanup_scope(this.clone(), node);
         this.borrow_mut().purge_scope(node);
         this.borrow_mut().unlink_child(node);
+        #[cfg(debug_assertions)]
+        this.borrow_mut().end_disposal(prev);
     }
 }
@@ -1,3 +1,4 @@
+use crate::flags::ReactiveFlags;
 use crate::types::{Link, LinkKey, NodeKey};
 
 impl super::ReactiveSystem {
@@ -37,7 +38,8 @@ impl super::ReactiveSystem {
     }
 
     /// Create a link between a dependency and a subscriber
-    pub fn link(&mut self, dep: NodeKey, sub: NodeKey, version: usize) {
+    pub fn link(&mut self, dep: NodeKey, sub: NodeKey, version: u32) {
+        self.check_not_observing();
         let prev_dep = self.nodes[sub].deps_tail;
         if let Some(prev_dep) = prev_dep
             && self.links[prev_dep].dep == dep
@@ -90,12 +92,78 @@ impl super::ReactiveSystem {
         } else {
             self.nodes[dep].subs = Some(new_link);
         }
+        if self.nodes[sub].flags.is_watched() {
+            self.add_watcher(dep);
+        }
+        self.observe(|observer| observer.link_created(dep, sub));
+    }
+
+    /// Count a new watched subscriber of `node`, flagging it and then its
+    /// transitive dependencies as having a watcher if it was the first
+    fn add_watcher(&mut self, node: NodeKey) {
+        let mut pending = vec![node];
+        while let Some(node) = pending.pop() {
+            let item = &mut self.nodes[node];
+            item.watched_subs += 1;
+            if item.watched_subs > 1 {
+                continue;
+            }
+            let watched = item.flags.is_watched();
+            item.flags = item.flags | ReactiveFlags::HAS_WATCHER;
+            if !watched {
+                self.push_deps(node, &mut pending);
+            }
+        }
+    }
+
+    /// Uncount a watched subscriber of `node`, clearing the watcher flag of
+    /// it and then of its transitive dependencies if it was the last
+    fn remove_watcher(&mut self, node: NodeKey) {
+        let mut pending = vec![node];
+        while let Some(node) = pending.pop() {
+            let Some(item) = self.nodes.get_mut(node) else {
+                continue;
+            };
+            item.watched_subs -= 1;
+            if item.watched_subs > 0 {
+                continue;
+            }
+            item.flags.remove(ReactiveFlags::HAS_WATCHER);
+            if !item.flags.is_watched() {
+                self.push_deps(node, &mut pending);
+            }
+        }
+    }
+
+    /// Remove `WATCHING` from `node`, uncounting it as a watched subscriber
+    /// of its dependencies if that leaves it unwatched
+    pub fn clear_watching(&mut self, node: NodeKey) {
+        let flags = &mut self.nodes[node].flags;
+        if !flags.contains(ReactiveFlags::WATCHING) {
+            return;
+        }
+        flags.remove(ReactiveFlags::WATCHING);
+        if !flags.is_watched() {
+            let mut pending = Vec::new();
+            self.push_deps(node, &mut pending);
+            for dep in pending {
+                self.remove_watcher(dep);
+            }
+        }
+    }
+
+    fn push_deps(&self, node: NodeKey, pending: &mut Vec<NodeKey>) {
+        let mut link = self.nodes[node].deps;
+        while let Some(key) = link {
+            pending.push(self.links[key].dep);
+            link = self.links[key].next_dep;
+        }
     }
 
     /// Core unlink logic: removes a link and updates all adjacent pointers
     /// Returns (next_dep, next_sub) for iteration purposes
     pub fn unlink(&mut self, link: LinkKey) {
-        let Self { nodes, links, .. } = self;
+        self.check_not_observing();
         let Some(Link {
             dep,
             sub,
@@ -104,10 +172,12 @@ impl super::ReactiveSystem {
             prev_dep,
             next_dep,
             ..
-        }) = links.remove(link)
+        }) = self.links.remove(link)
         else {
             return;
         };
+        self.observe(|observer| observer.link_removed(dep, sub));
+        let Self { nodes, links, .. } = self;
 
         // Update dep list in subscriber node
         if let Some(next_dep) = next_dep {
@@ -133,9 +203,13 @@ impl super::ReactiveSystem {
             links[prev_sub].next_sub = next_sub;
         } else {
             nodes[dep].subs = next_sub;
-            if next_sub.is_none() {
-                self.unwatched(dep);
-            }
+        }
+
+        if nodes[sub].flags.is_watched() {
+            self.remove_watcher(dep);
+        }
+        if prev_sub.is_none() && next_sub.is_none() {
+            self.unwatched(dep);
         }
     }
 }
@@ -0,0 +1,43 @@
+use super::ReactiveSystem;
+use crate::observer::RuntimeObserver;
+
+impl ReactiveSystem {
+    /// Call the installed observer and devtools streams, if any
+    #[inline]
+    pub fn observe(&self, event: impl Fn(&dyn RuntimeObserver)) {
+        if let Some(observer) = &self.observer {
+            #[cfg(debug_assertions)]
+            self.observing.set(true);
+            event(&**observer);
+            #[cfg(debug_assertions)]
+            self.observing.set(false);
+        }
+        #[cfg(feature = "devtools")]
+        for buffer in &self.devtools {
+            if let Some(buffer) = buffer.upgrade() {
+                event(&*buffer);
+            }
+        }
+    }
+
+    /// Whether `observe` would call anything, to skip work done only for
+    /// observers
+    #[inline]
+    pub fn is_observed(&self) -> bool {
+        #[cfg(feature = "devtools")]
+        if !self.devtools.is_empty() {
+            return true;
+        }
+        self.observer.is_some()
+    }
+
+    /// Panic in debug builds if an observer callback is using the system
+    #[inline]
+    pub fn check_not_observing(&self) {
+        #[cfg(debug_assertions)]
+        assert!(
+            !self.observing.get(),
+            "RuntimeObserver callbacks must not use the reactive system"
+        );
+    }
+}
@@ -1,40 +1,25 @@
+use std::collections::HashMap;
+
 use crate::{
+    explain::{PlannedMark, PlannedNode, PropagationPlan},
     system::ReactiveSystemRef,
     types::{Link, LinkKey, NodeKey, ReactiveFlags},
 };
 
 impl super::ReactiveSystem {
     /// Notify effects that need to run
+    ///
+    /// Effects owning `effect` through their owner links are queued with it,
+    /// outermost first.
     pub fn notify(&mut self, effect: NodeKey) {
-        let mut effect = effect;
-        let mut insert_index = self.queued_length;
-        let mut first_inserted_index = insert_index;
-
-        loop {
-            if insert_index >= self.queued.len() {
-                self.queued.push(effect);
-            } else {
-                self.queued[insert_index] = effect;
-            }
-            insert_index += 1;
-            let subs = self.nodes[effect].subs;
-            let Some(subs) = subs else {
-                break;
-            };
-            effect = self.links[subs].sub;
-            if !(self.nodes[effect].flags.contains(ReactiveFlags::WATCHING)) {
-                break;
-            }
-        }
-
-        self.queued_length = insert_index;
-        while first_inserted_index < {
-            insert_index -= 1;
-            insert_index
-        } {
-            self.queued.swap(first_inserted_index, insert_index);
-            first_inserted_index += 1;
-        }
+        let (nodes, links) = (&self.nodes, &self.links);
+        self.queue.push_chain(effect, |effect| {
+            let owner = links[nodes[effect].subs?].sub;
+            nodes[owner]
+                .flags
+                .contains(ReactiveFlags::WATCHING)
+                .then_some(owner)
+        });
     }
 
     /// Handle node that is no longer watched
@@ -43,49 +28,78 @@ impl super::ReactiveSystem {
             self.purge_scope(node);
         } else if self.nodes[node].deps_tail.is_some() {
             self.nodes[node].deps_tail = None;
-            self.nodes[node].flags = ReactiveFlags::MUTABLE | ReactiveFlags::DIRTY;
+            self.nodes[node]
+                .flags
+                .reset(ReactiveFlags::MUTABLE | ReactiveFlags::DIRTY);
             self.purge_deps(node, false);
         }
     }
 
     /// Propagate changes through subscribers
+    #[inline]
     pub fn propagate(&mut self, link: LinkKey) {
+        self.propagate_marking(link, false);
+    }
+
+    /// Propagate a write to the dependency of `link`, which is known to
+    /// have changed
+    ///
+    /// Its direct subscribers are marked dirty in the same pass rather than
+    /// pending, sparing the `shallow_propagate` that would otherwise walk
+    /// them again once the write is read. Only subscribers that were idle
+    /// are marked this way; returns false if any other was reached, in which
+    /// case the dependency still has to be updated and shallow-propagated
+    /// as usual.
+    #[inline]
+    pub fn propagate_write(&mut self, link: LinkKey) -> bool {
+        self.propagate_marking(link, true)
+    }
+
+    fn propagate_marking(&mut self, link: LinkKey, write: bool) -> bool {
         let mut link = link;
+        let source = self.links[link].dep;
         let mut next = self.links[link].next_sub;
-        self.stack.clear();
+        let mut settled = true;
+        let frame = self.stack.frame();
+        self.propagations += 1;
+        self.epoch += 1;
+        let epoch = self.epoch;
         'top: loop {
-            let sub_key = self.links[link].sub;
+            let Link {
+                dep, sub: sub_key, ..
+            } = self.links[link];
+            let direct = write && dep == source;
             let sub = &mut self.nodes[sub_key];
-            let mut flags = sub.flags;
+            let flags = sub.flags;
 
-            if !(flags.intersects(
-                ReactiveFlags::RECURSED_CHECK
-                    | ReactiveFlags::RECURSED
-                    | ReactiveFlags::DIRTY
-                    | ReactiveFlags::PENDING,
-            )) {
-                sub.flags = flags | ReactiveFlags::PENDING;
-            } else if !(flags.contains(ReactiveFlags::RECURSED_CHECK | ReactiveFlags::RECURSED)) {
-                flags = ReactiveFlags::NONE;
-            } else if !(flags.contains(ReactiveFlags::RECURSED_CHECK)) {
-                sub.flags = (flags & (!ReactiveFlags::RECURSED)) | ReactiveFlags::PENDING;
-            } else if !(flags.contains(ReactiveFlags::DIRTY | ReactiveFlags::PENDING))
-                && self.is_valid_link(link, sub_key)
-            {
-                self.nodes[sub_key].flags =
-                    flags | (ReactiveFlags::RECURSED | ReactiveFlags::PENDING);
-                flags &= ReactiveFlags::MUTABLE;
-            } else {
-                flags = ReactiveFlags::NONE;
-            }
-
-            if flags.contains(ReactiveFlags::WATCHING) {
-                self.notify(sub_key);
-            }
+            if sub.last_visited_epoch == epoch {
+                // Reached through another path of this pass, so it is
+                // pending at least but may not be dirty yet
+                if direct {
+                    settled = false;
+                }
+            } else if !flags.intersects(
+                ReactiveFlags::RECURSED_CHECK | ReactiveFlags::DIRTY | ReactiveFlags::PENDING,
+            ) {
+                // A node that is already pending or dirty was reached by an
+                // earlier pass its readers have yet to catch up with, and a
+                // running node is not re-run for writes made while it runs;
+                // nothing past either needs marking
+                sub.last_visited_epoch = epoch;
+                sub.flags = if direct {
+                    flags | ReactiveFlags::PENDING | ReactiveFlags::DIRTY
+                } else {
+                    flags | ReactiveFlags::PENDING
+                };
+                if flags.contains(ReactiveFlags::WATCHING) {
+                    self.notify(sub_key);
+                }
 
-            if flags.contains(ReactiveFlags::MUTABLE) {
-                let subs = self.nodes[sub_key].subs;
-                if let Some(subs) = subs {
+                // Nothing past an unwatched node needs notifying: its readers
+                // ask their dependencies instead, see `check_dirty`
+                if flags.contains(ReactiveFlags::MUTABLE | ReactiveFlags::HAS_WATCHER)
+                    && let Some(subs) = self.nodes[sub_key].subs
+                {
                     let next_sub = self.links[subs].next_sub;
                     link = subs;
                     if let Some(next_sub_val) = next_sub {
@@ -96,6 +110,110 @@ impl super::ReactiveSystem {
                     }
                     continue 'top;
                 }
+            } else if direct {
+                settled = false;
+            }
+
+            if let Some(next_sub) = next {
+                link = next_sub;
+                next = self.links[link].next_sub;
+                continue 'top;
+            }
+
+            if let Some(l) = self.stack.pop(frame) {
+                link = l;
+                next = self.links[link].next_sub;
+                continue 'top;
+            }
+
+            return settled;
+        }
+    }
+
+    /// Work out what `propagate` would do for a write to `source`, without
+    /// touching any flags
+    ///
+    /// Mirrors `propagate` and `notify` step for step, keeping the flags
+    /// they would set in an overlay.
+    pub fn explain_propagate(&self, source: NodeKey) -> PropagationPlan {
+        let mut plan = PropagationPlan {
+            source,
+            marked: Vec::new(),
+            effects: Vec::new(),
+        };
+        let Some(mut link) = self.nodes[source].subs else {
+            return plan;
+        };
+        let mut overlay: HashMap<NodeKey, ReactiveFlags> = HashMap::new();
+        let flags_of = |overlay: &HashMap<NodeKey, ReactiveFlags>, node: NodeKey| {
+            overlay
+                .get(&node)
+                .copied()
+                .unwrap_or(self.nodes[node].flags)
+        };
+        let mut paths = HashMap::from([(source, vec![source])]);
+        let mut next = self.links[link].next_sub;
+        let mut stack = Vec::new();
+        'top: loop {
+            let Link { dep, sub, .. } = self.links[link];
+            let mut flags = flags_of(&overlay, sub);
+
+            // Only idle nodes are marked, see `propagate`
+            let marked = !flags.intersects(
+                ReactiveFlags::RECURSED_CHECK | ReactiveFlags::DIRTY | ReactiveFlags::PENDING,
+            );
+            if marked {
+                overlay.insert(sub, flags | ReactiveFlags::PENDING);
+            } else {
+                flags = ReactiveFlags::NONE;
+            }
+
+            if marked {
+                let mut path = paths[&dep].clone();
+                path.push(sub);
+                let info = self.node_info(sub);
+                plan.marked.push(PlannedNode {
+                    node: sub,
+                    kind: info.kind,
+                    mark: if dep == source {
+                        PlannedMark::Dirty
+                    } else {
+                        PlannedMark::Pending
+                    },
+                    location: info.location,
+                    path: path.clone(),
+                });
+                paths.insert(sub, path);
+            }
+
+            if flags.contains(ReactiveFlags::WATCHING) {
+                // Like `notify`, queue enclosing watching effects ahead of
+                // the effect itself
+                let mut chain = vec![sub];
+                let mut effect = sub;
+                while let Some(subs) = self.nodes[effect].subs {
+                    effect = self.links[subs].sub;
+                    if !flags_of(&overlay, effect).contains(ReactiveFlags::WATCHING) {
+                        break;
+                    }
+                    chain.push(effect);
+                }
+                plan.effects.extend(chain.into_iter().rev());
+            }
+
+            if flags.contains(ReactiveFlags::MUTABLE)
+                && self.nodes[sub].flags.contains(ReactiveFlags::HAS_WATCHER)
+                && let Some(subs) = self.nodes[sub].subs
+            {
+                let next_sub = self.links[subs].next_sub;
+                link = subs;
+                if let Some(next_sub_val) = next_sub {
+                    if let Some(next) = next {
+                        stack.push(next);
+                    }
+                    next = Some(next_sub_val);
+                }
+                continue 'top;
             }
 
             if let Some(next_sub) = next {
@@ -104,13 +222,13 @@ impl super::ReactiveSystem {
                 continue 'top;
             }
 
-            if let Some(l) = self.stack.pop() {
+            if let Some(l) = stack.pop() {
                 link = l;
                 next = self.links[link].next_sub;
                 continue 'top;
             }
 
-            break;
+            return plan;
         }
     }
 
@@ -118,11 +236,32 @@ impl super::ReactiveSystem {
     pub fn check_dirty(this: ReactiveSystemRef<Self>, mut link: LinkKey, mut sub: NodeKey) -> bool {
         let mut check_depth = 0;
         let mut dirty = false;
-        this.borrow_mut().stack.clear();
+        // The nodes descended into so far, `checking[base..]`, to report
+        // cycles instead of looping or returning stale values
+        #[cfg(debug_assertions)]
+        let base = {
+            let checking = &mut this.borrow_mut().checking;
+            checking.push(sub);
+            checking.len() - 1
+        };
+        let frame = this.borrow().stack.frame();
         'top: loop {
             let dep = this.borrow().links[link].dep;
             let flags = this.borrow().nodes[dep].flags;
 
+            #[cfg(debug_assertions)]
+            {
+                let path = &this.borrow().checking[base..];
+                if flags.contains(ReactiveFlags::MUTABLE | ReactiveFlags::RECURSED_CHECK) {
+                    let cycle = this.borrow().evaluation_cycle(path, dep);
+                    this.borrow().cycle_detected(&cycle);
+                } else if let Some(start) = path.iter().position(|&node| node == dep) {
+                    let mut cycle = path[start..].to_vec();
+                    cycle.push(dep);
+                    this.borrow().cycle_detected(&cycle);
+                }
+            }
+
             if this.borrow().nodes[sub]
                 .flags
                 .contains(ReactiveFlags::DIRTY)
@@ -136,7 +275,10 @@ impl super::ReactiveSystem {
                     }
                     dirty = true;
                 }
-            } else if flags.contains(ReactiveFlags::MUTABLE | ReactiveFlags::PENDING) {
+            } else if flags.contains(ReactiveFlags::MUTABLE)
+                && (flags.contains(ReactiveFlags::PENDING)
+                    || Self::may_be_stale(flags, this.borrow().nodes[dep].deps))
+            {
                 if this.borrow_mut().links[link].next_sub.is_some()
                     || this.borrow_mut().links[link].prev_sub.is_some()
                 {
@@ -145,6 +287,8 @@ impl super::ReactiveSystem {
                 link = this.borrow_mut().nodes[dep].deps.unwrap();
                 sub = dep;
                 check_depth += 1;
+                #[cfg(debug_assertions)]
+                this.borrow_mut().checking.push(dep);
                 continue 'top;
             }
 
@@ -157,11 +301,13 @@ impl super::ReactiveSystem {
 
             while check_depth > 0 {
                 check_depth -= 1;
+                #[cfg(debug_assertions)]
+                this.borrow_mut().checking.pop();
                 let first_sub = this.borrow_mut().nodes[sub].subs.unwrap();
                 let has_multiple_subs = this.borrow_mut().links[first_sub].next_sub.is_some();
 
                 if has_multiple_subs {
-                    link = this.borrow_mut().stack.pop().unwrap();
+                    link = this.borrow_mut().stack.pop(frame).unwrap();
                 } else {
                     link = first_sub;
                 }
@@ -188,10 +334,22 @@ impl super::ReactiveSystem {
                 }
             }
 
+            #[cfg(debug_assertions)]
+            this.borrow_mut().checking.truncate(base);
             return dirty;
         }
     }
 
+    /// Whether a computed that `propagate` may have skipped has to check its
+    /// dependencies even though it is not marked pending
+    ///
+    /// Writes are not propagated past nodes without `HAS_WATCHER`, so the
+    /// computeds behind them are never marked and must be checked on read.
+    #[inline]
+    pub fn may_be_stale(flags: ReactiveFlags, deps: Option<LinkKey>) -> bool {
+        deps.is_some() && !flags.is_watched() && !flags.contains(ReactiveFlags::RECURSED_CHECK)
+    }
+
     /// Shallow propagation (mark dirty without full update)
     pub fn shallow_propagate(&mut self, link: LinkKey) {
         let mut link = link;
@@ -205,6 +363,16 @@ impl super::ReactiveSystem {
                 {
                     self.notify(sub);
                 }
+            } else if (flags
+                & (ReactiveFlags::MUTABLE
+                    | ReactiveFlags::WATCHING
+                    | ReactiveFlags::HAS_WATCHER
+                    | ReactiveFlags::DIRTY
+                    | ReactiveFlags::RECURSED_CHECK))
+                == ReactiveFlags::MUTABLE
+            {
+                // `propagate` did not reach it to mark it pending
+                self.nodes[sub].flags = flags | ReactiveFlags::DIRTY;
             }
             if let Some(next_sub) = next_sub {
                 link = next_sub;
@@ -213,16 +381,4 @@ impl super::ReactiveSystem {
             }
         }
     }
-
-    /// Check if a link is still valid
-    pub fn is_valid_link(&self, check_link: LinkKey, sub: NodeKey) -> bool {
-        let mut link = self.nodes[sub].deps_tail;
-        while let Some(link_key) = link {
-            if link_key == check_link {
-                return true;
-            }
-            link = self.links[link_key].prev_dep;
-        }
-        false
-    }
 }
@@ -0,0 +1,146 @@
+use std::collections::VecDeque;
+
+use crate::types::{LinkKey, NodeKey};
+
+/// Effects waiting to run, in the order they will run
+///
+/// An effect is queued at most once per flush; `propagate` only notifies
+/// effects that were not already pending. Popping keeps the allocation, so a
+/// steady workload stops allocating after the first flushes.
+#[derive(Default)]
+pub struct EffectQueue {
+    effects: VecDeque<NodeKey>,
+    /// Scratch space for `push_chain`, kept to reuse its allocation
+    chain: Vec<NodeKey>,
+    /// Number of effects taken off the front so far, see `position`
+    popped: usize,
+}
+
+impl EffectQueue {
+    /// Queue an effect and the watching effects that own it, outermost
+    /// first, so parents run before the children they may dispose
+    ///
+    /// `owner` returns the effect owning the given one, if it is watching.
+    pub fn push_chain(&mut self, effect: NodeKey, owner: impl Fn(NodeKey) -> Option<NodeKey>) {
+        self.chain
+            .extend(std::iter::successors(Some(effect), |&effect| owner(effect)));
+        self.effects.extend(self.chain.drain(..).rev());
+    }
+
+    /// Take the next effect to run
+    #[inline]
+    pub fn pop(&mut self) -> Option<NodeKey> {
+        let effect = self.effects.pop_front()?;
+        self.popped += 1;
+        Some(effect)
+    }
+
+    /// Number of effects queued so far, counting those already taken
+    ///
+    /// Pass it to `take_since` later to get back the effects queued after
+    /// this point that are still waiting.
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.popped + self.effects.len()
+    }
+
+    /// Remove the waiting effects queued after `position` returned `since`
+    pub fn take_since(&mut self, since: usize) -> Vec<NodeKey> {
+        let start = since.saturating_sub(self.popped).min(self.effects.len());
+        self.effects.drain(start..).collect()
+    }
+
+    /// Whether `effect` is waiting to run
+    pub fn contains(&self, effect: NodeKey) -> bool {
+        self.effects.contains(&effect)
+    }
+
+    /// Queue the effects waiting in `other` after the ones already waiting
+    pub fn append(&mut self, mut other: EffectQueue) {
+        self.effects.append(&mut other.effects);
+    }
+
+    /// Number of effects waiting to run
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.effects.len()
+    }
+
+    /// Whether no effect is waiting
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    /// Number of effects the queue can hold without reallocating
+    pub fn capacity(&self) -> usize {
+        self.effects.capacity()
+    }
+
+    /// Give back the room not taken by waiting effects
+    pub fn shrink_to_fit(&mut self) {
+        self.effects.shrink_to_fit();
+        self.chain.shrink_to_fit();
+    }
+}
+
+/// Links still to visit in `propagate` and `check_dirty`, deepest last
+///
+/// Starts with room for `LinkStack::INITIAL_CAPACITY` links, so traversals of
+/// ordinary depth never allocate, and keeps its allocation between calls.
+/// Remembers the deepest it has been, for `stats()`.
+///
+/// A traversal can start another one, as `check_dirty` does when it runs a
+/// getter that reads a dirty signal, so each works in a frame above the
+/// links of those in progress and only pops its own.
+pub struct LinkStack {
+    links: Vec<LinkKey>,
+    high_water: usize,
+}
+
+impl LinkStack {
+    pub const INITIAL_CAPACITY: usize = 32;
+
+    /// Start a traversal above the links of those in progress, returning
+    /// the frame to pop from
+    #[inline]
+    pub fn frame(&self) -> usize {
+        self.links.len()
+    }
+
+    #[inline]
+    pub fn push(&mut self, link: LinkKey) {
+        self.links.push(link);
+        self.high_water = self.high_water.max(self.links.len());
+    }
+
+    /// Take the last link pushed in `frame`, if any is left
+    #[inline]
+    pub fn pop(&mut self, frame: usize) -> Option<LinkKey> {
+        if self.links.len() > frame {
+            self.links.pop()
+        } else {
+            None
+        }
+    }
+
+    /// The most links the stack has held at once
+    pub fn high_water(&self) -> usize {
+        self.high_water
+    }
+
+    /// Give back the room a deep traversal grew the stack to, down to
+    /// `INITIAL_CAPACITY`
+    pub fn shrink_to_fit(&mut self) {
+        self.links.shrink_to(Self::INITIAL_CAPACITY);
+    }
+}
+
+impl Default for LinkStack {
+    fn default() -> Self {
+        Self {
+            links: Vec::with_capacity(Self::INITIAL_CAPACITY),
+            high_water: 0,
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::system::ReactiveSystemRef;
-use crate::types::Location;
+use crate::types::{BorrowConflict, Location};
 use crate::{
     flags::ReactiveFlags,
     types::{NodeInner, NodeKey, ReactiveNode, SignalNode},
@@ -11,7 +11,7 @@ impl super::ReactiveSystem {
     pub fn signal_new<T: 'static>(&mut self, initial: T, caller: Location) -> NodeKey {
         use crate::types::BorrowState;
         use std::cell::Cell;
-        let node = self.nodes.insert(ReactiveNode::new(
+        let node = self.insert_node(ReactiveNode::new(
             NodeInner::Signal(SignalNode {
                 value: Box::leak(Box::new(initial)),
                 borrow_state: Cell::new(BorrowState::Unused),
@@ -25,14 +25,15 @@ impl super::ReactiveSystem {
     }
 
     /// Get a signal from node key
+    ///
+    /// Its value sits behind a pointer and its borrow state in a `Cell`, so
+    /// reading or borrowing it needs only shared access to the system.
     #[inline]
-    pub fn signal(&mut self, node: NodeKey) -> &mut SignalNode {
-        let NodeInner::Signal(signal) = &mut self
-            .nodes
-            .get_mut(node)
-            .expect("Signal accessed after cleanup")
-            .inner
-        else {
+    pub fn signal(&self, node: NodeKey) -> &SignalNode {
+        if !self.nodes.contains_key(node) {
+            self.accessed_after_cleanup(node);
+        }
+        let NodeInner::Signal(signal) = &self.nodes[node].inner else {
             panic!("Node is not a Signal");
         };
         return signal;
@@ -41,6 +42,10 @@ impl super::ReactiveSystem {
     /// Track a signal access for reactive dependencies
     #[inline]
     pub fn signal_track(&mut self, node: NodeKey) {
+        self.check_not_observing();
+        if !self.nodes.contains_key(node) {
+            self.accessed_after_cleanup(node);
+        }
         if self.nodes[node]
             .flags
             .contains(crate::types::ReactiveFlags::DIRTY)
@@ -48,6 +53,11 @@ impl super::ReactiveSystem {
             self.update_signal(node);
             let subs = self.nodes[node].subs;
             if let Some(subs) = subs {
+                // Subscribers the write left pending have to recompute, as
+                // when `check_dirty` updates the signal; this read may come
+                // first, from a getter that was marked dirty directly, and
+                // would otherwise leave them to find the signal clean
+                self.shallow_propagate(subs);
                 self.propagate(subs);
             }
         }
@@ -69,37 +79,80 @@ impl super::ReactiveSystem {
 
     /// Get a signal value (with tracking)
     #[inline]
-    pub fn signal_get<T: 'static + Clone>(&mut self, node: NodeKey) -> T {
+    pub fn signal_get<T: 'static + Clone>(&self, node: NodeKey) -> T {
         unsafe { &*(self.signal(node).value as *const dyn Any as *const T) }.clone()
     }
 
     /// Notify subscribers of a signal change
     #[inline]
     pub fn signal_notify(this: ReactiveSystemRef<Self>, node: NodeKey) {
-        let subs = {
-            let node = &mut this.borrow_mut().nodes[node];
-            node.flags = ReactiveFlags::MUTABLE | ReactiveFlags::DIRTY;
-            let subs = node.subs;
-            subs
-        };
+        this.borrow().announce_set(node);
+        let subs = this.borrow().nodes[node].subs;
+        // The subscribers learn the signal changed right away unless some
+        // were busy, in which case marking it dirty lets reading it catch
+        // them up
+        let settled = subs.is_some_and(|subs| this.borrow_mut().propagate_write(subs));
+        this.borrow_mut().nodes[node].flags.reset(if settled {
+            ReactiveFlags::MUTABLE
+        } else {
+            ReactiveFlags::MUTABLE | ReactiveFlags::DIRTY
+        });
 
-        if let Some(subs) = subs {
-            this.borrow_mut().propagate(subs);
-            if this.borrow_mut().batch_depth == 0 {
-                Self::flush(this.clone());
-            }
+        if subs.is_some() && this.borrow().flushes_immediately() {
+            Self::flush(this.clone());
         }
     }
 
+    /// Tell tracing and observers that a signal was set
+    #[inline]
+    fn announce_set(&self, node: NodeKey) {
+        self.check_not_observing();
+        #[cfg(feature = "tracing")]
+        tracing::trace!(
+            node = ?node,
+            batched = !self.flushes_immediately(),
+            "signal_set"
+        );
+        self.observe(|observer| observer.signal_set(node));
+    }
+
     /// Set a signal value
+    ///
+    /// The old value is swapped out and dropped after the new one is in
+    /// place, so the write never has to move the borrow state through
+    /// `Writing`; it only needs to check that no guard is held. A signal
+    /// nothing subscribes to is then just marked dirty, for the next tracked
+    /// read to pick up, without going through `propagate` or a flush.
     #[inline]
-    pub fn signal_set<T: 'static>(this: ReactiveSystemRef<Self>, node: NodeKey, value: T) {
-        {
-            let mut binding = this.borrow_mut();
-            let signal = binding.signal(node);
-            signal.borrow_write_check();
-            unsafe { *(signal.value as *mut dyn Any as *mut T) = value };
-            signal.release_write();
+    pub fn signal_set<T: 'static>(
+        this: ReactiveSystemRef<Self>,
+        node: NodeKey,
+        value: T,
+        caller: Location,
+    ) {
+        let system = this.borrow_mut();
+        if system.in_transaction() {
+            Self::transaction_set(this, node, value, caller);
+            return;
+        }
+        let signal = system.signal(node);
+        let slot = signal.value.cast::<T>();
+        if signal.held_at().is_some() {
+            // Panics with where the guard in the way was taken
+            system.signal_write_check(node, caller);
+        }
+        drop(unsafe { std::ptr::replace(slot, value) });
+
+        let system = this.borrow_mut();
+        let subs = system.nodes[node].subs;
+        if subs.is_none() {
+            // Without subscribers there is nothing to propagate to, in a
+            // batch or not
+            system.announce_set(node);
+            system.nodes[node]
+                .flags
+                .reset(ReactiveFlags::MUTABLE | ReactiveFlags::DIRTY);
+            return;
         }
         Self::signal_notify(this, node);
     }
@@ -109,37 +162,102 @@ impl super::ReactiveSystem {
     pub fn signal_update<T: 'static>(
         this: ReactiveSystemRef<Self>,
         node: NodeKey,
-        f: impl FnOnce(&mut T) -> (),
+        f: impl FnOnce(&mut T),
+        caller: Location,
     ) {
+        this.borrow().in_place_write_check(node, caller);
         {
-            let mut binding = this.borrow_mut();
-            let signal = binding.signal(node);
+            let signal = this.borrow().signal(node);
             f(unsafe { &mut *(signal.value as *mut dyn Any as *mut T) });
         }
         Self::signal_notify(this, node);
     }
 
-    /// Check if a read borrow is allowed, panic if not
+    /// Check if a read borrow at `caller` is allowed, panic if not
     #[inline]
-    pub fn signal_borrow_read_check(&mut self, node: NodeKey) {
-        self.signal(node).borrow_read_check();
+    pub fn signal_borrow_read_check(&self, node: NodeKey, caller: Location) {
+        if let Err(conflict) = self.signal(node).try_borrow_read(caller) {
+            self.borrow_conflict(node, conflict, "read", caller);
+        }
     }
 
-    /// Check if a write borrow is allowed, panic if not
+    /// Check if a write borrow at `caller` is allowed, panic if not
     #[inline]
-    pub fn signal_borrow_write_check(&mut self, node: NodeKey) {
-        self.signal(node).borrow_write_check();
+    pub fn signal_write_check(&self, node: NodeKey, caller: Location) {
+        if let Err(conflict) = self.signal(node).try_borrow_write(caller) {
+            self.borrow_conflict(node, conflict, "write", caller);
+        }
+    }
+
+    #[cold]
+    #[allow(unused_variables)]
+    fn borrow_conflict(
+        &self,
+        node: NodeKey,
+        conflict: BorrowConflict,
+        operation: &str,
+        caller: Location,
+    ) -> ! {
+        #[allow(unused_mut)]
+        let mut message = String::from(conflict.message);
+        #[cfg(any(debug_assertions, feature = "track-caller"))]
+        {
+            use crate::types::format_location;
+            use std::fmt::Write;
+
+            let _ = write!(
+                message,
+                "\n  signal created at {}\n  {operation} attempted at {}\n  {} guard taken at {}",
+                format_location(&self.nodes[node].caller),
+                format_location(&caller),
+                conflict.held_as,
+                format_location(&conflict.held_at),
+            );
+        }
+        panic!("{message}{}", self.describe_owners(node));
+    }
+
+    #[cold]
+    #[allow(unused_variables)]
+    pub(super) fn disposed_while_borrowed(&self, node: NodeKey, held_at: Location) -> ! {
+        #[allow(unused_mut)]
+        let mut message = String::from("Signal disposed while a guard still borrows it");
+        #[cfg(any(debug_assertions, feature = "track-caller"))]
+        {
+            use crate::types::format_location;
+            use std::fmt::Write;
+
+            let _ = write!(
+                message,
+                "\n  signal created at {}\n  guard taken at {}",
+                format_location(&self.nodes[node].caller),
+                format_location(&held_at),
+            );
+        }
+        panic!("{message}{}", self.describe_owners(node));
+    }
+
+    #[cold]
+    #[allow(unused_variables)]
+    fn accessed_after_cleanup(&self, node: NodeKey) -> ! {
+        #[cfg(debug_assertions)]
+        panic!(
+            "Signal accessed after cleanup{}",
+            self.describe_tombstone(node)
+        );
+        #[cfg(not(debug_assertions))]
+        panic!("Signal accessed after cleanup");
     }
 
     /// Release a read borrow
     #[inline]
-    pub fn signal_release_read(&mut self, node: NodeKey) {
+    pub fn signal_release_read(&self, node: NodeKey) {
         self.signal(node).release_read();
     }
 
     /// Release a write borrow
     #[inline]
-    pub fn signal_release_write(&mut self, node: NodeKey) {
+    pub fn signal_release_write(&self, node: NodeKey) {
         self.signal(node).release_write();
     }
 }
@@ -0,0 +1,205 @@
+use std::collections::{HashMap, HashSet};
+
+use super::ReactiveSystem;
+use crate::flags::ReactiveFlags;
+use crate::leak::LeakReport;
+use crate::snapshot::{GraphSnapshot, LinkInfo, NodeInfo, NodeKind};
+use crate::types::{NodeInner, NodeKey, ReactiveNode, caller};
+
+/// What an inert node rebuilt by `restore_topology` stands in for
+pub struct Placeholder {
+    pub kind: NodeKind,
+    pub location: Option<String>,
+}
+
+impl ReactiveSystem {
+    /// Build a named-field copy of the graph's topology
+    pub fn snapshot(&self) -> GraphSnapshot {
+        let nodes = self.nodes.keys().map(|key| self.node_info(key)).collect();
+        let links = self
+            .links
+            .iter()
+            .map(|(key, link)| LinkInfo {
+                key,
+                dep: link.dep,
+                sub: link.sub,
+            })
+            .collect();
+        GraphSnapshot {
+            root: self.root,
+            nodes,
+            links,
+        }
+    }
+
+    /// Describe a single node as it appears in a snapshot
+    pub fn node_info(&self, key: NodeKey) -> NodeInfo {
+        let node = &self.nodes[key];
+        let placeholder = self.placeholders.get(key);
+        NodeInfo {
+            key,
+            kind: match placeholder {
+                Some(placeholder) => placeholder.kind,
+                None => node_kind(node),
+            },
+            flags: node.flags.names().into_iter().map(String::from).collect(),
+            location: match placeholder {
+                Some(placeholder) => placeholder.location.clone(),
+                None => node_location(node),
+            },
+            name: node.name.as_deref().cloned(),
+            parent: node.parent,
+            subscribers: count_links(node.subs, |link| self.links[link].next_sub),
+            dependencies: count_links(node.deps, |link| self.links[link].next_dep),
+        }
+    }
+
+    /// Find the nodes the ownership tree no longer reaches and the links
+    /// touching them, and list what the root scope still owns
+    pub fn leak_report(&self) -> LeakReport {
+        let mut reachable = HashSet::with_capacity(self.nodes.len());
+        let mut pending = vec![self.root];
+        while let Some(node) = pending.pop() {
+            if !reachable.insert(node) {
+                continue;
+            }
+            let mut child = self.nodes.get(node).and_then(|node| node.child);
+            while let Some(key) = child {
+                pending.push(key);
+                child = self.nodes.get(key).and_then(|node| node.next);
+            }
+        }
+
+        let unreachable = self
+            .nodes
+            .keys()
+            .filter(|key| !reachable.contains(key))
+            .map(|key| self.node_info(key))
+            .collect();
+        let links = self
+            .links
+            .iter()
+            .filter(|(_, link)| !reachable.contains(&link.dep) || !reachable.contains(&link.sub))
+            .map(|(key, link)| LinkInfo {
+                key,
+                dep: link.dep,
+                sub: link.sub,
+            })
+            .collect();
+        let root_owned =
+            std::iter::successors(self.nodes[self.root].child, |&key| self.nodes[key].next)
+                .map(|key| self.node_info(key))
+                .collect();
+
+        LeakReport {
+            counts: self.count(),
+            unreachable,
+            links,
+            root_owned,
+        }
+    }
+
+    /// Count live nodes other than the root by creation location and kind,
+    /// most common first
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    pub fn nodes_by_location(&self) -> Vec<(crate::types::Location, NodeKind, usize)> {
+        let mut counts = HashMap::new();
+        for (key, node) in self.nodes.iter() {
+            if key == self.root {
+                continue;
+            }
+            let kind = match self.placeholders.get(key) {
+                Some(placeholder) => placeholder.kind,
+                None => node_kind(node),
+            };
+            *counts.entry((node.caller, kind)).or_insert(0) += 1;
+        }
+        let mut counts: Vec<_> = counts
+            .into_iter()
+            .map(|((location, kind), count)| (location, kind, count))
+            .collect();
+        counts.sort_by(|a, b| {
+            b.2.cmp(&a.2)
+                .then_with(|| a.0.cmp(b.0))
+                .then_with(|| (a.1 as u8).cmp(&(b.1 as u8)))
+        });
+        counts
+    }
+
+    /// Rebuild the topology of `snapshot` from inert placeholder nodes
+    ///
+    /// The snapshot's root maps to this system's root; every other node is
+    /// added as a `NodeInner::None` node that remembers the kind and
+    /// location it stands in for. Returns the key of each restored node by
+    /// its key in the snapshot.
+    pub fn restore_topology(&mut self, snapshot: &GraphSnapshot) -> HashMap<NodeKey, NodeKey> {
+        let mut keys = HashMap::with_capacity(snapshot.nodes.len());
+        keys.insert(snapshot.root, self.root);
+
+        // Parents may be listed after their children, so create every node
+        // before linking them
+        for info in &snapshot.nodes {
+            if info.key == snapshot.root {
+                continue;
+            }
+            // Linking below works out which restored nodes have watchers
+            let mut flags = ReactiveFlags::from_names(info.flags.iter().map(String::as_str));
+            flags.remove(ReactiveFlags::HAS_WATCHER);
+            let mut node = ReactiveNode::new(NodeInner::None, flags, None, caller());
+            node.name = info.name.clone().map(Box::new);
+            let key = self.insert_node(node);
+            self.placeholders.insert(
+                key,
+                Placeholder {
+                    kind: info.kind,
+                    location: info.location.clone(),
+                },
+            );
+            keys.insert(info.key, key);
+        }
+
+        for info in &snapshot.nodes {
+            if info.key == snapshot.root {
+                continue;
+            }
+            let key = keys[&info.key];
+            let parent = info
+                .parent
+                .and_then(|parent| keys.get(&parent).copied())
+                .unwrap_or(self.root);
+            self.nodes[key].parent = Some(parent);
+            self.link_child(key);
+        }
+
+        for link in &snapshot.links {
+            if let (Some(&dep), Some(&sub)) = (keys.get(&link.dep), keys.get(&link.sub)) {
+                self.link(dep, sub, 0);
+            }
+        }
+
+        keys
+    }
+}
+
+fn count_links<K: Copy>(head: Option<K>, next: impl Fn(K) -> Option<K>) -> usize {
+    std::iter::successors(head, |&link| next(link)).count()
+}
+
+pub(super) fn node_kind(node: &ReactiveNode) -> NodeKind {
+    match node.inner {
+        NodeInner::None => NodeKind::Scope,
+        NodeInner::Signal(_) => NodeKind::Signal,
+        NodeInner::Computed(_) => NodeKind::Computed,
+        NodeInner::Effect(_) => NodeKind::Effect,
+    }
+}
+
+#[cfg(any(debug_assertions, feature = "track-caller"))]
+fn node_location(node: &ReactiveNode) -> Option<String> {
+    Some(crate::types::format_location(&node.caller))
+}
+
+#[cfg(not(any(debug_assertions, feature = "track-caller")))]
+fn node_location(_: &ReactiveNode) -> Option<String> {
+    None
+}
@@ -0,0 +1,101 @@
+use std::collections::VecDeque;
+
+use slotmap::SparseSecondaryMap;
+
+use super::ReactiveSystem;
+use super::snapshot::node_kind;
+use crate::snapshot::NodeKind;
+use crate::types::{Location, NodeKey, format_location};
+
+/// How many removed nodes are remembered for diagnostics
+const MAX_TOMBSTONES: usize = 1024;
+
+/// What is left of a removed node, to explain later uses of its handle
+pub struct Tombstone {
+    pub kind: NodeKind,
+    pub created_at: Location,
+    /// Name and creation site of the scope whose disposal removed the node
+    pub owner: Option<(Option<String>, Location)>,
+    /// Where that disposal was requested, if it came from a dispose call
+    pub disposed_at: Option<Location>,
+}
+
+/// A disposal in progress
+#[derive(Clone, Copy)]
+pub struct Disposal {
+    pub node: NodeKey,
+    pub at: Option<Location>,
+}
+
+/// The most recently removed nodes, oldest evicted first
+#[derive(Default)]
+pub struct Tombstones {
+    map: SparseSecondaryMap<NodeKey, Tombstone>,
+    order: VecDeque<NodeKey>,
+}
+
+impl ReactiveSystem {
+    /// Attribute the nodes removed from now on to disposing `node`, as
+    /// requested at `at`; returns the disposal to restore afterwards
+    pub fn begin_disposal(&mut self, node: NodeKey, at: Option<Location>) -> Option<Disposal> {
+        self.disposal.replace(Disposal { node, at })
+    }
+
+    /// Restore the disposal that was in progress before `begin_disposal`
+    pub fn end_disposal(&mut self, prev: Option<Disposal>) {
+        self.disposal = prev;
+    }
+
+    /// Remember `node`, which is about to be removed
+    pub fn bury(&mut self, node: NodeKey) {
+        let Some(item) = self.nodes.get(node) else {
+            return;
+        };
+        let owner_key = match self.disposal {
+            Some(disposal) => Some(disposal.node),
+            None => item.parent,
+        };
+        let owner = owner_key
+            .and_then(|key| self.nodes.get(key))
+            .map(|owner| (owner.name.as_deref().cloned(), owner.caller));
+        let tombstone = Tombstone {
+            kind: node_kind(item),
+            created_at: item.caller,
+            owner,
+            disposed_at: self.disposal.and_then(|disposal| disposal.at),
+        };
+
+        let tombstones = &mut self.tombstones;
+        tombstones.map.insert(node, tombstone);
+        tombstones.order.push_back(node);
+        if tombstones.order.len() > MAX_TOMBSTONES
+            && let Some(oldest) = tombstones.order.pop_front()
+        {
+            tombstones.map.remove(oldest);
+        }
+    }
+
+    /// Where a removed node was created and disposed, formatted for
+    /// appending to a panic message; empty if it is no longer remembered
+    pub fn describe_tombstone(&self, node: NodeKey) -> String {
+        let Some(tombstone) = self.tombstones.map.get(node) else {
+            return String::new();
+        };
+        let mut message = format!(
+            "\n  {:?} created at {}",
+            tombstone.kind,
+            format_location(&tombstone.created_at)
+        );
+        if let Some((name, location)) = &tombstone.owner {
+            message.push_str("\n  disposed with scope ");
+            if let Some(name) = name {
+                message.push_str(&format!("{name:?} "));
+            }
+            message.push_str(&format!("created at {}", format_location(location)));
+        }
+        if let Some(at) = &tombstone.disposed_at {
+            message.push_str(&format!("\n  dispose called at {}", format_location(at)));
+        }
+        message
+    }
+}
@@ -0,0 +1,210 @@
+use crate::system::ReactiveSystemRef;
+use crate::types::{
+    Link, Location, NodeInner, NodeKey, ReactiveFlags, ReactiveNode, caller, format_location,
+};
+
+/// Writes recorded by an open transaction
+#[derive(Default)]
+pub struct TransactionLog {
+    /// Restores the value replaced by each write, in write order
+    pub restores: Vec<Box<dyn FnOnce()>>,
+    /// Signals written during the transaction, notified again on rollback
+    pub touched: Vec<NodeKey>,
+    /// Position of the effect queue when the transaction started; effects
+    /// queued after it are left to run by the transaction's writes
+    pub queued: usize,
+    /// Effects that ran during the transaction, seeing its writes
+    pub ran: Vec<NodeKey>,
+}
+
+impl super::ReactiveSystem {
+    /// Open a new (possibly nested) transaction
+    ///
+    /// Writes made inside it propagate as usual, so computeds read in the
+    /// transaction see them, but it holds a batch open until it is closed
+    /// so no effect runs before then.
+    pub fn start_transaction(&mut self) {
+        self.transactions.push(TransactionLog {
+            queued: self.queue.position(),
+            ..TransactionLog::default()
+        });
+        self.start_batch();
+    }
+
+    /// Whether a transaction is currently open
+    #[inline]
+    pub fn in_transaction(&self) -> bool {
+        !self.transactions.is_empty()
+    }
+
+    /// Write a signal value inside a transaction, recording the old value
+    pub fn transaction_set<T: 'static>(
+        this: ReactiveSystemRef<Self>,
+        node: NodeKey,
+        value: T,
+        caller: Location,
+    ) {
+        let old = {
+            let binding = this.borrow_mut();
+            binding.signal_write_check(node, caller);
+            let signal = binding.signal(node);
+            let old = std::mem::replace(unsafe { &mut *(signal.value as *mut T) }, value);
+            signal.release_write();
+            old
+        };
+        Self::record_restore(this, node, old);
+        Self::signal_notify(this, node);
+    }
+
+    /// Panic if `node` is about to be modified in place inside a
+    /// transaction that kept no value to restore it to
+    ///
+    /// Only a write that replaces the value, or a copy taken by
+    /// `record_write`, can be undone; once either is recorded, later
+    /// in-place writes are undone with it.
+    pub fn in_place_write_check(&self, node: NodeKey, caller: Location) {
+        if let Some(log) = self.transactions.last()
+            && !log.touched.contains(&node)
+        {
+            panic!(
+                "Signal::update and Signal::write cannot be rolled back inside a transaction, \
+                 replace the value with Signal::set instead\n  write attempted at {}",
+                format_location(&caller)
+            );
+        }
+    }
+
+    /// Keep a copy of the value of `node` before it is modified in place,
+    /// if a transaction is open
+    pub fn record_write<T: Clone + 'static>(this: ReactiveSystemRef<Self>, node: NodeKey) {
+        let recorded = match this.borrow().transactions.last() {
+            Some(log) => log.touched.contains(&node),
+            None => return,
+        };
+        // The first value recorded is the one restored
+        if recorded {
+            return;
+        }
+        let old = unsafe { &*(this.borrow().signal(node).value as *const T) }.clone();
+        Self::record_restore(this, node, old);
+    }
+
+    /// Record `old` as the value to give `node` back if the innermost
+    /// transaction is rolled back
+    fn record_restore<T: 'static>(this: ReactiveSystemRef<Self>, node: NodeKey, old: T) {
+        let log = this.borrow_mut().transactions.last_mut().unwrap();
+        log.restores.push(Box::new(move || {
+            // The signal may have been disposed inside the transaction
+            if let Some(ReactiveNode {
+                inner: NodeInner::Signal(signal),
+                ..
+            }) = this.borrow_mut().nodes.get_mut(node)
+            {
+                unsafe { *(signal.value as *mut T) = old };
+            }
+        }));
+        if !log.touched.contains(&node) {
+            log.touched.push(node);
+        }
+    }
+
+    /// Close the innermost transaction, keeping its writes
+    pub fn commit_transaction(this: ReactiveSystemRef<Self>) {
+        let TransactionLog {
+            restores,
+            touched,
+            ran,
+            ..
+        } = this.borrow_mut().transactions.pop().unwrap();
+
+        // A nested transaction hands its writes over to the enclosing one
+        if let Some(outer) = this.borrow_mut().transactions.last_mut() {
+            outer.restores.extend(restores);
+            for node in touched {
+                if !outer.touched.contains(&node) {
+                    outer.touched.push(node);
+                }
+            }
+            outer.ran.extend(ran);
+        }
+        Self::end_batch(this, caller());
+    }
+
+    /// Close the innermost transaction, restoring every value it replaced
+    ///
+    /// The restored signals are notified again, so computeds that read the
+    /// discarded values catch up. Effects the discarded writes queued would
+    /// find the values they last ran with, so they are settled instead of
+    /// run; effects that were already queued, or that ran during the
+    /// transaction, run as usual.
+    pub fn rollback_transaction(this: ReactiveSystemRef<Self>) {
+        let TransactionLog {
+            restores,
+            touched,
+            queued,
+            ran,
+        } = this.borrow_mut().transactions.pop().unwrap();
+        for restore in restores.into_iter().rev() {
+            restore();
+        }
+        for node in touched {
+            if this.borrow().nodes.contains_key(node) {
+                Self::signal_notify(this, node);
+            }
+        }
+
+        // Settling an effect can update computeds that queue more of them
+        let mut rerun = Vec::new();
+        loop {
+            let waiting = this.borrow_mut().queue.take_since(queued);
+            if waiting.is_empty() {
+                break;
+            }
+            for effect in waiting {
+                if !this.borrow().nodes.contains_key(effect) {
+                    continue;
+                }
+                if ran.contains(&effect)
+                    || rerun.contains(&effect)
+                    || this.borrow().queue.contains(effect)
+                {
+                    rerun.push(effect);
+                } else {
+                    Self::settle(this, effect);
+                }
+            }
+        }
+        for effect in rerun {
+            this.borrow_mut().queue.push_chain(effect, |_| None);
+        }
+        Self::end_batch(this, caller());
+    }
+
+    /// Bring the computeds `effect` reads up to date and clear its marks,
+    /// without running it
+    fn settle(this: ReactiveSystemRef<Self>, effect: NodeKey) {
+        let prev_sub = this.borrow().set_active_sub(None);
+        let mut link = this.borrow().nodes[effect].deps;
+        while let Some(key) = link {
+            let Link { dep, next_dep, .. } = this.borrow().links[key];
+            if matches!(this.borrow().nodes[dep].inner, NodeInner::Computed(_)) {
+                Self::computed_track(this, dep);
+            }
+            link = next_dep;
+        }
+        this.borrow().set_active_sub(prev_sub);
+        if let Some(node) = this.borrow_mut().nodes.get_mut(effect) {
+            node.flags
+                .remove(ReactiveFlags::DIRTY | ReactiveFlags::PENDING);
+        }
+    }
+
+    /// Note that `effect` ran, so that rolling back the innermost
+    /// transaction runs it again with the restored values
+    #[inline]
+    pub fn record_run(&mut self, effect: NodeKey) {
+        if let Some(log) = self.transactions.last_mut() {
+            log.ran.push(effect);
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use super::ReactiveSystem;
+use crate::flags::ReactiveFlags;
+use crate::types::{LinkKey, NodeInner, NodeKey};
+use crate::validate::InvariantViolation;
+
+impl ReactiveSystem {
+    /// Check the links and the ownership tree for broken pointers
+    pub fn validate(&self) -> Result<(), Vec<InvariantViolation>> {
+        let mut violations = Vec::new();
+
+        for (link, item) in self.links.iter() {
+            if !self.nodes.contains_key(item.dep) || !self.nodes.contains_key(item.sub) {
+                violations.push(InvariantViolation::DanglingLink {
+                    link,
+                    dep: item.dep,
+                    sub: item.sub,
+                });
+            }
+        }
+
+        let mut chained = 0;
+        for (node, item) in self.nodes.iter() {
+            chained += self.validate_chain(
+                node,
+                item.deps,
+                item.deps_tail,
+                Chain::Deps,
+                &mut violations,
+            );
+            chained += self.validate_chain(
+                node,
+                item.subs,
+                item.subs_tail,
+                Chain::Subs,
+                &mut violations,
+            );
+            self.validate_tree(node, &mut violations);
+
+            if item.flags.contains(ReactiveFlags::WATCHING)
+                && !matches!(item.inner, NodeInner::Effect(_))
+                && !self.placeholders.contains_key(node)
+            {
+                violations.push(InvariantViolation::WatchingWithoutEffect { node });
+            }
+
+            let flagged = item.flags.contains(ReactiveFlags::HAS_WATCHER);
+            let watched = std::iter::successors(item.subs, |&link| {
+                self.links.get(link).and_then(|link| link.next_sub)
+            })
+            .take(self.links.len())
+            .filter(|&link| {
+                self.links
+                    .get(link)
+                    .and_then(|link| self.nodes.get(link.sub))
+                    .is_some_and(|sub| sub.flags.is_watched())
+            })
+            .count() as u32;
+            if item.watched_subs != watched {
+                violations.push(InvariantViolation::StaleWatcherCount {
+                    node,
+                    counted: item.watched_subs,
+                    watched,
+                });
+            }
+            let watched = watched > 0;
+            if flagged != watched {
+                violations.push(InvariantViolation::StaleWatcherFlag { node, flagged });
+            }
+        }
+
+        // Every link belongs to exactly one dep chain and one sub chain
+        if chained != 2 * self.links.len() {
+            for (link, item) in self.links.iter() {
+                let in_deps = self.chain_contains(item.sub, link, Chain::Deps);
+                let in_subs = self.chain_contains(item.dep, link, Chain::Subs);
+                if !in_deps || !in_subs {
+                    violations.push(InvariantViolation::UnchainedLink { link });
+                }
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Walk one dependency or subscriber chain of `node`, returning how many
+    /// links it holds
+    fn validate_chain(
+        &self,
+        node: NodeKey,
+        head: Option<LinkKey>,
+        tail: Option<LinkKey>,
+        chain: Chain,
+        violations: &mut Vec<InvariantViolation>,
+    ) -> usize {
+        let mut count = 0;
+        let mut prev = None;
+        let mut current = head;
+        while let Some(link) = current {
+            let Some(item) = self.links.get(link) else {
+                violations.push(chain.broken(node, link));
+                return count;
+            };
+            let (owner, item_prev, next) = match chain {
+                Chain::Deps => (item.sub, item.prev_dep, item.next_dep),
+                Chain::Subs => (item.dep, item.prev_sub, item.next_sub),
+            };
+            if owner != node || item_prev != prev {
+                violations.push(chain.broken(node, link));
+                return count;
+            }
+            count += 1;
+            // A cycle in the chain would hold more links than exist
+            if count > self.links.len() {
+                violations.push(chain.broken(node, link));
+                return count;
+            }
+            prev = Some(link);
+            current = next;
+        }
+        if tail != prev {
+            violations.push(InvariantViolation::UnreachableTail {
+                node,
+                subs: matches!(chain, Chain::Subs),
+            });
+        }
+        count
+    }
+
+    /// Check that `node` and its children point at each other
+    fn validate_tree(&self, node: NodeKey, violations: &mut Vec<InvariantViolation>) {
+        let item = &self.nodes[node];
+        let consistent = match item.parent {
+            Some(parent) => match self.nodes.get(parent) {
+                None => false,
+                Some(owner) => match item.prev {
+                    None => owner.child == Some(node),
+                    Some(prev) => self
+                        .nodes
+                        .get(prev)
+                        .is_some_and(|prev| prev.next == Some(node) && prev.parent == Some(parent)),
+                },
+            },
+            None => item.prev.is_none() && item.next.is_none(),
+        };
+        let consistent = consistent
+            && item.next.is_none_or(|next| {
+                self.nodes
+                    .get(next)
+                    .is_some_and(|next| next.prev == Some(node))
+            })
+            && item.child.is_none_or(|child| {
+                self.nodes
+                    .get(child)
+                    .is_some_and(|child| child.parent == Some(node) && child.prev.is_none())
+            });
+        if !consistent {
+            violations.push(InvariantViolation::BrokenTree { node });
+        }
+    }
+
+    fn chain_contains(&self, node: NodeKey, link: LinkKey, chain: Chain) -> bool {
+        let Some(item) = self.nodes.get(node) else {
+            return false;
+        };
+        let head = match chain {
+            Chain::Deps => item.deps,
+            Chain::Subs => item.subs,
+        };
+        std::iter::successors(head, |&current| {
+            let current = self.links.get(current)?;
+            match chain {
+                Chain::Deps => current.next_dep,
+                Chain::Subs => current.next_sub,
+            }
+        })
+        .take(self.links.len())
+        .any(|current| current == link)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Chain {
+    Deps,
+    Subs,
+}
+
+impl Chain {
+    fn broken(self, node: NodeKey, link: LinkKey) -> InvariantViolation {
+        match self {
+            Chain::Deps => InvariantViolation::BrokenDepChain { node, link },
+            Chain::Subs => InvariantViolation::BrokenSubChain { node, link },
+        }
+    }
+}
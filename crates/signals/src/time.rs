@@ -0,0 +1,196 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::effect::effect;
+use crate::future::spawn;
+use crate::runtime::timer::Sleep;
+use crate::scope::Owner;
+use crate::signal::{Signal, signal};
+
+/// Waits until `duration` has elapsed.
+///
+/// Works on any async runtime, including the built-in executor driven by
+/// [`join`](crate::join) or [`poll`](crate::poll): deadlines are tracked by a
+/// shared background timer thread.
+pub fn sleep(duration: Duration) -> Sleep {
+    Sleep::new(Instant::now() + duration)
+}
+
+/// Waits until `deadline` has been reached.
+pub fn sleep_until(deadline: Instant) -> Sleep {
+    Sleep::new(deadline)
+}
+
+/// Creates a signal counting the `period`s elapsed since its creation.
+///
+/// The counter starts at 0 and is incremented by a task spawned in the
+/// current scope. Ticks are scheduled against fixed deadlines, so a slow
+/// executor does not make the interval drift. Disposing the scope stops
+/// the timer.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// # use std::time::Duration;
+/// # tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap().block_on(async {
+/// let clock = scope(|| {});
+/// let ticks = clock.run_in(|| interval_signal(Duration::from_millis(10)));
+/// let elapsed = memo(move || ticks.get() * 10);
+///
+/// tokio::select! {
+///     _ = poll() => unreachable!(),
+///     _ = sleep(Duration::from_millis(35)) => {}
+/// }
+/// assert!(elapsed.get() >= 20);
+/// clock.dispose();
+/// # });
+/// ```
+#[track_caller]
+pub fn interval_signal(period: Duration) -> Signal<u64> {
+    let ticks = signal(0u64);
+    spawn(async move {
+        let mut deadline = Instant::now();
+        loop {
+            deadline += period;
+            sleep_until(deadline).await;
+            ticks.set(ticks.get_untracked() + 1);
+        }
+    });
+    ticks
+}
+
+/// Creates a signal that turns `true` once `delay` has elapsed.
+///
+/// The timer is a task spawned in the current scope; disposing the scope
+/// before the delay has elapsed cancels it.
+#[track_caller]
+pub fn timeout_signal(delay: Duration) -> Signal<bool> {
+    let fired = signal(false);
+    spawn(async move {
+        sleep(delay).await;
+        fired.set(true);
+    });
+    fired
+}
+
+/// Creates a signal that follows `source` once it has been quiet for `wait`.
+///
+/// Every change of `source` restarts the wait, so a burst of updates results
+/// in a single update of the returned signal, to the latest value, `wait`
+/// after the last one (trailing edge). The timer runs as a task owned by
+/// the current scope.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// # use std::time::Duration;
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let input = signal(String::new());
+/// let query = debounce(input, Duration::from_millis(20));
+///
+/// input.set(String::from("s"));
+/// input.set(String::from("si"));
+/// input.set(String::from("sig"));
+/// assert_eq!(query.get(), "");
+///
+/// join().await;
+/// assert_eq!(query.get(), "sig");
+/// # });
+/// ```
+#[track_caller]
+pub fn debounce<T: Clone + 'static>(source: Signal<T>, wait: Duration) -> Signal<T> {
+    let output = signal(source.get_untracked());
+    let initial = Cell::new(true);
+    effect(move || {
+        let value = source.get();
+        if initial.replace(false) {
+            return;
+        }
+        // Re-running this effect aborts the previous timer
+        spawn(async move {
+            sleep(wait).await;
+            output.set(value);
+        });
+    });
+    output
+}
+
+/// Creates a signal that follows `source` at most once per `interval`.
+///
+/// When `source` changes while no window is open, a window of `interval`
+/// starts; with `leading` the value is forwarded immediately. Changes during
+/// the window are held back, and with `trailing` the latest of them is
+/// forwarded when the window ends, which starts a new window. With neither
+/// flag set nothing is forwarded.
+///
+/// The window timer is a task owned by the current scope, so disposing the
+/// scope mid-burst drops any held-back value.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// # use std::time::Duration;
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let position = signal(0);
+/// let sampled = throttle(position, Duration::from_millis(10), true, true);
+///
+/// for x in 1..=10 {
+///     position.set(x);
+/// }
+/// assert_eq!(sampled.get(), 1); // Leading edge
+///
+/// join().await;
+/// assert_eq!(sampled.get(), 10); // Trailing edge
+/// # });
+/// ```
+#[track_caller]
+pub fn throttle<T: Clone + 'static>(
+    source: Signal<T>,
+    interval: Duration,
+    leading: bool,
+    trailing: bool,
+) -> Signal<T> {
+    let output = signal(source.get_untracked());
+    let owner = Owner::current();
+    let window_open = Rc::new(Cell::new(false));
+    let held: Rc<RefCell<Option<T>>> = Rc::new(RefCell::new(None));
+    let initial = Cell::new(true);
+
+    effect(move || {
+        let value = source.get();
+        if initial.replace(false) {
+            return;
+        }
+        if window_open.get() {
+            if trailing {
+                *held.borrow_mut() = Some(value);
+            }
+            return;
+        }
+
+        window_open.set(true);
+        if leading {
+            output.set(value);
+        } else if trailing {
+            *held.borrow_mut() = Some(value);
+        }
+
+        // Owned by the creating scope rather than this effect, so that the
+        // next change does not abort the window
+        let (window_open, held) = (window_open.clone(), held.clone());
+        owner.with(|| {
+            spawn(async move {
+                loop {
+                    sleep(interval).await;
+                    let Some(value) = held.borrow_mut().take() else {
+                        break;
+                    };
+                    output.set(value);
+                }
+                window_open.set(false);
+            })
+        });
+    });
+    output
+}
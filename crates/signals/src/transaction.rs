@@ -0,0 +1,53 @@
+use crate::runtime::REACTIVE_SYSTEM;
+use std::panic::{AssertUnwindSafe, catch_unwind, resume_unwind};
+
+/// Run `f` as a transaction over signal writes.
+///
+/// Every write inside the closure is applied immediately, so later reads in
+/// the closure, computeds included, see it, but effects do not run yet. If
+/// `f` returns `Ok`, the effects run once, as after a batch. If it returns
+/// `Err` or panics, every written signal is restored to the value it had
+/// before the transaction, and effects queued only by the discarded writes
+/// do not run. Effects that ran inside the transaction, like ones created
+/// there, re-run against the restored values.
+///
+/// Transactions nest: an inner transaction that commits hands its writes to
+/// the enclosing one, an inner one that fails only restores its own writes.
+///
+/// `Signal::update` and `Signal::write` mutate the value in place and have
+/// nothing to restore it from, so inside a transaction they panic unless
+/// the signal was already replaced there with `Signal::set`. In-place
+/// helpers such as `Signal::toggle` and `Signal::set_at` keep a clone of
+/// the value and can be used freely.
+///
+/// # Example
+///
+/// ```rust
+/// # use samara_signals::*;
+/// let name = signal(String::from("a"));
+///
+/// let result: Result<(), &str> = transaction(|| {
+///     name.set(String::from("b"));
+///     Err("invalid")
+/// });
+///
+/// assert!(result.is_err());
+/// assert_eq!(name.get(), "a");
+/// ```
+pub fn transaction<O, E>(f: impl FnOnce() -> Result<O, E>) -> Result<O, E> {
+    REACTIVE_SYSTEM.with(|ctx| ctx.start_transaction());
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(output)) => {
+            REACTIVE_SYSTEM.with(|ctx| ctx.commit_transaction());
+            Ok(output)
+        }
+        Ok(Err(err)) => {
+            REACTIVE_SYSTEM.with(|ctx| ctx.rollback_transaction());
+            Err(err)
+        }
+        Err(payload) => {
+            REACTIVE_SYSTEM.with(|ctx| ctx.rollback_transaction());
+            resume_unwind(payload)
+        }
+    }
+}
@@ -1,6 +1,5 @@
 use ::slotmap::new_key_type;
 use serde::Serialize;
-use std::rc::Rc;
 use std::{any::Any, cell::Cell, fmt::Debug};
 
 mod refcell;
@@ -18,13 +17,16 @@ pub trait ComputedOps {
 }
 
 /// Computed node without equality check (always dirty after update)
-pub struct ComputedNodeInner<T> {
+///
+/// The getter is stored inline, so the node's value and closure share a
+/// single allocation.
+pub struct ComputedNodeInner<T, F> {
     value: Option<T>,
-    getter: Box<dyn Fn(Option<T>) -> T + 'static>,
+    getter: F,
 }
 
-impl<T: 'static> ComputedNodeInner<T> {
-    pub fn new(getter: Box<dyn Fn(Option<T>) -> T + 'static>) -> Self {
+impl<T: 'static, F: Fn(Option<T>) -> T> ComputedNodeInner<T, F> {
+    pub fn new(getter: F) -> Self {
         Self {
             value: None,
             getter,
@@ -32,7 +34,7 @@ impl<T: 'static> ComputedNodeInner<T> {
     }
 }
 
-impl<T: 'static> ComputedOps for ComputedNodeInner<T> {
+impl<T: 'static, F: Fn(Option<T>) -> T> ComputedOps for ComputedNodeInner<T, F> {
     #[inline]
     fn update(&mut self) -> bool {
         self.value = Some((self.getter)(self.value.take()));
@@ -46,14 +48,14 @@ impl<T: 'static> ComputedOps for ComputedNodeInner<T> {
 }
 
 /// Memo node with equality check (only dirty if value changed)
-pub struct MemoNodeInner<T: PartialEq> {
+pub struct MemoNodeInner<T: PartialEq, F> {
     prev: Option<T>,
     curr: Option<T>,
-    getter: Box<dyn Fn() -> T + 'static>,
+    getter: F,
 }
 
-impl<T: PartialEq + 'static> MemoNodeInner<T> {
-    pub fn new(getter: Box<dyn Fn() -> T + 'static>) -> Self {
+impl<T: PartialEq + 'static, F: Fn() -> T> MemoNodeInner<T, F> {
+    pub fn new(getter: F) -> Self {
         Self {
             prev: None,
             curr: None,
@@ -66,7 +68,7 @@ impl<T: PartialEq + 'static> MemoNodeInner<T> {
     }
 }
 
-impl<T: PartialEq + 'static> ComputedOps for MemoNodeInner<T> {
+impl<T: PartialEq + 'static, F: Fn() -> T> ComputedOps for MemoNodeInner<T, F> {
     #[inline]
     fn update(&mut self) -> bool {
         let new_value = (self.getter)();
@@ -98,9 +100,21 @@ impl<T: PartialEq + 'static> ComputedOps for MemoNodeInner<T> {
 /// Borrow state for runtime borrow checking (like RefCell)
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum BorrowState {
-    Unused,         // No active borrows
-    Reading(usize), // Number of active read guards
-    Writing,        // Active write guard (exclusive)
+    Unused, // No active borrows
+    /// Number of active read guards, and where the latest was taken. A
+    /// `u32` keeps signal nodes, and so every node, a word smaller.
+    Reading(u32, Location),
+    /// Active write guard (exclusive), and where it was taken
+    Writing(Location),
+}
+
+/// A borrow refused by `SignalNode::try_borrow_read`/`try_borrow_write`
+#[cfg_attr(not(any(debug_assertions, feature = "track-caller")), allow(dead_code))]
+pub struct BorrowConflict {
+    pub message: &'static str,
+    /// Where the guard that is in the way was taken
+    pub held_at: Location,
+    pub held_as: &'static str,
 }
 
 pub struct SignalNode {
@@ -109,46 +123,73 @@ pub struct SignalNode {
 }
 
 impl SignalNode {
-    /// Check if a read borrow is allowed, panic if not
+    /// Take a read borrow at `caller`, or describe the conflict if one is not
+    /// allowed
     #[inline]
-    pub fn borrow_read_check(&self) {
+    pub fn try_borrow_read(&self, caller: Location) -> Result<(), BorrowConflict> {
         match self.borrow_state.get() {
             BorrowState::Unused => {
-                self.borrow_state.set(BorrowState::Reading(1));
+                self.borrow_state.set(BorrowState::Reading(1, caller));
             }
-            BorrowState::Reading(count) => {
-                self.borrow_state.set(BorrowState::Reading(count + 1));
+            BorrowState::Reading(count, _) => {
+                self.borrow_state
+                    .set(BorrowState::Reading(count + 1, caller));
             }
-            BorrowState::Writing => {
-                panic!("Cannot borrow signal as readable while already borrowed as writable");
+            BorrowState::Writing(held_at) => {
+                return Err(BorrowConflict {
+                    message: "Cannot borrow signal as readable while already borrowed as writable",
+                    held_at,
+                    held_as: "write",
+                });
             }
         }
+        Ok(())
     }
 
-    /// Check if a write borrow is allowed, panic if not
+    /// Take a write borrow at `caller`, or describe the conflict if one is
+    /// not allowed
     #[inline]
-    pub fn borrow_write_check(&self) {
+    pub fn try_borrow_write(&self, caller: Location) -> Result<(), BorrowConflict> {
         match self.borrow_state.get() {
             BorrowState::Unused => {
-                self.borrow_state.set(BorrowState::Writing);
+                self.borrow_state.set(BorrowState::Writing(caller));
             }
-            BorrowState::Reading(_) => {
-                panic!("Cannot borrow signal as writable while already borrowed as readable");
+            BorrowState::Reading(_, held_at) => {
+                return Err(BorrowConflict {
+                    message: "Cannot borrow signal as writable while already borrowed as readable",
+                    held_at,
+                    held_as: "read",
+                });
             }
-            BorrowState::Writing => {
-                panic!("Cannot have multiple write guards to the same signal");
+            BorrowState::Writing(held_at) => {
+                return Err(BorrowConflict {
+                    message: "Cannot have multiple write guards to the same signal",
+                    held_at,
+                    held_as: "write",
+                });
             }
         }
+        Ok(())
+    }
+
+    /// Where the latest guard still borrowing the value was taken, if any
+    #[inline]
+    pub fn held_at(&self) -> Option<Location> {
+        match self.borrow_state.get() {
+            BorrowState::Unused => None,
+            BorrowState::Reading(_, held_at) | BorrowState::Writing(held_at) => Some(held_at),
+        }
     }
 
     /// Release a read borrow
     #[inline]
     pub fn release_read(&self) {
         match self.borrow_state.get() {
-            BorrowState::Reading(count) if count > 1 => {
-                self.borrow_state.set(BorrowState::Reading(count - 1));
+            BorrowState::Reading(count, held_at) if count > 1 => {
+                self.borrow_state
+                    .set(BorrowState::Reading(count - 1, held_at));
             }
-            BorrowState::Reading(1) => {
+            BorrowState::Reading(1, _) => {
                 self.borrow_state.set(BorrowState::Unused);
             }
             _ => panic!("Invalid borrow state during read release"),
@@ -159,7 +200,7 @@ impl SignalNode {
     #[inline]
     pub fn release_write(&self) {
         match self.borrow_state.get() {
-            BorrowState::Writing => {
+            BorrowState::Writing(_) => {
                 self.borrow_state.set(BorrowState::Unused);
             }
             _ => panic!("Invalid borrow state during write release"),
@@ -176,12 +217,32 @@ impl Drop for SignalNode {
 }
 
 pub struct EffectNode {
-    pub effect: Rc<RefCell<dyn FnMut()>>,
+    /// The effect's closure, taken out of the node while it runs so that a
+    /// run needs no reference count or borrow flag
+    pub effect: Option<Box<dyn FnMut()>>,
+}
+
+pub struct ComputedNode {
+    /// The computed's getter and value, taken out of the node while it
+    /// updates so that the getter may use the system freely, even to dispose
+    /// the computed
+    pub ops: Option<Box<dyn ComputedOps>>,
+}
+
+impl ComputedNode {
+    /// The current value, which must not be read while it is being updated
+    #[inline]
+    pub fn value(&self) -> &dyn Any {
+        match &self.ops {
+            Some(ops) => ops.as_any(),
+            None => panic!("Computed read while it is being updated"),
+        }
+    }
 }
 
 pub enum NodeInner {
     Effect(EffectNode),
-    Computed(Rc<RefCell<dyn ComputedOps>>),
+    Computed(ComputedNode),
     Signal(SignalNode),
     None,
 }
@@ -225,8 +286,20 @@ pub struct ReactiveNode {
     pub next: Option<NodeKey>,
     pub prev: Option<NodeKey>,
     pub flags: ReactiveFlags,
+    /// Number of subscribers that are watched, see `ReactiveFlags::is_watched`;
+    /// `HAS_WATCHER` is set exactly while it is nonzero
+    #[serde(skip)]
+    pub watched_subs: u32,
+    /// The `epoch` of the last propagation that reached the node
+    #[serde(skip)]
+    pub last_visited_epoch: u64,
     #[serde(serialize_with = "serialize_location")]
     pub caller: Location,
+    /// Optional label set with `Scope::set_name`, used in diagnostics.
+    /// Boxed so that the many unnamed nodes only pay for a pointer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[allow(clippy::box_collection)]
+    pub name: Option<Box<String>>,
 }
 
 impl ReactiveNode {
@@ -244,18 +317,23 @@ impl ReactiveNode {
             subs: None,
             subs_tail: None,
             flags,
+            watched_subs: 0,
+            last_visited_epoch: 0,
             parent,
             child: None,
             next: None,
             prev: None,
             caller,
+            name: None,
         }
     }
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct Link {
-    pub version: usize,
+    /// The `cycle` of the run that last tracked this link. Only compared
+    /// for equality, so the counter may wrap.
+    pub version: u32,
     pub dep: NodeKey,
     pub sub: NodeKey,
     pub prev_sub: Option<LinkKey>,
@@ -267,10 +345,31 @@ pub struct Link {
 pub use crate::flags::ReactiveFlags;
 
 use crate::system::ReactiveSystem;
-#[cfg(debug_assertions)]
-pub use crate::types::refcell::RefCell;
-#[cfg(not(debug_assertions))]
-pub use crate::types::refcell::UnsafeRefCell as RefCell;
-
-pub use crate::types::refcell::{Location, UnsafeBox, caller, serialize_location};
+pub use crate::types::refcell::{
+    Location, UnsafeBox, caller, format_location, panic_location, serialize_location,
+};
 pub use crate::types::slotmap::UnsafeSlotMap;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_layout() {
+        // Slotmap keys have a niche, so optional keys take no extra space
+        assert_eq!(size_of::<Option<NodeKey>>(), size_of::<NodeKey>());
+        assert_eq!(size_of::<Option<LinkKey>>(), size_of::<LinkKey>());
+        // Release builds do not record locations
+        #[cfg(not(any(debug_assertions, feature = "track-caller")))]
+        assert!(size_of::<ReactiveNode>() <= 120);
+        #[cfg(any(debug_assertions, feature = "track-caller"))]
+        assert!(size_of::<ReactiveNode>() <= 136);
+    }
+
+    #[test]
+    fn test_link_layout() {
+        // Keys are two u32s, so a u32 version leaves no padding, and a link
+        // fills its slot together with the slot's own version
+        assert_eq!(size_of::<Link>(), 52);
+        assert_eq!(align_of::<Link>(), 4);
+    }
+}
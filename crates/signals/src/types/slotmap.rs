@@ -1,6 +1,6 @@
-use std::ops::{Deref, DerefMut, Index, IndexMut};
 use serde::{Serialize, Serializer};
 use slotmap::{Key, SlotMap};
+use std::ops::{Deref, DerefMut, Index, IndexMut};
 
 pub struct UnsafeSlotMap<K: Key, V>(SlotMap<K, V>);
 
@@ -10,6 +10,25 @@ impl<K: Key, V> Default for UnsafeSlotMap<K, V> {
     }
 }
 
+impl<K: Key, V> UnsafeSlotMap<K, V> {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(SlotMap::with_capacity_and_key(capacity))
+    }
+
+    /// Index of the highest slot holding a live value, or 0 if there is none
+    ///
+    /// Slot indices start at 1, so this is also how many slots are spanned
+    /// by the live values.
+    pub fn highest_slot(&self) -> usize {
+        self.0
+            .keys()
+            .map(|key| key.data().as_ffi() as u32 as usize)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(not(any(debug_assertions, feature = "checked-slotmap")))]
 impl<K: Key, V> Index<K> for UnsafeSlotMap<K, V> {
     type Output = V;
 
@@ -19,6 +38,7 @@ impl<K: Key, V> Index<K> for UnsafeSlotMap<K, V> {
     }
 }
 
+#[cfg(not(any(debug_assertions, feature = "checked-slotmap")))]
 impl<K: Key, V> IndexMut<K> for UnsafeSlotMap<K, V> {
     #[inline]
     fn index_mut(&mut self, key: K) -> &mut V {
@@ -26,6 +46,44 @@ impl<K: Key, V> IndexMut<K> for UnsafeSlotMap<K, V> {
     }
 }
 
+// Checked indexing turns a stale key into a panic instead of undefined
+// behavior
+#[cfg(any(debug_assertions, feature = "checked-slotmap"))]
+impl<K: Key, V> Index<K> for UnsafeSlotMap<K, V> {
+    type Output = V;
+
+    #[inline]
+    #[track_caller]
+    fn index(&self, key: K) -> &V {
+        match self.0.get(key) {
+            Some(value) => value,
+            None => invalid_key(key),
+        }
+    }
+}
+
+#[cfg(any(debug_assertions, feature = "checked-slotmap"))]
+impl<K: Key, V> IndexMut<K> for UnsafeSlotMap<K, V> {
+    #[inline]
+    #[track_caller]
+    fn index_mut(&mut self, key: K) -> &mut V {
+        match self.0.get_mut(key) {
+            Some(value) => value,
+            None => invalid_key(key),
+        }
+    }
+}
+
+#[cfg(any(debug_assertions, feature = "checked-slotmap"))]
+#[cold]
+#[track_caller]
+fn invalid_key<K: Key>(key: K) -> ! {
+    panic!(
+        "{key:?} used after its node or link was removed, at {}",
+        std::panic::Location::caller()
+    )
+}
+
 impl<K: Key, V> Deref for UnsafeSlotMap<K, V> {
     type Target = SlotMap<K, V>;
     #[inline]
@@ -0,0 +1,126 @@
+use std::fmt;
+
+use crate::runtime::REACTIVE_SYSTEM;
+use crate::types::{LinkKey, NodeKey};
+
+/// A broken pointer found by [`validate`].
+///
+/// Any of these means the reactive system itself has a bug: the public API
+/// should never be able to produce them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvariantViolation {
+    /// The link's dependency or subscriber no longer exists.
+    DanglingLink {
+        link: LinkKey,
+        dep: NodeKey,
+        sub: NodeKey,
+    },
+    /// Walking `node`'s dependencies reached a link that is missing, belongs
+    /// to another subscriber, or disagrees with its predecessor.
+    BrokenDepChain { node: NodeKey, link: LinkKey },
+    /// Walking `node`'s subscribers reached a link that is missing, belongs
+    /// to another dependency, or disagrees with its predecessor.
+    BrokenSubChain { node: NodeKey, link: LinkKey },
+    /// `deps_tail`, or `subs_tail` if `subs` is set, is not the last link of
+    /// its chain.
+    UnreachableTail { node: NodeKey, subs: bool },
+    /// The link is missing from its subscriber's dependency chain or its
+    /// dependency's subscriber chain.
+    UnchainedLink { link: LinkKey },
+    /// The node's parent, child, next or prev pointer disagrees with the
+    /// node it points at.
+    BrokenTree { node: NodeKey },
+    /// The node is flagged `WATCHING` but is not an effect.
+    WatchingWithoutEffect { node: NodeKey },
+    /// The node's `HAS_WATCHER` flag disagrees with whether any of its
+    /// subscribers is an effect or flagged `HAS_WATCHER`.
+    StaleWatcherFlag { node: NodeKey, flagged: bool },
+    /// The node's count of watched subscribers disagrees with its
+    /// subscriber chain.
+    StaleWatcherCount {
+        node: NodeKey,
+        counted: u32,
+        watched: u32,
+    },
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DanglingLink { link, dep, sub } => {
+                write!(
+                    f,
+                    "link {link:?} from {dep:?} to {sub:?} points at a removed node"
+                )
+            }
+            Self::BrokenDepChain { node, link } => {
+                write!(f, "dependency chain of {node:?} is broken at {link:?}")
+            }
+            Self::BrokenSubChain { node, link } => {
+                write!(f, "subscriber chain of {node:?} is broken at {link:?}")
+            }
+            Self::UnreachableTail { node, subs } => {
+                let chain = if *subs { "subs" } else { "deps" };
+                write!(f, "{chain}_tail of {node:?} is not the end of its chain")
+            }
+            Self::UnchainedLink { link } => {
+                write!(f, "link {link:?} is missing from its chains")
+            }
+            Self::BrokenTree { node } => {
+                write!(f, "ownership pointers of {node:?} are inconsistent")
+            }
+            Self::WatchingWithoutEffect { node } => {
+                write!(f, "{node:?} is flagged WATCHING but is not an effect")
+            }
+            Self::StaleWatcherFlag {
+                node,
+                flagged: true,
+            } => {
+                write!(f, "{node:?} is flagged HAS_WATCHER but nothing watches it")
+            }
+            Self::StaleWatcherFlag {
+                node,
+                flagged: false,
+            } => {
+                write!(f, "{node:?} is watched but not flagged HAS_WATCHER")
+            }
+            Self::StaleWatcherCount {
+                node,
+                counted,
+                watched,
+            } => {
+                write!(
+                    f,
+                    "{node:?} counts {counted} watched subscribers but has {watched}"
+                )
+            }
+        }
+    }
+}
+
+/// Checks the current thread's reactive graph for internal inconsistencies.
+///
+/// Walks every node and link and verifies that both ends of each link exist,
+/// that the dependency and subscriber chains are doubly linked and end at
+/// their tails, that the ownership tree's pointers agree, that only
+/// effects are flagged `WATCHING`, and that `HAS_WATCHER` is set on exactly
+/// the nodes an effect depends on. Meant for tests of the reactive system
+/// itself; with the `validate` feature, debug builds run it after every
+/// flush and panic on the first failure.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// let a = signal(1);
+/// let s = scope(move || {
+///     effect(move || {
+///         a.get();
+///     });
+/// });
+/// a.set(2);
+/// s.dispose();
+/// assert_eq!(validate(), Ok(()));
+/// ```
+pub fn validate() -> Result<(), Vec<InvariantViolation>> {
+    REACTIVE_SYSTEM.with(|ctx| ctx.validate())
+}
@@ -0,0 +1,70 @@
+use std::cell::Cell;
+
+use tokio::sync::watch;
+
+use crate::effect::effect;
+use crate::future::spawn;
+use crate::signal::{Signal, signal};
+
+/// Creates a signal following the values sent on a tokio `watch` channel.
+///
+/// The signal starts with the channel's current value. A task spawned on
+/// the executor then writes every value it is woken for; the channel only
+/// keeps the latest one, so bursts of sends are written once. The task stops
+/// when the sender is dropped, and is aborted when the current scope is
+/// disposed, dropping `rx`; until then it keeps [`join`](crate::join) from
+/// returning, so drive it with [`tick`](crate::tick) or
+/// [`drive_on_tokio`](crate::drive_on_tokio) instead.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// let (tx, rx) = tokio::sync::watch::channel(1);
+/// let value = signal_from_watch(rx);
+/// tx.send(2).unwrap();
+/// tx.send(3).unwrap();
+/// tick(10);
+/// assert_eq!(value.get(), 3);
+/// ```
+#[track_caller]
+pub fn signal_from_watch<T: Clone + 'static>(mut rx: watch::Receiver<T>) -> Signal<T> {
+    let value = signal(rx.borrow_and_update().clone());
+    spawn(async move {
+        while rx.changed().await.is_ok() {
+            value.set(rx.borrow_and_update().clone());
+        }
+    });
+    value
+}
+
+/// Creates a tokio `watch` channel following the values of `signal`.
+///
+/// The channel starts with the signal's current value. An effect then sends
+/// the value after every flush that changed it, so writes batched together
+/// are sent once. The effect is owned by the current scope; disposing it
+/// drops the sender, which ends the receivers' `changed()` with an error.
+///
+/// # Example
+/// ```rust
+/// # use samara_signals::*;
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let value = signal(1);
+/// let mut rx = signal_to_watch(value);
+/// value.set(2);
+/// rx.changed().await.unwrap();
+/// assert_eq!(*rx.borrow(), 2);
+/// # });
+/// ```
+#[track_caller]
+pub fn signal_to_watch<T: Clone + 'static>(signal: Signal<T>) -> watch::Receiver<T> {
+    let (tx, rx) = watch::channel(signal.get_untracked());
+    let first = Cell::new(true);
+    effect(move || {
+        let value = signal.get();
+        // The channel already holds the value the first run reads
+        if !first.replace(false) {
+            tx.send_replace(value);
+        }
+    });
+    rx
+}
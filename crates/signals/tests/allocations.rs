@@ -0,0 +1,116 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use samara_signals::*;
+
+/// Counts the allocations made by the current thread, so tests running in
+/// parallel do not disturb each other
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations<O>(f: impl FnOnce() -> O) -> (O, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let output = f();
+    (output, ALLOCATIONS.with(Cell::get) - before)
+}
+
+#[test]
+fn test_memo_allocates_once() {
+    // Warm up the thread's runtime so its own first allocations are not
+    // counted
+    reserve(200, 200);
+    let src = signal(0);
+
+    // The getter is stored inline with the value, in the one box the node
+    // holds
+    let (last, created) = allocations(|| {
+        let mut last = memo(move || src.get());
+        for _ in 1..100 {
+            let prev = last;
+            last = memo(move || prev.get() + 1);
+        }
+        last
+    });
+    assert_eq!(created, 100);
+
+    assert_eq!(last.get(), 99);
+    src.set(1);
+    assert_eq!(last.get(), 100);
+}
+
+#[test]
+fn test_memo_chain_updates_without_allocating() {
+    reserve(1100, 1100);
+    let src = signal(0);
+    let mut last = memo(move || src.get());
+    for _ in 1..1000 {
+        let prev = last;
+        last = memo(move || prev.get() + 1);
+    }
+    // The first write and read grow the runtime's scratch space to fit the
+    // chain; later ones reuse it
+    assert_eq!(last.get(), 999);
+    src.set(1);
+    assert_eq!(last.get(), 1000);
+
+    let (value, updated) = allocations(|| {
+        src.set(2);
+        last.get()
+    });
+    assert_eq!(value, 1001);
+    assert_eq!(updated, 0);
+}
+
+#[test]
+fn test_computed_allocates_once() {
+    reserve(10, 10);
+    let src = signal(1);
+
+    let (doubled, created) = allocations(|| computed(move |_| src.get() * 2));
+    assert_eq!(created, 1);
+    assert_eq!(doubled.get(), 2);
+}
+
+#[test]
+fn test_polling_a_task_does_not_copy_the_queue() {
+    reserve(200, 200);
+    let src = signal(0);
+    let _effect = effect(move || {
+        src.get();
+    });
+    spawn(std::future::poll_fn(|cx| {
+        cx.waker().wake_by_ref();
+        std::task::Poll::<()>::Pending
+    }));
+    tick(1);
+    let (_, idle) = allocations(|| tick(10));
+
+    start_batch();
+    // Leaves an effect waiting in the queue that every poll swaps out
+    src.set(1);
+    let (_, queued) = allocations(|| tick(10));
+    end_batch();
+    assert_eq!(queued, idle);
+}
@@ -295,11 +295,43 @@ async fn test_async_race_condition() {
     assert_eq!(s.get(), vec![1, 2]);
 }
 
+#[tokio::test]
+async fn test_spawn_order_is_fifo() {
+    let log = signal(Vec::new());
+    scope(move || {
+        for id in 0..3 {
+            spawn(async move {
+                log.write().push(format!("start {id}"));
+                for step in 0..2 {
+                    // Yielding twice per task interleaves them round-robin
+                    tokio::task::yield_now().await;
+                    log.write().push(format!("{id}.{step}"));
+                }
+            });
+        }
+    });
+    let observed = signal(Vec::new());
+    effect(move || {
+        let len = log.read().len();
+        observed.write().push(len);
+    });
+
+    join().await;
+    assert_eq!(
+        log.get(),
+        [
+            "start 0", "start 1", "start 2", "0.0", "1.0", "2.0", "0.1", "1.1", "2.1"
+        ]
+    );
+    // Every push was observed on its own, in order
+    assert_eq!(observed.get(), (0..=9).collect::<Vec<_>>());
+}
+
 #[tokio::test]
 async fn test_async_resource() {
     let s = signal(1);
 
-    let Resource { value, loading } = resource(move || async move {
+    let Resource { value, loading, .. } = resource(move || async move {
         tokio::time::sleep(Duration::from_millis(100)).await;
         s.get() * 2
     });
@@ -325,3 +357,1170 @@ async fn test_async_resource() {
     assert_eq!(value.get(), Some(6));
     assert_eq!(loading.get(), false);
 }
+
+#[tokio::test]
+async fn test_batch_spans_await_per_task() {
+    let a1 = signal(0);
+    let a2 = signal(0);
+    let b1 = signal(0);
+    let b2 = signal(0);
+    let c = signal(0);
+    let log = signal(Vec::<String>::new());
+
+    effect(move || {
+        let entry = format!("a=({},{})", a1.get(), a2.get());
+        log.write().push(entry);
+    });
+    effect(move || {
+        let entry = format!("b=({},{})", b1.get(), b2.get());
+        log.write().push(entry);
+    });
+    effect(move || {
+        let entry = format!("c={}", c.get());
+        log.write().push(entry);
+    });
+
+    spawn(async move {
+        start_batch();
+        a1.set(1);
+        tokio::task::yield_now().await;
+        a2.set(1);
+        end_batch();
+    });
+
+    spawn(async move {
+        start_batch();
+        b1.set(1);
+        tokio::task::yield_now().await;
+        b2.set(1);
+        end_batch();
+    });
+
+    spawn(async move {
+        // Not batched, even though the other tasks are suspended mid-batch
+        c.set(1);
+    });
+
+    join().await;
+
+    assert_eq!(batch_depth(), 0);
+    let log = log.get();
+    assert_eq!(&log[..3], &["a=(0,0)", "b=(0,0)", "c=0"]);
+    // Each task's writes are observed atomically
+    assert!(!log.contains(&String::from("a=(1,0)")));
+    assert!(!log.contains(&String::from("b=(1,0)")));
+    // The unbatched write was flushed before either batch closed
+    let c_index = log.iter().position(|entry| entry == "c=1").unwrap();
+    let a_index = log.iter().position(|entry| entry == "a=(1,1)").unwrap();
+    let b_index = log.iter().position(|entry| entry == "b=(1,1)").unwrap();
+    assert!(c_index < a_index && c_index < b_index);
+}
+
+#[tokio::test]
+async fn test_task_finishing_inside_batch_flushes_its_effects() {
+    let s = signal(0);
+    let seen = signal(0);
+
+    effect(move || {
+        let value = s.get();
+        seen.set(value);
+    });
+
+    spawn(async move {
+        start_batch();
+        s.set(1);
+        // The batch is never closed
+    });
+
+    join().await;
+
+    assert_eq!(batch_depth(), 0);
+    assert_eq!(seen.get(), 1);
+}
+
+#[tokio::test]
+async fn test_auto_batch_flushes_at_join() {
+    let s = signal(0);
+    let runs = signal(0);
+
+    effect(move || {
+        s.get();
+        *runs.write() += 1;
+    });
+
+    set_auto_batch(true);
+    s.set(1);
+    s.set(2);
+
+    spawn(async move {
+        s.set(3);
+        s.set(4);
+        tokio::task::yield_now().await;
+        s.set(5);
+    });
+
+    assert_eq!(runs.get(), 1);
+    join().await;
+
+    // One run per executor boundary: the writes before join, the task's
+    // first poll, and its second poll
+    assert_eq!(s.get(), 5);
+    assert_eq!(runs.get(), 4);
+    assert_eq!(pending_effects(), 0);
+    set_auto_batch(false);
+}
+
+#[tokio::test]
+async fn test_dispose_async_waits_for_tasks() {
+    struct SetOnDrop(Arc<AtomicBool>);
+    impl Drop for SetOnDrop {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    let dropped = Arc::new(AtomicBool::new(false));
+    let finished = Arc::new(AtomicBool::new(false));
+
+    let (dropped_in_task, finished_in_task) = (dropped.clone(), finished.clone());
+    let s = scope(move || {
+        scope(move || {
+            spawn(async move {
+                let _guard = SetOnDrop(dropped_in_task);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                finished_in_task.store(true, Ordering::SeqCst);
+            });
+        });
+    });
+
+    let start = std::time::Instant::now();
+    tokio::join!(join(), async {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!dropped.load(Ordering::SeqCst));
+        s.dispose_async().await;
+        assert!(dropped.load(Ordering::SeqCst));
+    });
+
+    assert!(!finished.load(Ordering::SeqCst));
+    assert!(start.elapsed() < Duration::from_millis(100));
+    assert_eq!(count(), (1, 0));
+}
+
+#[tokio::test]
+async fn test_try_resource_error_then_retry() {
+    let calls = Arc::new(AtomicI32::new(0));
+    let calls_in_fetcher = calls.clone();
+    let data = try_resource(move || {
+        let attempt = calls_in_fetcher.fetch_add(1, Ordering::SeqCst);
+        async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            if attempt != 1 {
+                Err(String::from("offline"))
+            } else {
+                Ok(attempt * 10)
+            }
+        }
+    });
+
+    let errors = signal(Vec::new());
+    effect(move || {
+        let error = data.error.get();
+        errors.write().push(error);
+    });
+
+    assert_eq!(data.loading.get(), true);
+    join().await;
+    assert_eq!(data.value.get(), None);
+    assert_eq!(data.error.get().as_deref(), Some("offline"));
+    assert_eq!(data.loading.get(), false);
+
+    data.retry();
+    join().await;
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(data.value.get(), Some(10));
+    assert_eq!(data.error.get(), None);
+
+    // A later failure keeps the previous value
+    data.refetch();
+    join().await;
+    assert_eq!(data.value.get(), Some(10));
+    assert_eq!(data.loading.get(), false);
+
+    let offline = Some(String::from("offline"));
+    assert_eq!(errors.get(), vec![None, offline.clone(), None, offline]);
+}
+
+#[tokio::test]
+async fn test_resource_latest_wins() {
+    let source = signal(1);
+    let completed = Arc::new(AtomicI32::new(0));
+
+    let completed_in_fetcher = completed.clone();
+    let data = resource(move || {
+        let key = source.get();
+        let completed = completed_in_fetcher.clone();
+        async move {
+            let delay = if key == 2 { 200 } else { 50 };
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            completed.fetch_add(1, Ordering::SeqCst);
+            key * 10
+        }
+    });
+
+    source.set(2);
+    source.set(3);
+    join().await;
+
+    assert_eq!(data.value.get(), Some(30));
+    assert_eq!(data.loading.get(), false);
+    // Only the fetch for the latest key ran to completion
+    assert_eq!(completed.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_resource_loading_vs_refetching() {
+    let source = signal(1);
+    let data = resource(move || {
+        let key = source.get();
+        async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            key * 10
+        }
+    });
+    let state = move || (data.loading.get(), data.refetching.get());
+
+    assert_eq!(state(), (true, false));
+    join().await;
+    assert_eq!(data.value.get(), Some(10));
+    assert_eq!(state(), (false, false));
+
+    source.set(2);
+    assert_eq!(state(), (false, true));
+    assert_eq!(data.value.get(), Some(10));
+
+    // Superseding the refetch keeps the state consistent
+    source.set(3);
+    assert_eq!(state(), (false, true));
+
+    join().await;
+    assert_eq!(data.value.get(), Some(30));
+    assert_eq!(state(), (false, false));
+}
+
+fn counting_user_resource(fetches: Arc<AtomicI32>) -> (Signal<i32>, CachedResource<i32, String>) {
+    let id = signal(1);
+    let user = cached_resource(
+        move || id.get(),
+        move |id| {
+            fetches.fetch_add(1, Ordering::SeqCst);
+            async move {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                format!("user {id}")
+            }
+        },
+    );
+    (id, user)
+}
+
+#[tokio::test]
+async fn test_cached_resource_hit_skips_fetch() {
+    let fetches = Arc::new(AtomicI32::new(0));
+    let (id, user) = counting_user_resource(fetches.clone());
+    join().await;
+    id.set(2);
+    join().await;
+    assert_eq!(fetches.load(Ordering::SeqCst), 2);
+
+    id.set(1);
+    assert_eq!(user.value.get().as_deref(), Some("user 1"));
+    assert_eq!((user.loading.get(), user.refetching.get()), (false, false));
+    join().await;
+    assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    assert_eq!(user.cached_len(), 2);
+}
+
+#[tokio::test]
+async fn test_cached_resource_miss_fetches() {
+    let fetches = Arc::new(AtomicI32::new(0));
+    let (id, user) = counting_user_resource(fetches.clone());
+    assert!(user.loading.get());
+    join().await;
+    assert_eq!(user.value.get().as_deref(), Some("user 1"));
+
+    id.set(3);
+    // A miss keeps the previous value while the new key is fetched
+    assert_eq!(user.value.get().as_deref(), Some("user 1"));
+    assert!(user.refetching.get());
+    join().await;
+    assert_eq!(user.value.get().as_deref(), Some("user 3"));
+    assert_eq!(fetches.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_cached_resource_invalidate_key() {
+    let fetches = Arc::new(AtomicI32::new(0));
+    let (id, user) = counting_user_resource(fetches.clone());
+    join().await;
+    id.set(2);
+    join().await;
+
+    // Invalidating another key refetches it on its next selection
+    user.invalidate_key(&1);
+    assert_eq!(user.cached_len(), 1);
+    id.set(1);
+    assert!(user.refetching.get());
+    join().await;
+    assert_eq!(fetches.load(Ordering::SeqCst), 3);
+
+    // Invalidating the current key refetches it immediately
+    user.invalidate_key(&1);
+    join().await;
+    assert_eq!(fetches.load(Ordering::SeqCst), 4);
+    assert_eq!(user.value.get().as_deref(), Some("user 1"));
+}
+
+#[tokio::test]
+async fn test_cached_resource_revalidate() {
+    let fetches = Arc::new(AtomicI32::new(0));
+    let (id, user) = counting_user_resource(fetches.clone());
+    user.set_revalidate(true);
+    join().await;
+    id.set(2);
+    join().await;
+
+    id.set(1);
+    assert_eq!(user.value.get().as_deref(), Some("user 1"));
+    assert_eq!((user.loading.get(), user.refetching.get()), (false, true));
+    join().await;
+    assert!(!user.refetching.get());
+    assert_eq!(fetches.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_resource_with_initial_keeps_previous_while_loading() {
+    let page = signal(1);
+    let data = resource_with_initial(0, move || {
+        let page = page.get();
+        async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            page * 10
+        }
+    });
+    let latest = data.latest();
+
+    assert_eq!(data.value.get(), Some(0));
+    assert_eq!((data.loading.get(), data.refetching.get()), (false, true));
+    join().await;
+    assert_eq!(data.value.get(), Some(10));
+
+    // The previous value is retained for the whole refetch
+    page.set(2);
+    assert_eq!(data.value.get(), Some(10));
+    poll_for(Duration::from_millis(5)).await;
+    assert_eq!(data.value.get(), Some(10));
+    assert_eq!(latest.get(), Some(10));
+    assert!(data.refetching.get());
+
+    join().await;
+    assert_eq!(latest.get(), Some(20));
+    assert!(!data.refetching.get());
+}
+
+#[tokio::test]
+async fn test_resource_latest_survives_errors() {
+    let fail = signal(false);
+    let data = try_resource(move || {
+        let fail = fail.get();
+        async move { if fail { Err("offline") } else { Ok(1) } }
+    });
+    let latest = data.latest();
+    assert_eq!(latest.get(), None);
+    join().await;
+    assert_eq!(latest.get(), Some(1));
+
+    fail.set(true);
+    join().await;
+    assert_eq!(data.error.get(), Some("offline"));
+    assert_eq!(latest.get(), Some(1));
+}
+
+#[tokio::test]
+async fn test_resource_with_source_ignores_other_reads() {
+    let fetches = Arc::new(AtomicI32::new(0));
+    let id = signal(1);
+    let unrelated = signal(0);
+    let counter = fetches.clone();
+    let data = resource_with_source(
+        move || id.get(),
+        move |id| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            let before = unrelated.get();
+            async move {
+                tokio::task::yield_now().await;
+                id * 100 + before + unrelated.get()
+            }
+        },
+    );
+    join().await;
+    assert_eq!(data.value.get(), Some(100));
+
+    unrelated.set(1);
+    join().await;
+    assert_eq!(fetches.load(Ordering::SeqCst), 1);
+    assert_eq!(data.value.get(), Some(100));
+
+    id.set(2);
+    join().await;
+    assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    assert_eq!(data.value.get(), Some(202));
+}
+
+#[tokio::test]
+async fn test_resource_with_source_equal_value_does_not_refetch() {
+    let fetches = Arc::new(AtomicI32::new(0));
+    let n = signal(3);
+    let counter = fetches.clone();
+    let data = resource_with_source(
+        move || n.get() % 2,
+        move |parity| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async move { parity }
+        },
+    );
+    join().await;
+
+    // Same parity, so the source value is equal
+    n.set(5);
+    join().await;
+    assert_eq!(fetches.load(Ordering::SeqCst), 1);
+    assert!(!data.refetching.get());
+
+    n.set(4);
+    join().await;
+    assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    assert_eq!(data.value.get(), Some(0));
+
+    // An explicit refetch still runs the fetcher
+    data.refetch();
+    join().await;
+    assert_eq!(fetches.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_resource_map() {
+    let source = signal(2);
+    let data = resource(move || {
+        let n = source.get();
+        async move { n * 10 }
+    });
+    let label = data.map(|n| format!("#{n}"));
+
+    assert_eq!(label.get(), None);
+    join().await;
+    assert_eq!(label.get().as_deref(), Some("#20"));
+    source.set(3);
+    join().await;
+    assert_eq!(label.get().as_deref(), Some("#30"));
+}
+
+#[tokio::test]
+async fn test_resource_and_then_chains_fetches() {
+    let user_id = signal(1);
+    let user = resource(move || {
+        let id = user_id.get();
+        async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            id
+        }
+    });
+    let orders = user.and_then(|&id| {
+        resource(move || async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            vec![id * 100, id * 100 + 1]
+        })
+    });
+    let state = move || (orders.loading.get(), orders.refetching.get());
+
+    // Loading while the user is fetched, then while the orders are
+    assert_eq!(state(), (true, false));
+    poll_for(Duration::from_millis(15)).await;
+    assert_eq!(user.value.get(), Some(1));
+    assert_eq!(state(), (true, false));
+    assert_eq!(orders.value.get(), None);
+
+    join().await;
+    assert_eq!(orders.value.get(), Some(vec![100, 101]));
+    assert_eq!(state(), (false, false));
+
+    // A new upstream value refetches the downstream and keeps the old value
+    user_id.set(2);
+    join().await;
+    assert_eq!(orders.value.get(), Some(vec![200, 201]));
+    assert_eq!(state(), (false, false));
+}
+
+#[tokio::test]
+async fn test_resource_and_then_refetching_follows_upstream_value() {
+    let user_id = signal(1);
+    let user = resource(move || {
+        let id = user_id.get();
+        async move { id }
+    });
+    let orders = user.and_then(|&id| {
+        resource(move || async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            id * 100
+        })
+    });
+    join().await;
+    assert_eq!(orders.value.get(), Some(100));
+
+    user_id.set(2);
+    // The upstream resolves immediately and the downstream follows it
+    tick(16);
+    assert_eq!(user.value.get(), Some(2));
+    assert_eq!(
+        (orders.loading.get(), orders.refetching.get()),
+        (false, true)
+    );
+    assert_eq!(orders.value.get(), Some(100));
+    join().await;
+    assert_eq!(orders.value.get(), Some(200));
+}
+
+#[tokio::test]
+async fn test_task_handle_abort() {
+    let completed = Arc::new(AtomicBool::new(false));
+    let cleaned = Arc::new(AtomicBool::new(false));
+
+    let (completed_in_task, cleaned_in_task) = (completed.clone(), cleaned.clone());
+    let s = scope(|| {});
+    let handle = s.run_in(|| {
+        spawn(async move {
+            on_cleanup(move || cleaned_in_task.store(true, Ordering::SeqCst));
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            completed_in_task.store(true, Ordering::SeqCst);
+        })
+    });
+
+    let (result, ()) = tokio::join!(handle_after_abort(handle), join());
+    assert_eq!(result, Err(Aborted));
+    assert!(!completed.load(Ordering::SeqCst));
+    // Aborting the task is not a disposal of its scope
+    assert!(!cleaned.load(Ordering::SeqCst));
+
+    s.dispose();
+    assert!(cleaned.load(Ordering::SeqCst));
+}
+
+async fn handle_after_abort(handle: TaskHandle) -> Result<(), Aborted> {
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert!(!handle.is_finished());
+    handle.abort();
+    handle.await
+}
+
+#[tokio::test]
+async fn test_task_handle_await_ordering() {
+    let log = signal(Vec::new());
+
+    let first = spawn(async move {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        log.write().push("first");
+    });
+    spawn(async move {
+        log.write().push("waiting");
+        assert_eq!(first.await, Ok(()));
+        log.write().push("after first");
+    });
+    // Dropping a handle does not cancel the task
+    drop(spawn(async move {
+        log.write().push("detached");
+    }));
+
+    join().await;
+    assert_eq!(
+        log.get(),
+        vec!["waiting", "detached", "first", "after first"]
+    );
+}
+
+#[tokio::test]
+async fn test_effect_async_aborts_previous_run() {
+    struct SetOnDrop(Arc<AtomicBool>);
+    impl Drop for SetOnDrop {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    let source = signal(1);
+    let finished = signal(Vec::new());
+    let first_dropped = Arc::new(AtomicBool::new(false));
+
+    let first_dropped_in_effect = first_dropped.clone();
+    effect_async(move || {
+        // Tracked before the future is polled
+        let key = source.get();
+        let guard = (key == 1).then(|| SetOnDrop(first_dropped_in_effect.clone()));
+        async move {
+            let _guard = guard;
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            finished.write().push(key);
+        }
+    });
+
+    tokio::join!(join(), async {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        source.set(2);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        // The first run was aborted and dropped mid-sleep
+        assert!(first_dropped.load(Ordering::SeqCst));
+    });
+
+    assert_eq!(finished.get(), vec![2]);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_spawn_blocking_feeds_signal() {
+    let reactive_thread = std::thread::current().id();
+    let result = signal(None);
+    spawn(async move {
+        let (sum, worker_thread) = spawn_blocking(|| {
+            std::thread::sleep(Duration::from_millis(10));
+            ((1..=100u32).sum::<u32>(), std::thread::current().id())
+        })
+        .await;
+        assert_ne!(worker_thread, std::thread::current().id());
+        result.set(Some(sum));
+    });
+
+    join().await;
+    assert_eq!(result.get(), Some(5050));
+    assert_eq!(std::thread::current().id(), reactive_thread);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_drive_on_tokio() {
+    let local = tokio::task::LocalSet::new();
+    local
+        .run_until(async {
+            let driver = drive_on_tokio();
+            let s = signal(0);
+
+            spawn(async move {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                s.set(1);
+            });
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            assert_eq!(s.get(), 1);
+
+            // Tasks spawned later wake the idle driver
+            spawn(async move { s.set(2) });
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            assert_eq!(s.get(), 2);
+
+            driver.abort();
+        })
+        .await;
+}
+
+#[tokio::test]
+async fn test_join_timeout_reports_pending() {
+    let (_tx, rx) = tokio::sync::oneshot::channel::<()>();
+    let done = signal(false);
+
+    // Waits on a channel no one writes to
+    spawn(async move {
+        let _ = rx.await;
+    });
+    spawn(async move {
+        sleep(Duration::from_millis(5)).await;
+        done.set(true);
+    });
+
+    let start = std::time::Instant::now();
+    let result = join_timeout(Duration::from_millis(30)).await;
+    assert_eq!(result, JoinResult::TimedOut { pending: 1 });
+    assert!(start.elapsed() >= Duration::from_millis(30));
+    assert!(done.get());
+
+    spawn(async {});
+    assert_eq!(
+        join_timeout(Duration::from_millis(10)).await,
+        JoinResult::TimedOut { pending: 1 }
+    );
+}
+
+#[tokio::test]
+async fn test_join_timeout_finished() {
+    let done = signal(false);
+    spawn(async move {
+        sleep(Duration::from_millis(5)).await;
+        done.set(true);
+    });
+    let result = join_timeout(Duration::from_secs(5)).await;
+    assert_eq!(result, JoinResult::Finished);
+    assert!(done.get());
+}
+
+#[tokio::test]
+async fn test_poll_for() {
+    let ticks = signal(0);
+    spawn(async move {
+        loop {
+            sleep(Duration::from_millis(5)).await;
+            ticks.update(|ticks| *ticks += 1);
+        }
+    });
+    poll_for(Duration::from_millis(28)).await;
+    assert!((3..=5).contains(&ticks.get()), "{}", ticks.get());
+}
+
+/// Returns `Pending` once, waking itself, like `tokio::task::yield_now`
+struct YieldOnce(bool);
+
+impl std::future::Future for YieldOnce {
+    type Output = ();
+
+    fn poll(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<()> {
+        if self.0 {
+            return std::task::Poll::Ready(());
+        }
+        self.0 = true;
+        cx.waker().wake_by_ref();
+        std::task::Poll::Pending
+    }
+}
+
+#[test]
+fn test_tick_bounded_progress() {
+    let finished = signal(0);
+    for _ in 0..3 {
+        spawn(async move {
+            YieldOnce(false).await;
+            finished.update(|finished| *finished += 1);
+        });
+    }
+
+    let first = tick(3);
+    assert!(first.completed < 3, "{first:?}");
+    assert_eq!(first.completed + first.remaining, 3);
+    assert_eq!(finished.get(), first.completed);
+
+    let second = tick(3);
+    assert_eq!(second.remaining, 0);
+    assert_eq!(first.completed + second.completed, 3);
+    assert_eq!(finished.get(), 3);
+
+    assert_eq!(tick(3), TickReport::default());
+}
+
+fn delayed(ms: u64) -> Resource<u64> {
+    resource(move || async move {
+        tokio::time::sleep(Duration::from_millis(ms)).await;
+        ms
+    })
+}
+
+#[tokio::test]
+async fn test_suspense_staggered_resources() {
+    let ((pending, count), _scope) = scope_with(|| {
+        provide_suspense();
+        let _fast = delayed(10);
+        let _slow = delayed(80);
+        (use_suspense(), pending_count())
+    });
+
+    assert!(pending.get());
+    assert_eq!(count.get(), 2);
+    poll_for(Duration::from_millis(40)).await;
+    assert!(pending.get());
+    assert_eq!(count.get(), 1);
+    join().await;
+    assert!(!pending.get());
+    assert_eq!(count.get(), 0);
+}
+
+#[tokio::test]
+async fn test_suspense_nested_boundaries() {
+    let ((outer, inner, refetch), _scope) = scope_with(|| {
+        provide_suspense();
+        let outer = use_suspense();
+        let _outer_data = delayed(10);
+        let ((inner, inner_data), _) = scope_with(|| {
+            provide_suspense();
+            (use_suspense(), delayed(80))
+        });
+        (outer, inner, inner_data)
+    });
+
+    assert!(outer.get() && inner.get());
+    poll_for(Duration::from_millis(20)).await;
+    // The inner resource still counts toward the outer boundary
+    assert!(outer.get() && inner.get());
+    join().await;
+    assert!(!outer.get() && !inner.get());
+
+    refetch.refetch();
+    assert!(outer.get() && inner.get());
+    join().await;
+    assert!(!outer.get());
+}
+
+#[tokio::test]
+async fn test_suspense_disposed_while_pending() {
+    scope(|| {
+        provide_suspense();
+        let pending = pending_count();
+        let child = scope_owned(|| {
+            delayed(10);
+        });
+        assert_eq!(pending.get(), 1);
+        drop(child);
+        assert_eq!(pending.get(), 0);
+    });
+    assert_eq!(pending_count().get(), 0);
+}
+
+#[tokio::test]
+async fn test_task_panic_is_resumed_from_join() {
+    use futures_util::FutureExt;
+    use std::panic::AssertUnwindSafe;
+
+    let healthy_done = signal(false);
+    let task_scope = scope(move || {
+        provide_context(7i32);
+        spawn(async {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            panic!("task failed");
+        });
+        spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            healthy_done.set(true);
+        });
+    });
+
+    let result = AssertUnwindSafe(join()).catch_unwind().await;
+    let payload = result.expect_err("the task panic is resumed");
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"task failed"));
+    // The healthy task ran to completion before the panic was reported
+    assert!(healthy_done.get());
+
+    // The task's captured scope did not leak into the caller's context
+    assert_eq!(use_context::<i32>(), None);
+    let runs = signal(0);
+    let source = signal(0);
+    effect(move || {
+        source.get();
+        runs.update(|runs| *runs += 1);
+    });
+    source.set(1);
+    assert_eq!(runs.get(), 2);
+    task_scope.dispose();
+}
+
+#[test]
+fn test_task_panic_is_resumed_from_tick() {
+    spawn(async { panic!("boom") });
+    let result = std::panic::catch_unwind(|| tick(4));
+    assert!(result.is_err());
+    assert_eq!(tick(4), TickReport::default());
+}
+
+#[tokio::test]
+async fn test_cancellation_token_lets_task_exit_cleanly() {
+    let flushed = signal(false);
+    let ticks = signal(0);
+    let s = scope(move || {
+        spawn(async move {
+            let token = use_context::<CancellationToken>().unwrap();
+            loop {
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = tokio::time::sleep(Duration::from_millis(2)) => {
+                        ticks.update(|ticks| *ticks += 1);
+                    }
+                }
+            }
+            flushed.set(true);
+        });
+    });
+
+    tokio::join!(join(), async {
+        while ticks.get() == 0 {
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        s.dispose_async().await;
+    });
+
+    assert!(flushed.get());
+    assert_eq!(count(), (3, 0));
+}
+
+#[test]
+fn test_cancellation_token_per_scope() {
+    let (tokens, _scope) = scope_with(|| {
+        let parent_token = cancellation_token();
+        let ((child_token, nested_token), _) = scope_with(|| {
+            let child_token = cancellation_token();
+            let (nested_token, _) = scope_with(cancellation_token);
+            (child_token, nested_token)
+        });
+        // Provided as a context and stable within the scope
+        assert!(use_context::<CancellationToken>().is_some());
+        assert!(!cancellation_token().is_cancelled());
+        vec![parent_token, child_token, nested_token]
+    });
+    assert!(tokens.iter().all(|token| !token.is_cancelled()));
+
+    let child = tokens[1].clone();
+    child.cancel();
+    assert!(tokens[2].is_cancelled());
+    assert!(!tokens[0].is_cancelled());
+}
+
+#[test]
+fn test_cancellation_token_cancelled_on_dispose_and_rerun() {
+    let (token, s) = scope_with(cancellation_token);
+    s.dispose();
+    assert!(token.is_cancelled());
+
+    let source = signal(0);
+    let seen = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    let seen_in_effect = seen.clone();
+    effect(move || {
+        source.get();
+        seen_in_effect.borrow_mut().push(cancellation_token());
+    });
+    source.set(1);
+    let seen = seen.borrow();
+    assert!(seen[0].is_cancelled());
+    assert!(!seen[1].is_cancelled());
+}
+
+#[tokio::test]
+async fn test_on_cleanup_async_awaited_by_dispose_async() {
+    let log = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    let s = scope({
+        let log = log.clone();
+        move || {
+            for name in ["a", "b"] {
+                let log = log.clone();
+                on_cleanup_async(move || {
+                    log.borrow_mut().push(format!("spawn {name}"));
+                    async move {
+                        tokio::time::sleep(Duration::from_millis(5)).await;
+                        log.borrow_mut().push(format!("done {name}"));
+                    }
+                });
+            }
+            for name in ["1", "2"] {
+                let log = log.clone();
+                on_cleanup(move || log.borrow_mut().push(format!("sync {name}")));
+            }
+        }
+    });
+
+    let seen_on_resolve = signal(0);
+    spawn({
+        let log = log.clone();
+        async move {
+            s.dispose_async().await;
+            seen_on_resolve.set(log.borrow().len());
+        }
+    });
+    join().await;
+    assert_eq!(seen_on_resolve.get(), 6);
+    // Sync cleanups first, then async ones, both in reverse order
+    assert_eq!(
+        &log.borrow()[..4],
+        ["sync 2", "sync 1", "spawn b", "spawn a"]
+    );
+    let mut done = log.borrow()[4..].to_vec();
+    done.sort();
+    assert_eq!(done, ["done a", "done b"]);
+}
+
+#[tokio::test]
+async fn test_on_cleanup_async_after_plain_dispose() {
+    let flushed = signal(0);
+    let s = scope(move || {
+        on_cleanup_async(move || async move {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            flushed.update(|n| *n += 1);
+        });
+    });
+    s.dispose();
+    assert_eq!(flushed.get(), 0);
+    join().await;
+    assert_eq!(flushed.get(), 1);
+}
+
+#[tokio::test]
+async fn test_on_cleanup_async_runs_on_effect_rerun() {
+    let source = signal(0);
+    let runs = signal(0);
+    effect(move || {
+        source.get();
+        on_cleanup_async(move || async move {
+            runs.update(|n| *n += 1);
+        });
+    });
+    source.set(1);
+    join().await;
+    assert_eq!(runs.get(), 1);
+}
+
+#[tokio::test]
+async fn test_pending_tasks() {
+    let sleeper = || tokio::time::sleep(Duration::from_millis(5));
+    let first = scope(move || {
+        spawn(sleeper());
+    });
+    let second = scope(move || {
+        scope(move || {
+            spawn(sleeper());
+        });
+    });
+    assert_eq!(pending_tasks(), 2);
+    assert_eq!(pending_tasks_for(first), 1);
+    // Counts tasks of descendant scopes too
+    assert_eq!(pending_tasks_for(second), 1);
+
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    {
+        let locations = pending_task_locations();
+        assert_eq!(locations.len(), 2);
+        assert!(locations[0].line() < locations[1].line());
+        assert_eq!(locations[0].file(), file!());
+    }
+
+    first.dispose();
+    assert_eq!(pending_tasks_for(first), 0);
+    assert_eq!(pending_tasks(), 1);
+
+    join().await;
+    assert_eq!(pending_tasks(), 0);
+    assert_eq!(pending_tasks_for(second), 0);
+}
+
+#[tokio::test]
+async fn test_resource_poll_every() {
+    let fetches = Arc::new(AtomicI32::new(0));
+    let counter = fetches.clone();
+    let data = resource(move || {
+        let fetch = counter.fetch_add(1, Ordering::SeqCst) + 1;
+        async move {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            fetch
+        }
+    });
+    let polling = data.poll_every(Duration::from_millis(5));
+
+    poll_for(Duration::from_millis(22)).await;
+    drop(polling);
+    join().await;
+
+    let total = fetches.load(Ordering::SeqCst);
+    assert!((3..=5).contains(&total), "{total} fetches");
+    assert_eq!(data.value.get(), Some(total));
+
+    // No more fetches once the guard is gone
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    join().await;
+    assert_eq!(fetches.load(Ordering::SeqCst), total);
+}
+
+#[tokio::test]
+async fn test_resource_poll_every_skips_while_in_flight() {
+    let fetches = Arc::new(AtomicI32::new(0));
+    let counter = fetches.clone();
+    let data = resource(move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(12))
+    });
+    let (_guard, polling) = scope_with(move || data.poll_every(Duration::from_millis(2)));
+
+    poll_for(Duration::from_millis(20)).await;
+    // Ticks during the slow first fetch were skipped
+    assert_eq!(fetches.load(Ordering::SeqCst), 2);
+
+    // Disposing the scope stops the timer too
+    polling.dispose();
+    join().await;
+    assert_eq!(fetches.load(Ordering::SeqCst), 2);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_watch_round_trips_through_a_signal() {
+    let (tx, rx) = tokio::sync::watch::channel(0);
+    let value = signal_from_watch(rx);
+    let seen = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    let seen_by_effect = seen.clone();
+    effect(move || seen_by_effect.borrow_mut().push(value.get()));
+
+    // A burst is written once
+    tick(10);
+    tx.send(1).unwrap();
+    tx.send(2).unwrap();
+    tick(10);
+    assert_eq!(*seen.borrow(), vec![0, 2]);
+
+    let source = signal(1);
+    let mut forwarded = signal_to_watch(source);
+    assert!(!forwarded.has_changed().unwrap());
+    start_batch();
+    source.set(2);
+    source.set(3);
+    end_batch();
+    forwarded.changed().await.unwrap();
+    assert_eq!(*forwarded.borrow_and_update(), 3);
+    assert!(!forwarded.has_changed().unwrap());
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_watch_bridges_stop_with_their_scope() {
+    let (tx, rx) = tokio::sync::watch::channel(0);
+    let source = signal(0);
+    let (mut forwarded, owner) = scope_with(move || {
+        signal_from_watch(rx);
+        signal_to_watch(source)
+    });
+    tick(10);
+    assert_eq!(pending_tasks(), 1);
+
+    owner.dispose();
+    join().await;
+    assert!(forwarded.changed().await.is_err());
+    assert!(tx.is_closed());
+    assert_eq!(pending_tasks(), 0);
+}
+
+#[tokio::test]
+async fn test_forward_stream_into_signal_sink() {
+    use futures_util::{SinkExt, StreamExt};
+
+    let (value, owner) = scope_with(|| signal(0));
+    let seen = Arc::new(AtomicI32::new(0));
+    let seen_by_effect = seen.clone();
+    effect(move || seen_by_effect.store(value.get(), Ordering::SeqCst));
+
+    spawn(async move {
+        let values = futures_util::stream::iter(1..=5).map(Ok);
+        values.forward(value.sink()).await.unwrap();
+    });
+    join().await;
+    assert_eq!(seen.load(Ordering::SeqCst), 5);
+
+    let mut sink = value.sink();
+    owner.dispose();
+    assert_eq!(sink.send(6).await, Err(Disposed));
+}
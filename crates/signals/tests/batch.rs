@@ -45,3 +45,303 @@ fn test_batch_nested() {
 
     assert_eq!(*value.borrow(), 4);
 }
+
+#[test]
+fn test_batch_depth_introspection() {
+    assert!(!is_batching());
+    assert_eq!(batch_depth(), 0);
+
+    start_batch();
+    assert!(is_batching());
+    assert_eq!(batch_depth(), 1);
+
+    start_batch();
+    assert_eq!(batch_depth(), 2);
+    end_batch();
+    assert_eq!(batch_depth(), 1);
+
+    end_batch();
+    assert!(!is_batching());
+    assert_eq!(batch_depth(), 0);
+}
+
+#[test]
+fn test_pending_effects_in_batch() {
+    let s1 = signal(1i32);
+    let s2 = signal(1i32);
+    let _e1 = effect(move || {
+        s1.get();
+    });
+    let _e2 = effect(move || {
+        s2.get();
+    });
+
+    assert_eq!(pending_effects(), 0);
+
+    start_batch();
+    s1.set(2);
+    assert_eq!(pending_effects(), 1);
+    s2.set(2);
+    assert_eq!(pending_effects(), 2);
+    end_batch();
+
+    assert_eq!(pending_effects(), 0);
+}
+
+#[test]
+fn test_introspection_during_end_batch_flush() {
+    let s = signal(0i32);
+    let observed = Rc::new(RefCell::new(Vec::new()));
+
+    let observed_for_closure = observed.clone();
+    let _effect = effect(move || {
+        s.get();
+        observed_for_closure
+            .borrow_mut()
+            .push((is_batching(), batch_depth(), pending_effects()));
+    });
+
+    start_batch();
+    s.set(1);
+    end_batch();
+
+    // The flush runs after the depth reaches zero
+    assert_eq!(*observed.borrow(), vec![(false, 0, 0), (false, 0, 0)]);
+}
+
+#[test]
+fn test_flush_sync_without_pending_is_noop() {
+    let s = signal(0i32);
+    let runs = Rc::new(RefCell::new(0));
+
+    let runs_for_closure = runs.clone();
+    let _effect = effect(move || {
+        s.get();
+        *runs_for_closure.borrow_mut() += 1;
+    });
+
+    flush_sync();
+    assert_eq!(*runs.borrow(), 1);
+}
+
+#[test]
+fn test_flush_sync_inside_batch() {
+    let s1 = signal(0i32);
+    let s2 = signal(0i32);
+    let s3 = signal(0i32);
+    let values = Rc::new(RefCell::new(Vec::new()));
+
+    let values_for_closure = values.clone();
+    let _effect = effect(move || {
+        values_for_closure
+            .borrow_mut()
+            .push(s1.get() + s2.get() + s3.get());
+    });
+
+    start_batch();
+    s1.set(1);
+    s2.set(2);
+    s3.set(3);
+    assert_eq!(*values.borrow(), vec![0]);
+
+    flush_sync();
+    assert_eq!(*values.borrow(), vec![0, 6]);
+    assert_eq!(pending_effects(), 0);
+
+    // Writes after the forced flush are deferred until the batch closes
+    s1.set(10);
+    assert_eq!(*values.borrow(), vec![0, 6]);
+    end_batch();
+
+    assert_eq!(*values.borrow(), vec![0, 6, 15]);
+}
+
+#[test]
+#[should_panic(expected = "flush_sync() called while effects are already being flushed")]
+fn test_flush_sync_during_flush_panics() {
+    let s = signal(0i32);
+    let _effect = effect(move || {
+        if s.get() > 0 {
+            flush_sync();
+        }
+    });
+
+    s.set(1);
+}
+
+#[test]
+fn test_unmatched_end_batch_panics_and_recovers() {
+    let s = signal(0i32);
+    let value = Rc::new(RefCell::new(0i32));
+
+    let value_for_closure = value.clone();
+    let _effect = effect(move || {
+        *value_for_closure.borrow_mut() = s.get();
+    });
+
+    let err = std::panic::catch_unwind(|| end_batch()).unwrap_err();
+    let message = err.downcast_ref::<String>().unwrap();
+    assert!(message.contains("without a matching start_batch()"));
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    assert!(message.contains("batch.rs"));
+
+    // The depth was not corrupted, so writes still notify
+    assert_eq!(batch_depth(), 0);
+    s.set(1);
+    assert_eq!(*value.borrow(), 1);
+}
+
+#[test]
+#[cfg(not(feature = "abort-on-unclosed-batch"))]
+fn test_unclosed_batch_at_thread_exit_is_reported() {
+    // The leak is reported on stderr rather than by panicking in the
+    // runtime's thread-local destructor, which would abort the process
+    let handle = std::thread::spawn(|| {
+        let s = signal(0i32);
+        start_batch();
+        s.set(1);
+    });
+    assert!(handle.join().is_ok());
+}
+
+#[test]
+fn test_flush_stats_counts_skipped_clean() {
+    let s1 = signal(0i32);
+    let s2 = signal(0i32);
+    let s3 = signal(0i32);
+    let large = memo(move || s3.get() > 100);
+    let runs = Rc::new(RefCell::new(0));
+
+    let runs1 = runs.clone();
+    let _e1 = effect(move || {
+        s1.get();
+        *runs1.borrow_mut() += 1;
+    });
+    let runs2 = runs.clone();
+    let _e2 = effect(move || {
+        s2.get();
+        *runs2.borrow_mut() += 1;
+    });
+    let runs3 = runs.clone();
+    let _e3 = effect(move || {
+        large.get();
+        *runs3.borrow_mut() += 1;
+    });
+
+    assert_eq!(last_flush_stats(), None);
+
+    let delivered = Rc::new(RefCell::new(Vec::new()));
+    let delivered_for_callback = delivered.clone();
+    on_flush(move |stats| delivered_for_callback.borrow_mut().push(*stats));
+
+    start_batch();
+    s1.set(1);
+    s2.set(1);
+    // Queues the third effect, but the memo's value does not change
+    s3.set(1);
+    end_batch();
+
+    assert_eq!(*runs.borrow(), 5);
+
+    let stats = last_flush_stats().unwrap();
+    assert_eq!(stats.queued, 3);
+    assert_eq!(stats.ran, 2);
+    assert_eq!(stats.skipped_clean, 1);
+    assert_eq!(*delivered.borrow(), vec![stats]);
+}
+
+#[test]
+fn test_flush_hooks_count_flushes() {
+    let a = signal(0i32);
+    let _e = effect(move || {
+        a.get();
+    });
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let start = on_flush_start({
+        let events = events.clone();
+        move || events.borrow_mut().push("start")
+    });
+    let end = on_flush_end({
+        let events = events.clone();
+        move |_| events.borrow_mut().push("end")
+    });
+
+    start_batch();
+    a.set(1);
+    a.set(2);
+    a.set(3);
+    end_batch();
+    assert_eq!(*events.borrow(), ["start", "end"]);
+
+    events.borrow_mut().clear();
+    a.set(4);
+    a.set(5);
+    assert_eq!(*events.borrow(), ["start", "end", "start", "end"]);
+
+    start.remove();
+    end.remove();
+    a.set(6);
+    assert_eq!(events.borrow().len(), 4);
+}
+
+#[test]
+fn test_flush_end_hook_writes_wait_for_next_flush() {
+    let a = signal(0i32);
+    let mirror = signal(0i32);
+    let runs = Rc::new(RefCell::new(Vec::new()));
+    let _e = effect({
+        let runs = runs.clone();
+        move || runs.borrow_mut().push(mirror.get())
+    });
+    let _a_effect = effect(move || {
+        a.get();
+    });
+
+    let flushes = Rc::new(RefCell::new(0));
+    let _hook = on_flush_end({
+        let flushes = flushes.clone();
+        move |_| {
+            *flushes.borrow_mut() += 1;
+            mirror.set(a.get_untracked());
+        }
+    });
+
+    a.set(1);
+    // The hook's write is queued, not flushed re-entrantly
+    assert_eq!(*flushes.borrow(), 1);
+    assert_eq!(*runs.borrow(), [0]);
+    assert_eq!(pending_effects(), 1);
+
+    flush_sync();
+    assert_eq!(*runs.borrow(), [0, 1]);
+    assert_eq!(*flushes.borrow(), 2);
+}
+
+#[test]
+fn test_auto_batch_coalesces_writes() {
+    let s = signal(0i32);
+    let values = Rc::new(RefCell::new(Vec::new()));
+
+    let values_for_closure = values.clone();
+    let _effect = effect(move || {
+        values_for_closure.borrow_mut().push(s.get());
+    });
+
+    set_auto_batch(true);
+    assert!(auto_batch());
+    s.set(1);
+    s.set(2);
+    s.set(3);
+    assert_eq!(*values.borrow(), vec![0]);
+
+    flush_sync();
+    assert_eq!(*values.borrow(), vec![0, 3]);
+
+    // Disabling restores synchronous flushing and drains deferred work
+    s.set(4);
+    set_auto_batch(false);
+    assert_eq!(*values.borrow(), vec![0, 3, 4]);
+    s.set(5);
+    assert_eq!(*values.borrow(), vec![0, 3, 4, 5]);
+}
@@ -35,21 +35,93 @@ fn test_guard_drop_releases_borrow() {
     let _guard2 = s.read(); // Should work after drop
 }
 
+#[test]
+fn test_guards_release_exactly_once() {
+    let s = signal(vec![1, 2, 3]);
+    let runs = signal(0);
+    effect(move || {
+        s.track();
+        runs.update(|runs| *runs += 1);
+    });
+
+    {
+        let first = s.read();
+        {
+            let second = s.peek();
+            assert_eq!(second.len() + first[0], 4);
+        }
+        // The first read guard still holds its borrow
+        let held = std::panic::catch_unwind(move || {
+            s.write();
+        });
+        assert!(held.is_err());
+    }
+    let mut sum = 0;
+    let guard = s.read();
+    for _ in 0..1000 {
+        sum += guard[2];
+    }
+    drop(guard);
+    assert_eq!(sum, 3000);
+
+    {
+        let mut guard = s.write();
+        guard.push(4);
+        guard[0] = 0;
+    }
+    // One notification per write guard, and no borrow left behind
+    assert_eq!(runs.get_untracked(), 2);
+    s.set(vec![]);
+    assert_eq!(runs.get_untracked(), 3);
+}
+
 // Panic scenarios - SHOULD panic
+fn panic_message(f: impl FnOnce() + std::panic::UnwindSafe) -> String {
+    let err = std::panic::catch_unwind(f).unwrap_err();
+    err.downcast_ref::<String>().unwrap().clone()
+}
+
 #[test]
-#[should_panic]
 fn test_write_then_read_panics() {
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    let created = line!() + 1;
     let s = signal(42i32);
-    let _write_guard = s.write();
-    let _read_guard = s.read(); // Panic
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    let (written, read) = (line!() + 2, line!() + 3);
+    let message = panic_message(move || {
+        let _write_guard = s.write();
+        let _read_guard = s.read(); // Panic
+    });
+
+    assert!(
+        message.starts_with("Cannot borrow signal as readable while already borrowed as writable")
+    );
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    {
+        assert!(message.contains(&format!("signal created at {}:{created}:", file!())));
+        assert!(message.contains(&format!("read attempted at {}:{read}:", file!())));
+        assert!(message.contains(&format!("write guard taken at {}:{written}:", file!())));
+    }
 }
 
 #[test]
-#[should_panic]
 fn test_read_then_write_panics() {
     let s = signal(42i32);
-    let _read_guard = s.read();
-    let _write_guard = s.write(); // Panic
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    let (read, written) = (line!() + 2, line!() + 3);
+    let message = panic_message(move || {
+        let _read_guard = s.read();
+        let _write_guard = s.write(); // Panic
+    });
+
+    assert!(
+        message.starts_with("Cannot borrow signal as writable while already borrowed as readable")
+    );
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    {
+        assert!(message.contains(&format!("write attempted at {}:{written}:", file!())));
+        assert!(message.contains(&format!("read guard taken at {}:{read}:", file!())));
+    }
 }
 
 #[test]
@@ -69,11 +141,43 @@ fn test_write_guard_prevents_peek() {
 }
 
 #[test]
-#[should_panic]
 fn test_read_guard_prevents_set() {
     let s = signal(42i32);
-    let _read_guard = s.read();
-    s.set(100); // Panic - cannot set while reading
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    let (read, set) = (line!() + 2, line!() + 3);
+    let message = panic_message(move || {
+        let _read_guard = s.read();
+        s.set(100); // Panic - cannot set while reading
+    });
+
+    assert!(
+        message.starts_with("Cannot borrow signal as writable while already borrowed as readable")
+    );
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    {
+        assert!(message.contains(&format!("write attempted at {}:{set}:", file!())));
+        assert!(message.contains(&format!("read guard taken at {}:{read}:", file!())));
+    }
+}
+
+#[test]
+fn test_with_prevents_set() {
+    let s = signal(42i32);
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    let with = line!() + 2;
+    let message = panic_message(move || {
+        s.with(|_| s.set(100)); // Panic - cannot set while reading
+    });
+
+    assert!(
+        message.starts_with("Cannot borrow signal as writable while already borrowed as readable")
+    );
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    assert!(message.contains(&format!("read guard taken at {}:{with}:", file!())));
+
+    // The borrow ends with the closure, even one that panicked
+    s.set(100);
+    assert_eq!(s.get(), 100);
 }
 
 #[test]
@@ -83,3 +187,21 @@ fn test_write_guard_prevents_set() {
     let _write_guard = s.write();
     s.set(100); // Panic - cannot set while writing
 }
+
+#[test]
+fn test_dispose_while_borrowed_panics() {
+    let s = signal(42i32);
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    let read = line!() + 2;
+    let message = panic_message(move || {
+        let _read_guard = s.read();
+        s.dispose(); // Panic - the guard points at the value
+    });
+
+    assert!(message.starts_with("Signal disposed while a guard still borrows it"));
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    assert!(message.contains(&format!("guard taken at {}:{read}:", file!())));
+
+    // The guard released its borrow while unwinding
+    s.dispose();
+}
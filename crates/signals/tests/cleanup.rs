@@ -182,3 +182,74 @@ fn test_nest_cleanup() {
 
     assert_eq!(vec.get(), vec![222, 221, 22, 21, 12, 11, 3, 2, 1, 0]);
 }
+
+#[test]
+fn test_on_cleanup_at_root_runs_on_global_cleanup() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+
+    let log_in_cleanup = log.clone();
+    on_cleanup(move || log_in_cleanup.borrow_mut().push("root"));
+    assert!(log.borrow().is_empty());
+
+    cleanup();
+    assert_eq!(*log.borrow(), vec!["root"]);
+}
+
+#[test]
+fn test_try_on_cleanup_outside_scope() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+
+    let log_in_cleanup = log.clone();
+    let result = try_on_cleanup(move || log_in_cleanup.borrow_mut().push("root"));
+    assert_eq!(result, Err(OutsideScopeError));
+
+    // The rejected callback is not kept around
+    cleanup();
+    assert!(log.borrow().is_empty());
+}
+
+#[test]
+fn test_try_on_cleanup_nested() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+
+    let log_in_scope = log.clone();
+    let outer = scope(move || {
+        let log_in_cleanup = log_in_scope.clone();
+        assert_eq!(
+            try_on_cleanup(move || log_in_cleanup.borrow_mut().push("scope")),
+            Ok(())
+        );
+
+        let log_in_effect = log_in_scope.clone();
+        effect(move || {
+            let log_in_cleanup = log_in_effect.clone();
+            assert_eq!(
+                try_on_cleanup(move || log_in_cleanup.borrow_mut().push("effect")),
+                Ok(())
+            );
+        });
+    });
+
+    outer.dispose();
+    assert_eq!(*log.borrow(), vec!["effect", "scope"]);
+}
+
+#[test]
+fn test_deeply_nested_scopes_dispose_without_overflow() {
+    let order = Rc::new(RefCell::new(Vec::new()));
+    let outer = scope(|| {});
+    let mut current = outer;
+    for depth in 0..10_000 {
+        let order = order.clone();
+        current.run_in(|| on_cleanup(move || order.borrow_mut().push(depth)));
+        current = current.child();
+    }
+
+    outer.dispose();
+
+    let order = order.borrow();
+    assert_eq!(order.len(), 10_000);
+    assert_eq!(order[..3], [9999, 9998, 9997]);
+    assert_eq!(order.last(), Some(&0));
+    assert_eq!(count(), (1, 0));
+}
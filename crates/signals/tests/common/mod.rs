@@ -0,0 +1,11 @@
+use samara_signals::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Records every value `computed` takes, from an effect reading it
+pub fn record<T: Clone + 'static>(computed: Computed<T>) -> Rc<RefCell<Vec<T>>> {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let seen_by_effect = seen.clone();
+    effect(move || seen_by_effect.borrow_mut().push(computed.get()));
+    seen
+}
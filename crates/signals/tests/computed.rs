@@ -230,3 +230,68 @@ fn test_computed_diamond_effect() {
     src.set(2);
     assert_eq!(*c.borrow(), 3);
 }
+
+#[cfg(debug_assertions)]
+fn cycle_message(f: impl FnOnce() + std::panic::UnwindSafe) -> String {
+    let err = std::panic::catch_unwind(f).unwrap_err();
+    err.downcast_ref::<String>().unwrap().clone()
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn test_cycle_on_first_read_lists_locations() {
+    let slot: Rc<std::cell::Cell<Option<Computed<i32>>>> = Rc::default();
+    let b_line = line!() + 1;
+    let b = memo({
+        let slot = slot.clone();
+        move || slot.get().unwrap().get() + 1
+    });
+    let a_line = line!() + 1;
+    let a = memo(move || b.get() + 1);
+    slot.set(Some(a));
+    let message = cycle_message(move || {
+        a.get();
+    });
+
+    let a_at = format!("computed.rs:{a_line}:13");
+    let b_at = format!("computed.rs:{b_line}:13");
+    assert!(
+        message.starts_with("dependency cycle detected:"),
+        "{message}"
+    );
+    let lines: Vec<_> = message.lines().skip(1).collect();
+    assert_eq!(lines.len(), 3, "{message}");
+    assert!(lines[0].contains(&a_at), "{message}");
+    assert!(lines[1].contains(&b_at), "{message}");
+    assert!(lines[2].contains(&a_at), "{message}");
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn test_cycle_formed_after_first_read() {
+    let s = signal(0);
+    let slot: Rc<std::cell::Cell<Option<Computed<i32>>>> = Rc::default();
+    let a_line = line!() + 1;
+    let a = memo({
+        let slot = slot.clone();
+        move || s.get() + slot.get().map_or(0, |b| b.get())
+    });
+    let b_line = line!() + 1;
+    let b = memo(move || a.get() + 1);
+    assert_eq!(b.get(), 1);
+
+    // `a` now reads `b`, which still depends on `a`
+    slot.set(Some(b));
+    s.set(1);
+    let message = cycle_message(move || {
+        a.get();
+    });
+
+    let a_at = format!("computed.rs:{a_line}:13");
+    let b_at = format!("computed.rs:{b_line}:13");
+    let lines: Vec<_> = message.lines().skip(1).collect();
+    assert_eq!(lines.len(), 3, "{message}");
+    assert!(lines[0].contains(&a_at), "{message}");
+    assert!(lines[1].contains(&b_at), "{message}");
+    assert!(lines[2].contains(&a_at), "{message}");
+}
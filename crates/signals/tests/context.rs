@@ -1,4 +1,13 @@
-use samara_signals::{computed, effect, provide_context, scope, scoped, signal, use_context};
+use std::cell::Cell;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::rc::Rc;
+
+use samara_signals::{
+    ContextSnapshot, computed, count, effect, expect_context, provide_context, provide_context_dyn,
+    provide_context_keyed, provide_reactive_context, remove_context, reparent, scope, scoped,
+    signal, use_context, use_context_dyn, use_context_keyed, use_context_or_provide,
+    use_reactive_context,
+};
 
 #[derive(Clone, Debug, PartialEq)]
 struct Theme(String);
@@ -47,6 +56,28 @@ fn test_integration_context_shadowing() {
     });
 }
 
+#[test]
+fn test_integration_remove_context_unshadows_parent() {
+    scope(|| {
+        provide_context(Config(10));
+
+        scope(|| {
+            provide_context(Config(20));
+            assert_eq!(use_context::<Config>().unwrap(), Config(20));
+
+            assert_eq!(remove_context::<Config>(), Some(Config(20)));
+            assert_eq!(use_context::<Config>().unwrap(), Config(10));
+
+            // Nothing left to remove in this scope; the parent is untouched
+            assert_eq!(remove_context::<Config>(), None);
+            assert_eq!(use_context::<Config>().unwrap(), Config(10));
+        });
+
+        assert_eq!(remove_context::<Config>(), Some(Config(10)));
+        assert!(use_context::<Config>().is_none());
+    });
+}
+
 #[test]
 fn test_integration_multiple_context_types() {
     scope(|| {
@@ -177,3 +208,247 @@ fn test_scoped_basic_context() {
         assert_eq!(context, Theme(String::from("light")));
     });
 }
+
+#[test]
+fn test_use_context_or_provide_hit() {
+    scope(|| {
+        provide_context(Config(1));
+
+        scope(|| {
+            let called = Rc::new(Cell::new(false));
+            let called_in_default = called.clone();
+            let config = use_context_or_provide(move || {
+                called_in_default.set(true);
+                Config(2)
+            });
+            assert_eq!(config, Config(1));
+            assert!(!called.get());
+        });
+    });
+}
+
+#[test]
+fn test_use_context_or_provide_miss() {
+    scope(|| {
+        let config = use_context_or_provide(|| Config(7));
+        assert_eq!(config, Config(7));
+
+        // Provided in this scope, so later children see it
+        scope(|| {
+            assert_eq!(use_context::<Config>(), Some(Config(7)));
+        });
+    });
+
+    assert!(use_context::<Config>().is_none());
+}
+
+#[test]
+fn test_expect_context() {
+    scope(|| {
+        provide_context(Config(3));
+        scope(|| assert_eq!(expect_context::<Config>(), Config(3)));
+    });
+
+    let err = catch_unwind(AssertUnwindSafe(|| {
+        scope(|| {
+            expect_context::<Config>();
+        });
+    }))
+    .unwrap_err();
+    let message = err.downcast_ref::<String>().unwrap();
+    assert!(message.contains("Config"), "{message}");
+    assert!(message.contains(file!()), "{message}");
+}
+
+#[cfg(any(debug_assertions, feature = "track-caller"))]
+#[test]
+fn test_expect_context_lists_scope_chain() {
+    let scope_line = line!() + 3;
+    let err = catch_unwind(AssertUnwindSafe(|| {
+        scope(|| {
+            scope(|| {
+                expect_context::<Theme>();
+            });
+        });
+    }))
+    .unwrap_err();
+    let message = err.downcast_ref::<String>().unwrap();
+    assert!(message.contains("expect_context::<"), "{message}");
+    assert!(message.contains("Theme"), "{message}");
+    assert!(
+        message.contains(&format!("{}:{}", file!(), scope_line)),
+        "{message}"
+    );
+}
+
+#[test]
+fn test_keyed_contexts_shadow_independently() {
+    scope(|| {
+        provide_context(0i32);
+        provide_context_keyed(String::from("width"), 10i32);
+        provide_context_keyed(String::from("height"), 20i32);
+
+        scope(|| {
+            provide_context_keyed(String::from("width"), 11i32);
+
+            assert_eq!(
+                use_context_keyed::<_, i32>(&String::from("width")),
+                Some(11)
+            );
+            assert_eq!(
+                use_context_keyed::<_, i32>(&String::from("height")),
+                Some(20)
+            );
+            // Type-keyed context is unaffected
+            assert_eq!(use_context::<i32>(), Some(0));
+
+            scope(|| {
+                provide_context_keyed(String::from("height"), 21i32);
+                assert_eq!(
+                    use_context_keyed::<_, i32>(&String::from("width")),
+                    Some(11)
+                );
+                assert_eq!(
+                    use_context_keyed::<_, i32>(&String::from("height")),
+                    Some(21)
+                );
+            });
+        });
+
+        assert_eq!(
+            use_context_keyed::<_, i32>(&String::from("width")),
+            Some(10)
+        );
+        assert_eq!(
+            use_context_keyed::<_, i32>(&String::from("height")),
+            Some(20)
+        );
+        assert_eq!(use_context_keyed::<_, i32>(&String::from("depth")), None);
+        // Same key, different value type
+        assert_eq!(use_context_keyed::<_, u8>(&String::from("width")), None);
+    });
+}
+
+#[test]
+fn test_reactive_context_notifies_grandchildren() {
+    let seen = Rc::new(std::cell::RefCell::new(Vec::new()));
+
+    let seen_in_effect = seen.clone();
+    let root = scope(move || {
+        let theme = provide_reactive_context(Theme(String::from("light")));
+
+        scope(move || {
+            scope(move || {
+                effect(move || {
+                    let theme = use_reactive_context::<Theme>().unwrap();
+                    seen_in_effect.borrow_mut().push(theme.get().0);
+                });
+            });
+        });
+
+        theme.set(Theme(String::from("dark")));
+    });
+
+    assert_eq!(*seen.borrow(), vec!["light", "dark"]);
+
+    // The signal is owned by the providing scope
+    root.dispose();
+    assert_eq!(count().0, 1);
+}
+
+#[test]
+fn test_dyn_context_from_grandchild_effect() {
+    let results = Rc::new(std::cell::RefCell::new(Vec::new()));
+
+    let results_in_effect = results.clone();
+    scope(move || {
+        provide_context_dyn::<dyn Fn(i32) -> i32>(Rc::new(|x| x * 10));
+
+        scope(move || {
+            scope(move || {
+                effect(move || {
+                    let f = use_context_dyn::<dyn Fn(i32) -> i32>().unwrap();
+                    results_in_effect.borrow_mut().push(f(4));
+                });
+            });
+
+            // Shadowing works like typed contexts
+            provide_context_dyn::<dyn Fn(i32) -> i32>(Rc::new(|x| x + 1));
+            let f = use_context_dyn::<dyn Fn(i32) -> i32>().unwrap();
+            assert_eq!(f(4), 5);
+        });
+    });
+
+    assert_eq!(*results.borrow(), vec![40]);
+    assert!(use_context_dyn::<dyn Fn(i32) -> i32>().is_none());
+}
+
+#[test]
+fn test_context_snapshot_replays_inner_values() {
+    let snapshot = scope(|| {}).run_in(|| {
+        provide_context(Theme(String::from("light")));
+        provide_context(Config(1));
+        scope(|| {}).run_in(|| {
+            provide_context(Config(2));
+            scope(|| {}).run_in(|| {
+                provide_context(Theme(String::from("dark")));
+                scope(|| {}).run_in(ContextSnapshot::capture)
+            })
+        })
+    });
+    assert_eq!(snapshot.len(), 2);
+
+    // Replay at root, far away from where it was captured
+    let ((theme, config), portal) = snapshot.run_with(|| {
+        (
+            use_context::<Theme>().unwrap(),
+            use_context::<Config>().unwrap(),
+        )
+    });
+    assert_eq!(theme, Theme(String::from("dark")));
+    assert_eq!(config, Config(2));
+
+    // Children of the portal inherit as usual
+    portal.run_in(|| scope(|| assert_eq!(use_context::<Config>(), Some(Config(2)))));
+
+    // provide_into overwrites values the target already provides
+    let target = scope(|| provide_context(Config(9)));
+    snapshot.provide_into(&target);
+    assert_eq!(target.run_in(use_context::<Config>), Some(Config(2)));
+
+    assert!(use_context::<Config>().is_none());
+}
+
+#[test]
+fn test_cached_lookups_follow_later_providers() {
+    let tick = signal(0);
+    let seen = Rc::new(std::cell::RefCell::new(Vec::new()));
+    let outer = scope(|| provide_context(Config(1)));
+    let middle = outer.run_in(|| scope(|| {}));
+    let other = scope(|| provide_context(Config(3)));
+    let seen_by_effect = seen.clone();
+    middle.run_in(move || {
+        scope(move || {
+            effect(move || {
+                tick.get();
+                seen_by_effect
+                    .borrow_mut()
+                    .push(use_context::<Config>().map(|config| config.0));
+            });
+        })
+    });
+
+    // A cached lookup must not hide a value provided closer afterwards
+    tick.set(1);
+    middle.run_in(|| provide_context(Config(2)));
+    tick.set(2);
+    middle.run_in(remove_context::<Config>);
+    tick.set(3);
+    // ...nor survive the scope moving under different ancestors
+    reparent(middle.node(), other.node());
+    tick.set(4);
+    assert_eq!(
+        *seen.borrow(),
+        vec![Some(1), Some(1), Some(2), Some(1), Some(3)]
+    );
+}
@@ -2,16 +2,16 @@ use samara_signals::*;
 
 #[test]
 fn test_count_signal() {
-    let (nodes_before, links_before) = count();
     let _sig = signal(42);
-    let (nodes_after, links_after) = count();
 
     // Creating a signal should create exactly 1 node, 0 links
-    assert_eq!(nodes_after - nodes_before, 1);
-    assert_eq!(links_after - links_before, 0);
+    let stats = stats();
+    assert_eq!(stats.signals, 1);
+    assert_eq!(stats.total_nodes_excluding_root, 1);
+    assert_eq!(stats.links, 0);
 
     cleanup();
-    assert_eq!(count(), (1, 0));
+    assert_eq!(self::stats(), CountStats::default());
 }
 
 #[test]
@@ -85,11 +85,10 @@ fn test_count_scope() {
 
     // scope adds 1 scope node + 2 child nodes (signal + computed)
     // No links created since computed doesn't access signal
-    let nodes_delta = nodes_after - nodes_before;
-    let links_delta = links_after - links_before;
-
-    assert_eq!(nodes_delta, 3);
-    assert_eq!(links_delta, 0);
+    let stats = stats();
+    assert_eq!((stats.scopes, stats.signals, stats.computeds), (1, 1, 1));
+    assert_eq!(stats.total_nodes_excluding_root, nodes_after - nodes_before);
+    assert_eq!(stats.links, links_after - links_before);
 
     // After cleanup, nodes should decrease
     scope.dispose();
@@ -102,6 +101,44 @@ fn test_count_scope() {
     assert_eq!(count(), (1, 0));
 }
 
+#[test]
+fn test_count_per_scope() {
+    let (nodes_before, links_before) = count();
+
+    let outer_sig = signal(1);
+    let scope = scope(move || {
+        let sig = signal(100);
+        let _comp = memo(|| 50);
+        scope(move || {
+            effect(move || {
+                let _ = sig.get() + outer_sig.get();
+            });
+        });
+        effect(|| {});
+    });
+    let (nodes_after, links_after) = count();
+
+    // Everything except `outer_sig` lives under `scope`
+    assert_eq!(
+        scope.node_count(),
+        (nodes_after - nodes_before - 1, links_after - links_before)
+    );
+    // 2 dependency links for the inner effect + 1 owner link per effect
+    assert_eq!(scope.node_count(), (6, 4));
+    assert_eq!(scope.descendant_count(), 5);
+
+    let children = scope.children();
+    assert_eq!(children.len(), 2);
+    assert_eq!(
+        children.iter().map(Scope::descendant_count).sum::<usize>(),
+        1
+    );
+
+    scope.dispose();
+    assert_eq!(scope.node_count(), (0, 0));
+    assert_eq!(count(), (nodes_before + 1, links_before));
+}
+
 #[test]
 fn test_count_with_links() {
     let (nodes_before, links_before) = count();
@@ -202,10 +239,48 @@ fn test_count_cleanup() {
 
     scope.dispose();
 
-    let (nodes_after, links_after) = count();
+    assert_no_leaks((nodes_before, links_before));
+    assert!(leak_report().is_clean());
+}
 
-    assert_eq!(nodes_after, nodes_before);
-    assert_eq!(links_after, links_before);
+#[test]
+fn test_assert_no_leaks_names_leaked_node() {
+    let baseline = count();
+
+    let scope = scope(|| {
+        let s = signal(1);
+        let _c = memo(move || s.get() + 1);
+    });
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    let line = line!() + 1;
+    let leaked = signal(0);
+    let _ = memo(move || leaked.get());
+    scope.dispose();
+
+    let err = std::panic::catch_unwind(|| assert_no_leaks(baseline)).unwrap_err();
+    let message = err.downcast_ref::<String>().unwrap();
+    assert!(
+        message.starts_with(&format!(
+            "expected {} nodes and 0 links, found {} nodes and 0 links",
+            baseline.0,
+            baseline.0 + 2
+        )),
+        "{message}"
+    );
+    assert!(
+        message.contains("owned by the root scope:\n  Computed"),
+        "{message}"
+    );
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    assert!(
+        message.contains(&format!(
+            "Signal created at crates/signals/tests/count.rs:{line}:18"
+        )),
+        "{message}"
+    );
+
+    cleanup();
+    assert_no_leaks((1, 0));
 }
 
 #[test]
@@ -471,3 +546,189 @@ fn test_effect_run_no_leak() {
     assert_eq!(prev, (initial.0 + 5, initial.1 + 5));
     assert_eq!(prev, count());
 }
+
+#[test]
+fn test_stats_breakdown_by_kind() {
+    assert_eq!(stats(), CountStats::default());
+
+    let sig = signal(1);
+    let doubled = memo(move || sig.get() * 2);
+    let ui = scope(move || {
+        effect(move || {
+            doubled.get();
+        });
+    });
+
+    // sig -> doubled, doubled -> effect, and the effect's owner link
+    assert_eq!(
+        stats(),
+        CountStats {
+            signals: 1,
+            computeds: 1,
+            effects: 1,
+            scopes: 1,
+            links: 3,
+            total_nodes_excluding_root: 4,
+            max_stack_depth: 0,
+        }
+    );
+    assert_eq!(count(), (5, 3));
+
+    ui.dispose();
+    let stats = stats();
+    assert_eq!((stats.effects, stats.scopes), (0, 0));
+    assert_eq!(stats.total_nodes_excluding_root, 2);
+}
+
+#[cfg(any(debug_assertions, feature = "track-caller"))]
+#[test]
+fn test_nodes_by_location_groups_memos() {
+    let a = signal(1);
+    let before = nodes_by_location();
+
+    let mut memos = Vec::new();
+    let line = line!() + 2;
+    for i in 0..10 {
+        memos.push(memo(move || a.get() + i));
+    }
+
+    let counts = nodes_by_location();
+    let (location, kind, count) = counts[0];
+    assert_eq!((location.file(), location.line()), (file!(), line));
+    assert_eq!((kind, count), (NodeKind::Computed, 10));
+
+    let changes = diff_nodes_by_location(&before, &counts);
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].delta(), 10);
+    assert!(
+        changes[0]
+            .to_string()
+            .starts_with(&format!("10 new Computed nodes from {}:{line}:", file!())),
+        "{}",
+        changes[0]
+    );
+}
+
+#[test]
+fn test_links_capacity_steady_across_updates() {
+    let src = signal(0);
+    let left = signal(0);
+    let right = signal(0);
+    let mut last = memo(move || src.get());
+    for _ in 0..10 {
+        let prev = last;
+        last = memo(move || prev.get() + 1);
+    }
+    for _ in 0..10 {
+        effect(move || {
+            last.get();
+        });
+    }
+    // Switches between two dependencies, so its links are rebuilt each time
+    effect(move || {
+        if src.get() % 2 == 0 {
+            left.get();
+        } else {
+            right.get();
+        }
+    });
+
+    src.set(1);
+    let (counts, capacity) = (count(), links_capacity());
+    for i in 2..=1000 {
+        src.set(i);
+        assert_eq!(count(), counts);
+    }
+    assert_eq!(links_capacity(), capacity);
+}
+
+#[test]
+fn test_reserve_grows_capacity_up_front() {
+    let before = links_capacity();
+    reserve(10, before + 100);
+    let reserved = links_capacity();
+    assert!(reserved >= count().1 + before + 100);
+
+    let src = signal(0);
+    for _ in 0..100 {
+        effect(move || {
+            src.get();
+        });
+    }
+    assert_eq!(links_capacity(), reserved);
+}
+
+#[test]
+fn test_stats_reports_propagation_stack_depth() {
+    // A wide graph never needs to come back to a sibling branch
+    let src = signal(1);
+    for _ in 0..1000 {
+        let doubled = memo(move || src.get() * 2);
+        effect(move || {
+            doubled.get();
+        });
+    }
+    src.set(2);
+    assert_eq!(stats().max_stack_depth, 0);
+
+    // Every memo of the chain has a second subscriber to come back to after
+    // the rest of the chain
+    let head = signal(1);
+    let mut chain = vec![memo(move || head.get())];
+    for _ in 0..40 {
+        let prev = *chain.last().unwrap();
+        chain.push(memo(move || prev.get() + 1));
+    }
+    let last = *chain.last().unwrap();
+    effect(move || {
+        last.get();
+    });
+    for &node in &chain[..40] {
+        effect(move || {
+            node.get();
+        });
+    }
+    head.set(2);
+    // The write marks the first memo dirty, so checking the chain stops
+    // there rather than coming back from the signal
+    assert_eq!(stats().max_stack_depth, 39);
+}
+
+#[test]
+fn test_shrink_to_fit_after_scope_churn() {
+    let source = signal(0);
+    let scopes: Vec<_> = (0..10_000)
+        .map(|i| {
+            scope(move || {
+                provide_context(i);
+                on_cleanup(|| {});
+                effect(move || {
+                    source.get();
+                });
+            })
+        })
+        .collect();
+    for scope in scopes {
+        scope.dispose();
+    }
+
+    let before = capacity_stats();
+    assert_eq!(before.links.len, 0);
+    assert!(before.links.capacity >= 10_000);
+    assert!(before.contexts.capacity >= 10_000);
+    assert!(shrink_to_fit());
+
+    let after = capacity_stats();
+    assert!(after.links.capacity < before.links.capacity);
+    assert!(after.contexts.capacity < before.contexts.capacity);
+    assert!(after.cleanups.capacity < before.cleanups.capacity);
+    // Live nodes cannot move, so the node map keeps its slots
+    assert_eq!(after.nodes, before.nodes);
+    let report = compact_report();
+    assert_eq!(report.nodes.live, 2);
+    assert!(report.nodes.trailing() >= 20_000);
+
+    start_batch();
+    assert!(!shrink_to_fit());
+    end_batch();
+}
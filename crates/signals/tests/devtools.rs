@@ -0,0 +1,78 @@
+#![cfg(feature = "devtools")]
+
+use futures_util::{FutureExt, StreamExt};
+use samara_signals::*;
+use serde_json::{Value, json};
+
+/// Everything buffered so far, as JSON
+fn drain(events: &mut DevtoolsEvents) -> Vec<Value> {
+    std::iter::from_fn(|| events.next().now_or_never().flatten())
+        .map(|event| serde_json::to_value(event).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_devtools_events_for_signal_and_effect() {
+    let mut events = devtools_events();
+    let a = signal(1);
+    let e = effect(move || {
+        a.get();
+    });
+    a.set(2);
+    e.dispose();
+
+    let key = |node: NodeKey| serde_json::to_value(node).unwrap();
+    let (a, e) = (key(a.node()), key(e.node()));
+    let mut events = drain(&mut events);
+
+    let created = events.remove(0);
+    assert_eq!(created["type"], "NodeCreated");
+    assert_eq!(created["node"], a);
+    assert_eq!(created["kind"], "Signal");
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    assert!(
+        created["location"]
+            .as_str()
+            .unwrap()
+            .contains("devtools.rs")
+    );
+    assert_eq!(events[0]["type"], "NodeCreated");
+    assert_eq!(events[0]["kind"], "Effect");
+
+    // Durations vary, so compare the rest without them
+    for event in &mut events {
+        if event["type"] == "EffectRan" {
+            assert!(event["duration_us"].is_u64());
+            event.as_object_mut().unwrap().remove("duration_us");
+        }
+        if event["type"] == "NodeCreated" {
+            event.as_object_mut().unwrap().remove("location");
+        }
+    }
+    assert_eq!(
+        events,
+        [
+            json!({"type": "NodeCreated", "node": e, "kind": "Effect"}),
+            json!({"type": "LinkCreated", "dep": a, "sub": e}),
+            json!({"type": "EffectRan", "node": e}),
+            json!({"type": "SignalSet", "node": a}),
+            json!({"type": "EffectRan", "node": e}),
+            json!({"type": "LinkRemoved", "dep": a, "sub": e}),
+            json!({"type": "NodeDisposed", "node": e}),
+        ]
+    );
+}
+
+#[test]
+fn test_devtools_events_drop_oldest_when_full() {
+    let a = signal(0);
+    let mut events = devtools_events();
+    for i in 1..=1100 {
+        a.set(i);
+    }
+
+    assert_eq!(events.dropped(), 76);
+    let events = drain(&mut events);
+    assert_eq!(events.len(), 1024);
+    assert_eq!(events[0], json!({"type": "SignalSet", "node": a.node()}));
+}
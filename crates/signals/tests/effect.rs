@@ -381,3 +381,70 @@ fn test_circular_effect_dependencies() {
 
     assert_eq!(c.get(), vec![0, 1, 0]);
 }
+
+#[test]
+fn test_effect_disposing_itself_while_running() {
+    let s = signal(0);
+    let runs = Rc::new(RefCell::new(0));
+    let handle: Rc<RefCell<Option<Effect>>> = Rc::new(RefCell::new(None));
+
+    let e = effect({
+        let runs = runs.clone();
+        let handle = handle.clone();
+        move || {
+            *runs.borrow_mut() += 1;
+            if s.get() == 1 {
+                handle.borrow().unwrap().dispose();
+            }
+        }
+    });
+    *handle.borrow_mut() = Some(e);
+
+    let before = count();
+    s.set(1);
+    assert_eq!(*runs.borrow(), 2);
+    assert_eq!(count().0, before.0 - 1);
+
+    s.set(2);
+    assert_eq!(*runs.borrow(), 2);
+}
+
+#[test]
+fn test_deeply_nested_effects_run_outer_first() {
+    const DEPTH: usize = 50;
+    let signals: Rc<Vec<Signal<i32>>> = Rc::new((0..DEPTH).map(|_| signal(0)).collect());
+    let order = Rc::new(RefCell::new(Vec::new()));
+
+    fn nest(level: usize, signals: Rc<Vec<Signal<i32>>>, order: Rc<RefCell<Vec<usize>>>) {
+        effect(move || {
+            signals[level].get();
+            order.borrow_mut().push(level);
+            if level + 1 < DEPTH {
+                nest(level + 1, signals.clone(), order.clone());
+            }
+        });
+    }
+    nest(0, signals.clone(), order.clone());
+    let all_levels: Vec<usize> = (0..DEPTH).collect();
+    assert_eq!(*order.borrow(), all_levels);
+
+    let mut capacity = None;
+    for round in 1..=100 {
+        order.borrow_mut().clear();
+        start_batch();
+        // Queues the whole chain innermost write first; the outermost
+        // effect must still run first and replace the others
+        signals[DEPTH - 1].set(round);
+        signals[DEPTH / 2].set(round);
+        signals[0].set(round);
+        end_batch();
+        assert_eq!(*order.borrow(), all_levels, "round {round}");
+
+        // Grows on the first flush, then reuses its allocation
+        match capacity {
+            None => capacity = Some(queue_capacity()),
+            Some(capacity) => assert_eq!(queue_capacity(), capacity, "round {round}"),
+        }
+    }
+    assert!(capacity.unwrap() >= DEPTH);
+}
@@ -73,3 +73,458 @@ fn test_scope_track_in_outer_effect() {
     source.set(2);
     assert_eq!(*triggers.borrow(), 1);
 }
+
+#[test]
+fn test_scope_with_returns_signal() {
+    let (nodes_before, _) = count();
+    let runs = Rc::new(RefCell::new(0i32));
+
+    let runs_for_closure = runs.clone();
+    let (s, scope) = scope_with(move || {
+        let s = signal(1i32);
+        effect(move || {
+            s.get();
+            *runs_for_closure.borrow_mut() += 1;
+        });
+        s
+    });
+
+    assert_eq!(count().0 - nodes_before, 3); // scope + signal + effect
+    s.set(2);
+    assert_eq!(*runs.borrow(), 2);
+
+    scope.dispose();
+    assert_eq!(count().0, nodes_before);
+}
+
+#[test]
+fn test_scope_run_in() {
+    let scope = scope(|| {
+        provide_context(7i32);
+    });
+
+    let (nodes_before, _) = count();
+    let _s = scope.run_in(|| {
+        assert_eq!(use_context::<i32>(), Some(7));
+        signal(1i32)
+    });
+    assert_eq!(count().0 - nodes_before, 1);
+
+    // Back at the root, the scope's context is no longer visible
+    assert_eq!(use_context::<i32>(), None);
+
+    scope.dispose();
+    assert_eq!(count().0, nodes_before - 1);
+}
+
+#[test]
+fn test_scope_run_in_restores_on_panic() {
+    let scope = scope(|| {
+        provide_context(7i32);
+    });
+
+    let result = std::panic::catch_unwind(|| {
+        scope.run_in(|| panic!("boom"));
+    });
+    assert!(result.is_err());
+    assert_eq!(use_context::<i32>(), None);
+    scope.dispose();
+}
+
+#[test]
+fn test_scope_run_in_is_not_tracked() {
+    let other = scope(|| {});
+    let a = signal(1i32);
+    let runs = Rc::new(RefCell::new(0i32));
+
+    let runs_for_closure = runs.clone();
+    let _effect = effect(move || {
+        other.run_in(|| a.get());
+        *runs_for_closure.borrow_mut() += 1;
+    });
+    assert_eq!(*runs.borrow(), 1);
+
+    a.set(2);
+    assert_eq!(*runs.borrow(), 1);
+    other.dispose();
+}
+
+#[test]
+#[should_panic(expected = "disposed scope")]
+fn test_scope_run_in_disposed() {
+    let scope = scope(|| {});
+    scope.dispose();
+    scope.run_in(|| {});
+}
+
+#[test]
+fn test_scope_child_disposed_with_parent() {
+    let (nodes_before, _) = count();
+    let runs = Rc::new(RefCell::new(0i32));
+    let source = signal(0i32);
+
+    let parent = scope(|| {});
+    let child = parent.child();
+    let runs_for_closure = runs.clone();
+    let (s, grandchild) = child.child_with(move || {
+        effect(move || {
+            source.get();
+            *runs_for_closure.borrow_mut() += 1;
+        });
+        signal(1i32)
+    });
+    assert_eq!(s.get(), 1);
+
+    // source + parent + child + grandchild + effect + signal
+    assert_eq!(count().0 - nodes_before, 6);
+
+    parent.dispose();
+    assert_eq!(count().0 - nodes_before, 1);
+
+    source.set(1);
+    assert_eq!(*runs.borrow(), 1);
+
+    // Handles of disposed children are inert
+    grandchild.dispose();
+    child.dispose();
+}
+
+#[test]
+fn test_owned_scope_disposes_on_drop() {
+    let baseline = count();
+    let cleaned = Rc::new(RefCell::new(false));
+
+    {
+        let cleaned = cleaned.clone();
+        let _scope = scope_owned(move || {
+            let s = signal(1i32);
+            let _c = memo(move || s.get() * 2);
+            effect(move || {
+                s.get();
+            });
+            on_cleanup(move || *cleaned.borrow_mut() = true);
+        });
+        assert_ne!(count(), baseline);
+    }
+
+    assert!(*cleaned.borrow());
+    assert_eq!(count(), baseline);
+}
+
+#[test]
+fn test_owned_scope_double_dispose() {
+    let baseline = count();
+    let scope = scope_owned(|| {
+        let _s = signal(1i32);
+    });
+    scope.dispose();
+    assert_eq!(count(), baseline);
+    drop(scope);
+    assert_eq!(count(), baseline);
+}
+
+#[test]
+fn test_owned_scope_into_raw() {
+    let baseline = count();
+    let scope = scope_owned(|| {
+        let _s = signal(1i32);
+    })
+    .into_raw();
+    assert_eq!(count().0 - baseline.0, 2);
+    scope.dispose();
+    assert_eq!(count(), baseline);
+}
+
+#[test]
+fn test_scope_adopt_survives_old_parent() {
+    let baseline = count();
+    let source = signal(0i32);
+    let runs = Rc::new(RefCell::new(0i32));
+
+    let runs_for_closure = runs.clone();
+    let (item, old_parent) = scope_with(move || {
+        provide_context(1i32);
+        scope(move || {
+            let local = signal(10i32);
+            effect(move || {
+                source.get();
+                local.get();
+                *runs_for_closure.borrow_mut() += 1;
+            });
+        })
+    });
+    let new_parent = scope(|| provide_context(2i32));
+
+    new_parent.adopt(item);
+    assert_eq!(item.run_in(use_context::<i32>), Some(2));
+
+    old_parent.dispose();
+    // source + new parent + item + local signal + effect
+    assert_eq!(count().0 - baseline.0, 5);
+
+    source.set(1);
+    assert_eq!(*runs.borrow(), 2);
+
+    new_parent.dispose();
+    assert_eq!(count().0 - baseline.0, 1);
+    source.set(2);
+    assert_eq!(*runs.borrow(), 2);
+}
+
+#[test]
+fn test_adopted_effect_survives_old_parent() {
+    let source = signal(0i32);
+    let runs = Rc::new(RefCell::new(0i32));
+
+    let runs_for_closure = runs.clone();
+    let (effect, old_parent) = scope_with(move || {
+        effect(move || {
+            source.get();
+            *runs_for_closure.borrow_mut() += 1;
+        })
+    });
+    let new_parent = scope(|| {});
+
+    new_parent.adopt(Scope::new(effect.node()));
+    old_parent.dispose();
+    source.set(1);
+    assert_eq!(*runs.borrow(), 2);
+
+    new_parent.dispose();
+    source.set(2);
+    assert_eq!(*runs.borrow(), 2);
+}
+
+#[test]
+#[should_panic(expected = "own descendant")]
+fn test_scope_adopt_rejects_cycles() {
+    let (inner, outer) = scope_with(|| scope(|| {}));
+    inner.adopt(outer);
+}
+
+#[test]
+fn test_scope_names() {
+    let sidebar = scope_named("sidebar", || {});
+    let item = scope(|| {});
+    assert_eq!(item.name(), None);
+    item.set_name("item 3");
+
+    assert_eq!(sidebar.name().as_deref(), Some("sidebar"));
+    assert_eq!(item.name().as_deref(), Some("item 3"));
+
+    let snapshot = serialize();
+    assert!(snapshot.contains("\"name\":\"sidebar\""), "{snapshot}");
+    assert!(snapshot.contains("\"name\":\"item 3\""), "{snapshot}");
+
+    let e = effect(|| {});
+    e.set_name("logger");
+    assert_eq!(e.name().as_deref(), Some("logger"));
+
+    sidebar.dispose();
+    assert_eq!(sidebar.name(), None);
+}
+
+#[test]
+fn test_scope_named_is_not_tracked() {
+    let a = signal(1i32);
+    let runs = Rc::new(RefCell::new(0i32));
+
+    let runs_for_closure = runs.clone();
+    let _effect = effect(move || {
+        scope_named("item", move || {
+            a.get();
+        });
+        *runs_for_closure.borrow_mut() += 1;
+    });
+
+    a.set(2);
+    assert_eq!(*runs.borrow(), 1);
+}
+
+#[test]
+fn test_scope_names_in_panics() {
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+
+    let err = catch_unwind(AssertUnwindSafe(|| {
+        scope_named("sidebar", || {
+            scope_named("item 3", || {
+                let s = signal(0);
+                let _read = s.read();
+                s.set(1);
+            });
+        });
+    }))
+    .unwrap_err();
+    let message = err.downcast_ref::<String>().unwrap();
+    assert!(message.contains("\"sidebar\" > \"item 3\""), "{message}");
+
+    let err = catch_unwind(AssertUnwindSafe(|| {
+        scope_named("sidebar", || {
+            expect_context::<u8>();
+        });
+    }))
+    .unwrap_err();
+    let message = err.downcast_ref::<String>().unwrap();
+    assert!(message.contains("u8"), "{message}");
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    assert!(
+        message.contains("scope \"sidebar\" created at"),
+        "{message}"
+    );
+}
+
+#[test]
+fn test_with_root_provides_to_later_siblings() {
+    let outer = scope(|| {
+        scope(|| {
+            with_root(|| provide_context(String::from("global")));
+            assert_eq!(use_context::<String>().as_deref(), Some("global"));
+        });
+    });
+    outer.dispose();
+
+    let sibling = scope(|| {});
+    assert_eq!(
+        sibling.run_in(use_context::<String>).as_deref(),
+        Some("global")
+    );
+    assert_eq!(root_scope().node(), sibling.run_in(|| root_scope().node()));
+}
+
+#[test]
+fn test_with_root_nodes_survive_until_cleanup() {
+    let source = signal(0);
+    let runs = Rc::new(RefCell::new(0));
+
+    let runs_in_effect = runs.clone();
+    let (read_in_root, outer) = scope_with(move || {
+        effect(move || {
+            // Not tracked by the outer effect
+            with_root(|| source.get());
+        });
+        with_root(move || {
+            effect(move || {
+                source.get();
+                *runs_in_effect.borrow_mut() += 1;
+            });
+            source
+        })
+    });
+    let root_children = root_scope().children().len();
+    outer.dispose();
+    assert_eq!(root_scope().children().len(), root_children - 1);
+
+    read_in_root.set(1);
+    assert_eq!(*runs.borrow(), 2);
+
+    cleanup();
+    assert_eq!(count(), (1, 0));
+}
+
+#[test]
+fn test_owner_with_creates_under_captured_scope() {
+    let baseline = count();
+    let (owner, captured) = scope_with(|| {
+        provide_context(7i32);
+        Owner::current()
+    });
+    assert!(owner.is_alive());
+
+    // From the root, after the scope's closure has returned
+    let (s, seen) = owner.with(|| (signal(1), use_context::<i32>()));
+    assert_eq!(seen, Some(7));
+    assert_eq!(captured.descendant_count(), 1);
+    assert_eq!(s.get(), 1);
+
+    captured.dispose();
+    assert!(!owner.is_alive());
+    assert_eq!(count(), baseline);
+}
+
+#[test]
+fn test_owner_with_is_untracked() {
+    let source = signal(0);
+    let runs = Rc::new(RefCell::new(0));
+    let owner = Owner::current();
+
+    let runs_in_effect = runs.clone();
+    effect(move || {
+        owner.with(|| source.get());
+        *runs_in_effect.borrow_mut() += 1;
+    });
+    source.set(1);
+    assert_eq!(*runs.borrow(), 1);
+}
+
+#[test]
+#[should_panic(expected = "disposed owner")]
+fn test_owner_with_disposed() {
+    let (owner, scope) = scope_with(Owner::current);
+    scope.dispose();
+    owner.with(|| {});
+}
+
+#[test]
+fn test_stale_signal_names_its_disposal() {
+    #[cfg(debug_assertions)]
+    let (scope_line, signal_line) = (line!() + 2, line!() + 4);
+    let created = Rc::new(RefCell::new(None));
+    let owner = scope_named("form", {
+        let created = created.clone();
+        move || *created.borrow_mut() = Some(signal(0))
+    });
+    let stale = created.borrow().unwrap();
+    #[cfg(debug_assertions)]
+    let disposed_line = line!() + 1;
+    owner.dispose();
+
+    let err = std::panic::catch_unwind(move || stale.get()).unwrap_err();
+    let message = match err.downcast_ref::<String>() {
+        Some(message) => message.as_str(),
+        None => err.downcast_ref::<&str>().unwrap(),
+    };
+    assert!(message.starts_with("Signal accessed after cleanup"));
+    #[cfg(debug_assertions)]
+    {
+        assert!(message.contains(&format!("Signal created at {}:{signal_line}:", file!())));
+        assert!(message.contains(&format!(
+            "disposed with scope \"form\" created at {}:{scope_line}:",
+            file!()
+        )));
+        assert!(message.contains(&format!("dispose called at {}:{disposed_line}:", file!())));
+    }
+}
+
+#[test]
+fn test_unowned_signal_survives_effect_rerun() {
+    let trigger = signal(0);
+    let created = Rc::new(RefCell::new(Vec::new()));
+    let seen = Rc::new(RefCell::new(Vec::new()));
+
+    let created_in_effect = created.clone();
+    effect(move || {
+        trigger.get();
+        let owned = signal(0);
+        let detached = unowned(|| signal(0));
+        created_in_effect.borrow_mut().push((owned, detached));
+    });
+
+    let (first_owned, first_detached) = created.borrow()[0];
+    let seen_in_effect = seen.clone();
+    effect(move || seen_in_effect.borrow_mut().push(first_detached.get()));
+
+    let before = count();
+    trigger.set(1);
+    // The owned signal was replaced, the unowned one was kept alongside a new one
+    assert_eq!(count().0, before.0 + 1);
+
+    first_detached.set(5);
+    assert_eq!(*seen.borrow(), vec![0, 5]);
+
+    let result = std::panic::catch_unwind(move || first_owned.get());
+    assert!(result.is_err());
+
+    first_detached.dispose();
+    first_detached.dispose();
+    assert_eq!(count().0, before.0);
+}
@@ -0,0 +1,121 @@
+#![cfg(feature = "compat-futures-signals")]
+
+use futures_signals::signal::Mutable;
+use samara_signals::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[test]
+fn test_from_mutable_follows_both_sides() {
+    let mutable = Mutable::new(1);
+    let value = from_mutable(mutable.clone());
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let seen_by_effect = seen.clone();
+    effect(move || seen_by_effect.borrow_mut().push(value.get()));
+
+    // A burst of changes is written once
+    mutable.set(2);
+    mutable.set(3);
+    tick(10);
+    assert_eq!(*seen.borrow(), vec![1, 3]);
+
+    value.set(4);
+    assert_eq!(mutable.get(), 4);
+    tick(10);
+    // The value forwarded to the mutable is not written back
+    assert_eq!(*seen.borrow(), vec![1, 3, 4]);
+}
+
+#[test]
+fn test_to_mutable_follows_both_sides() {
+    let value = signal(String::from("a"));
+    let mutable = to_mutable(value);
+    assert_eq!(mutable.get_cloned(), "a");
+
+    value.set(String::from("b"));
+    assert_eq!(mutable.get_cloned(), "b");
+
+    mutable.set(String::from("c"));
+    tick(10);
+    assert_eq!(value.get(), "c");
+}
+
+#[test]
+fn test_mutable_ping_pong_settles() {
+    let mutable = Mutable::new(0);
+    let value = from_mutable(mutable.clone());
+    let runs = Rc::new(RefCell::new(0));
+    let runs_by_effect = runs.clone();
+    effect(move || {
+        value.get();
+        *runs_by_effect.borrow_mut() += 1;
+    });
+
+    for i in 1..=50 {
+        if i % 2 == 0 {
+            value.set(i);
+        } else {
+            mutable.set(i);
+        }
+        tick(1);
+    }
+    tick(10);
+    assert_eq!((value.get(), mutable.get()), (50, 50));
+    // Each write reaches the signal once, and nothing bounces back
+    assert_eq!(*runs.borrow(), 51);
+
+    // Without the executor running in between, only the signal's writes
+    // reach its readers, and the mutable's latest value matches the last
+    for i in 51..=100 {
+        if i % 2 == 0 {
+            value.set(i);
+        } else {
+            mutable.set(i);
+        }
+    }
+    tick(10);
+    assert_eq!((value.get(), mutable.get()), (100, 100));
+    assert_eq!(*runs.borrow(), 76);
+
+    tick(100);
+    assert_eq!(*runs.borrow(), 76);
+}
+
+#[test]
+fn test_mutable_bridges_stop_with_their_scope() {
+    let mutable = Mutable::new(0);
+    let source = signal(0);
+    let (forwarded, owner) = scope_with({
+        let mutable = mutable.clone();
+        move || {
+            from_mutable(mutable);
+            to_mutable(source)
+        }
+    });
+    tick(10);
+    assert_eq!(pending_tasks(), 2);
+
+    owner.dispose();
+    tick(10);
+    assert_eq!(pending_tasks(), 0);
+
+    // Neither side is forwarded anymore
+    source.set(1);
+    assert_eq!(forwarded.get(), 0);
+    forwarded.set(2);
+    tick(10);
+    assert_eq!(source.get(), 1);
+}
+
+#[test]
+fn test_to_mutable_task_stops_with_the_signal() {
+    let (value, owner) = scope_with(|| signal(0));
+    let mutable = to_mutable(value);
+    tick(10);
+    assert_eq!(pending_tasks(), 1);
+
+    owner.dispose();
+    mutable.set(1);
+    tick(10);
+    assert_eq!(pending_tasks(), 0);
+}
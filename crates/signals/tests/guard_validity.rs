@@ -5,16 +5,8 @@ use std::rc::Rc;
 #[test]
 #[should_panic]
 fn test_read_guard_after_cleanup() {
-    let s = Rc::new(RefCell::new(None));
+    let (s, scope) = scope_with(|| signal(42i32));
 
-    let scope = scope({
-        let s = s.clone();
-        move || {
-            *s.borrow_mut() = Some(signal(42i32));
-        }
-    });
-
-    let s = s.borrow().unwrap();
     let g = s.read();
 
     scope.dispose(); // Cleanup scope and its signals
@@ -66,14 +58,7 @@ fn test_multiple_guards_same_signal() {
 
 #[test]
 fn test_guard_valid_outlives_scope() {
-    let s = Rc::new(RefCell::new(None));
-
-    let s_clone = s.clone();
-    let _ = scope(move || {
-        let signal = signal(1i32);
-        *s_clone.borrow_mut() = Some(signal);
-    });
+    let (signal, _) = scope_with(|| signal(1i32));
 
-    let signal = s.borrow().unwrap();
     assert_eq!(*signal.read() + 1, 2);
 }
@@ -0,0 +1,76 @@
+use samara_signals::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[test]
+fn test_undo_and_redo_restore_both_signals_at_once() {
+    let a = signal(0);
+    let b = signal("x");
+    let edits = history().track(a).track(b).build();
+
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let seen_by_effect = seen.clone();
+    effect(move || seen_by_effect.borrow_mut().push((a.get(), b.get())));
+
+    a.set(1);
+    b.set("y");
+    a.set(2);
+    start_batch();
+    a.set(3);
+    b.set("z");
+    end_batch();
+    assert_eq!(seen.borrow().len(), 5);
+
+    assert!(edits.undo());
+    assert_eq!((a.get(), b.get()), (2, "y"));
+    assert!(edits.undo());
+    assert_eq!((a.get(), b.get()), (1, "y"));
+    assert!(edits.redo());
+    assert_eq!((a.get(), b.get()), (2, "y"));
+    // One run per undo and redo, each seeing a coherent state
+    assert_eq!(seen.borrow()[5..], [(2, "y"), (1, "y"), (2, "y")]);
+
+    // An edit after undoing drops what could be redone
+    assert!(edits.can_redo().get());
+    b.set("w");
+    assert!(!edits.can_redo().get());
+    assert!(!edits.redo());
+    assert!(edits.undo());
+    assert_eq!((a.get(), b.get()), (2, "y"));
+}
+
+#[test]
+fn test_depth_limits_the_undo_steps() {
+    let value = signal(0);
+    let edits = history().track(value).depth(2).build();
+    assert!(!edits.can_undo().get());
+
+    for next in 1..=5 {
+        value.set(next);
+    }
+    assert!(edits.can_undo().get());
+    assert!(edits.undo());
+    assert!(edits.undo());
+    assert_eq!(value.get(), 3);
+    assert!(!edits.can_undo().get());
+    assert!(!edits.undo());
+
+    edits.redo();
+    edits.clear();
+    assert_eq!(value.get(), 4);
+    assert!(!edits.can_undo().get());
+    assert!(!edits.can_redo().get());
+}
+
+#[test]
+fn test_history_is_disposed_with_its_scope() {
+    let before = count();
+    let owner = scope(|| {
+        let value = signal(0);
+        let edits = history().track(value).build();
+        value.set(1);
+        edits.undo();
+    });
+    owner.dispose();
+    assert_eq!(count(), before);
+}
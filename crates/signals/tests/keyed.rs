@@ -0,0 +1,101 @@
+use samara_signals::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Item {
+    id: u32,
+    label: &'static str,
+}
+
+fn item(id: u32, label: &'static str) -> Item {
+    Item { id, label }
+}
+
+/// The mapped label memos, with the ids of the rows created and cleaned up
+type Rows = (
+    Computed<Vec<Computed<&'static str>>>,
+    Rc<RefCell<Vec<u32>>>,
+    Rc<RefCell<Vec<u32>>>,
+);
+
+/// Maps items to memos of their labels, recording created and cleaned up ids
+fn rows(list: Signal<Vec<Item>>) -> Rows {
+    let created = Rc::new(RefCell::new(Vec::new()));
+    let cleaned = Rc::new(RefCell::new(Vec::new()));
+    let mapped = map_keyed(move || list.get(), |item| item.id, {
+        let created = created.clone();
+        let cleaned = cleaned.clone();
+        move |item: Signal<Item>| {
+            let id = item.get_untracked().id;
+            created.borrow_mut().push(id);
+            let cleaned = cleaned.clone();
+            on_cleanup(move || cleaned.borrow_mut().push(id));
+            memo(move || item.get().label)
+        }
+    });
+    (mapped, created, cleaned)
+}
+
+fn labels(mapped: Computed<Vec<Computed<&'static str>>>) -> Vec<&'static str> {
+    mapped.get().iter().map(|label| label.get()).collect()
+}
+
+#[test]
+fn test_reorder_reuses_every_row() {
+    let list = signal((1..=5).map(|id| item(id, "x")).collect::<Vec<_>>());
+    let (mapped, created, cleaned) = rows(list);
+    assert_eq!(*created.borrow(), vec![1, 2, 3, 4, 5]);
+    labels(mapped);
+
+    let before = count();
+    list.set(vec![
+        item(5, "e"),
+        item(3, "c"),
+        item(1, "a"),
+        item(4, "d"),
+        item(2, "b"),
+    ]);
+    assert_eq!(labels(mapped), vec!["e", "c", "a", "d", "b"]);
+    assert_eq!(created.borrow().len(), 5);
+    assert!(cleaned.borrow().is_empty());
+    assert_eq!(count(), before);
+}
+
+#[test]
+fn test_remove_disposes_only_that_row() {
+    let list = signal((1..=5).map(|id| item(id, "x")).collect::<Vec<_>>());
+    let (mapped, created, cleaned) = rows(list);
+    let first = mapped.get();
+
+    list.update(|items| {
+        items.remove(2);
+    });
+    assert_eq!(*cleaned.borrow(), vec![3]);
+    assert_eq!(created.borrow().len(), 5);
+
+    // The retained rows keep their outputs
+    let second = mapped.get();
+    assert_eq!(second.len(), 4);
+    for (kept, old) in second.iter().zip([0, 1, 3, 4]) {
+        assert_eq!(kept.node(), first[old].node());
+    }
+
+    list.update(|items| items.push(item(6, "f")));
+    assert_eq!(*created.borrow(), vec![1, 2, 3, 4, 5, 6]);
+    assert_eq!(labels(mapped), vec!["x", "x", "x", "x", "f"]);
+}
+
+#[test]
+fn test_rows_are_disposed_with_the_owning_scope() {
+    let before = count();
+    let (cleaned, owner) = scope_with(|| {
+        let list = signal(vec![item(1, "a"), item(2, "b")]);
+        let (mapped, _, cleaned) = rows(list);
+        labels(mapped);
+        cleaned
+    });
+    owner.dispose();
+    assert_eq!(*cleaned.borrow(), vec![2, 1]);
+    assert_eq!(count(), before);
+}
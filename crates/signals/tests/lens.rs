@@ -0,0 +1,70 @@
+mod common;
+
+use common::record;
+use samara_signals::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Clone, Debug, PartialEq)]
+struct User {
+    name: String,
+    age: u32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct State {
+    user: User,
+    visits: u32,
+}
+
+fn state() -> Signal<State> {
+    signal(State {
+        user: User {
+            name: String::from("ada"),
+            age: 36,
+        },
+        visits: 0,
+    })
+}
+
+#[test]
+fn test_sibling_lenses_do_not_rerun_each_other() {
+    let state = state();
+    let user = state.lens(|s| &s.user, |s| &mut s.user);
+    let name = user.lens(|u| &u.name, |u| &mut u.name);
+    let age = user.compose(|u| &u.age, |u| &mut u.age);
+
+    let names = record(name.read());
+    let ages = record(age.read());
+
+    age.set(37);
+    age.update(|age| *age += 1);
+    assert_eq!(*names.borrow(), vec!["ada"]);
+    assert_eq!(*ages.borrow(), vec![36, 37, 38]);
+
+    name.set(String::from("grace"));
+    assert_eq!(*names.borrow(), vec!["ada", "grace"]);
+    assert_eq!(*ages.borrow(), vec![36, 37, 38]);
+}
+
+#[test]
+fn test_writes_reach_root_subscribers() {
+    let state = state();
+    let name = state
+        .lens(|s| &s.user, |s| &mut s.user)
+        .lens(|u| &u.name, |u| &mut u.name);
+    let visits = state.lens(|s| &s.visits, |s| &mut s.visits);
+
+    let roots = Rc::new(RefCell::new(Vec::new()));
+    let roots_by_effect = roots.clone();
+    effect(move || roots_by_effect.borrow_mut().push(state.get()));
+
+    name.update(|name| name.push('!'));
+    visits.set(1);
+
+    let roots = roots.borrow();
+    assert_eq!(roots.len(), 3);
+    assert_eq!(roots[1].user.name, "ada!");
+    assert_eq!(roots[2].visits, 1);
+    assert_eq!(name.root().get_untracked(), roots[2]);
+}
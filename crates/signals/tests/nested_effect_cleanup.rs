@@ -97,3 +97,59 @@ fn test_nested_scope_cleanup() {
     // Verify it only ran once
     assert_eq!(*outer_runs.borrow(), 1);
 }
+
+#[test]
+fn test_validate_survives_churn() {
+    let sources: Vec<_> = (0..4).map(signal).collect();
+    let scopes = Rc::new(RefCell::new(Vec::new()));
+    // A fixed linear congruential sequence keeps the churn reproducible
+    let mut seed = 7u32;
+    let mut next = move |bound: usize| {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        (seed >> 16) as usize % bound
+    };
+
+    for step in 0..200 {
+        match next(4) {
+            0 => {
+                let (a, b) = (sources[next(4)], sources[next(4)]);
+                let depth = next(3);
+                let owner = scope(move || {
+                    effect(move || {
+                        let value = a.get();
+                        for _ in 0..depth {
+                            let inner = effect(move || {
+                                b.get();
+                            });
+                            if value % 2 == 0 {
+                                inner.dispose();
+                            }
+                        }
+                        memo(move || a.get() + b.get()).get();
+                    });
+                });
+                scopes.borrow_mut().push(owner);
+            }
+            1 => {
+                let len = scopes.borrow().len();
+                if len > 0 {
+                    let owner = scopes.borrow_mut().swap_remove(next(len));
+                    owner.dispose();
+                }
+            }
+            _ => {
+                let source = sources[next(4)];
+                source.set(source.get() + 1);
+            }
+        }
+        if let Err(violations) = validate() {
+            panic!("step {step}: {violations:?}");
+        }
+    }
+
+    for owner in scopes.take() {
+        owner.dispose();
+    }
+    assert_eq!(validate(), Ok(()));
+    assert_eq!(stats().effects, 0);
+}
@@ -0,0 +1,116 @@
+use samara_signals::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    Created(NodeKey, NodeKind),
+    Disposed(NodeKey),
+    Linked(NodeKey, NodeKey),
+    Unlinked(NodeKey, NodeKey),
+    Ran(NodeKey),
+}
+
+#[derive(Default)]
+struct Recorder(RefCell<Vec<Event>>);
+
+impl Recorder {
+    fn take(&self) -> Vec<Event> {
+        self.0.take()
+    }
+}
+
+impl RuntimeObserver for Recorder {
+    fn node_created(
+        &self,
+        node: NodeKey,
+        kind: NodeKind,
+        _: Option<&'static std::panic::Location<'static>>,
+    ) {
+        self.0.borrow_mut().push(Event::Created(node, kind));
+    }
+
+    fn node_disposed(&self, node: NodeKey) {
+        self.0.borrow_mut().push(Event::Disposed(node));
+    }
+
+    fn link_created(&self, dep: NodeKey, sub: NodeKey) {
+        self.0.borrow_mut().push(Event::Linked(dep, sub));
+    }
+
+    fn link_removed(&self, dep: NodeKey, sub: NodeKey) {
+        self.0.borrow_mut().push(Event::Unlinked(dep, sub));
+    }
+
+    fn effect_ran(&self, effect: NodeKey, _: Duration) {
+        self.0.borrow_mut().push(Event::Ran(effect));
+    }
+}
+
+#[test]
+fn test_observer_event_sequence() {
+    let recorder = Rc::new(Recorder::default());
+    set_observer(recorder.clone());
+
+    let s = signal(1);
+    let doubled = memo(move || s.get() * 2);
+    let e = effect(move || {
+        doubled.get();
+    });
+
+    let events = recorder.take();
+    let key = |index: usize| match events[index] {
+        Event::Created(key, _) => key,
+        _ => panic!("{events:?}"),
+    };
+    let (s_key, memo_key, effect_key) = (key(0), key(1), key(2));
+    assert_eq!(
+        events,
+        [
+            Event::Created(s_key, NodeKind::Signal),
+            Event::Created(memo_key, NodeKind::Computed),
+            Event::Created(effect_key, NodeKind::Effect),
+            Event::Linked(s_key, memo_key),
+            Event::Linked(memo_key, effect_key),
+            Event::Ran(effect_key),
+        ]
+    );
+
+    s.set(2);
+    assert_eq!(recorder.take(), [Event::Ran(effect_key)]);
+
+    e.dispose();
+    assert_eq!(
+        recorder.take(),
+        [
+            Event::Unlinked(memo_key, effect_key),
+            Event::Unlinked(s_key, memo_key),
+            Event::Disposed(effect_key),
+        ]
+    );
+
+    remove_observer();
+    let _ = signal(0);
+    assert!(recorder.take().is_empty());
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "RuntimeObserver callbacks must not use the reactive system")]
+fn test_observer_must_not_reenter() {
+    struct Reentrant(Signal<i32>);
+
+    impl RuntimeObserver for Reentrant {
+        fn link_created(&self, _: NodeKey, _: NodeKey) {
+            self.0.get();
+        }
+    }
+
+    let counter = signal(0);
+    set_observer(Rc::new(Reentrant(counter)));
+    let s = signal(1);
+    effect(move || {
+        s.get();
+    });
+}
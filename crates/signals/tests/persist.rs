@@ -0,0 +1,68 @@
+use samara_signals::*;
+
+#[tokio::test]
+async fn test_persisted_signal_is_restored() {
+    let backend = MemoryBackend::new();
+    let owner = scope({
+        let backend = backend.clone();
+        move || {
+            provide_persistence(backend);
+            let volume = persisted_signal("volume", 5u32);
+            assert_eq!(volume.get(), 5);
+            volume.set(7);
+            volume.set(8);
+        }
+    });
+    join().await;
+    assert_eq!(backend.load("volume").unwrap(), b"8");
+    owner.dispose();
+
+    let (volume, _owner) = scope_with(move || {
+        provide_persistence(backend);
+        persisted_signal("volume", 5u32)
+    });
+    assert_eq!(volume.get(), 8);
+}
+
+#[test]
+fn test_pending_write_is_done_on_dispose() {
+    let backend = MemoryBackend::new();
+    backend.store("name", b"not json");
+    let owner = scope({
+        let backend = backend.clone();
+        move || {
+            provide_persistence(backend);
+            let name = persisted_signal("name", String::from("default"));
+            assert_eq!(name.get(), "default");
+            name.set(String::from("ada"));
+        }
+    });
+    assert_eq!(backend.load("name").unwrap(), b"not json");
+
+    owner.dispose();
+    assert_eq!(backend.load("name").unwrap(), br#""ada""#);
+}
+
+#[cfg(feature = "persist-json-file")]
+#[test]
+fn test_json_file_backend_survives_reopening() {
+    let path = std::env::temp_dir().join(format!("samara-persist-{}.json", std::process::id()));
+    let owner = scope({
+        let path = path.clone();
+        move || {
+            provide_persistence(JsonFileBackend::new(path));
+            persisted_signal("size", (1, 2)).set((3, 4));
+        }
+    });
+    owner.dispose();
+
+    let (size, _owner) = scope_with({
+        let path = path.clone();
+        move || {
+            provide_persistence(JsonFileBackend::new(path));
+            persisted_signal("size", (1, 2))
+        }
+    });
+    assert_eq!(size.get(), (3, 4));
+    std::fs::remove_file(path).unwrap();
+}
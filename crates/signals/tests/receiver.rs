@@ -0,0 +1,56 @@
+use samara_signals::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[test]
+fn test_tick_applies_the_latest_message() {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let latest = signal_from_receiver(rx);
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let seen_by_effect = seen.clone();
+    effect(move || seen_by_effect.borrow_mut().push(latest.get()));
+
+    std::thread::spawn(move || {
+        for message in ["a", "b", "c"] {
+            tx.send(message).unwrap();
+        }
+    })
+    .join()
+    .unwrap();
+    assert_eq!(latest.get(), None);
+
+    tick(1);
+    assert_eq!(latest.get(), Some("c"));
+    assert_eq!(*seen.borrow(), vec![None, Some("c")]);
+
+    // Nothing new, nothing written
+    tick(1);
+    assert_eq!(seen.borrow().len(), 2);
+}
+
+#[tokio::test]
+async fn test_join_appends_every_message() {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let messages = signal_vec_from_receiver(rx);
+    let deltas = Rc::new(RefCell::new(0));
+    let deltas_by_subscriber = deltas.clone();
+    messages.subscribe_deltas(move |_| *deltas_by_subscriber.borrow_mut() += 1);
+
+    for message in 1..=3 {
+        tx.send(message).unwrap();
+    }
+    join().await;
+    assert_eq!(messages.with(|messages| messages.to_vec()), vec![1, 2, 3]);
+    assert_eq!(*deltas.borrow(), 1);
+}
+
+#[test]
+fn test_receiver_is_dropped_with_its_scope() {
+    let (tx, rx) = std::sync::mpsc::channel::<u32>();
+    let owner = scope(move || {
+        signal_from_receiver(rx);
+    });
+    owner.dispose();
+    tick(1);
+    assert!(tx.send(1).is_err());
+}
@@ -124,3 +124,49 @@ fn test_scoped_multiple_calls() {
     s2.dispose();
     s3.dispose();
 }
+
+#[test]
+fn test_scoped_reusable_does_not_grow() {
+    let mut render = scoped_reusable(|n: i32| {
+        let s = signal(n);
+        s.get()
+    });
+
+    assert_eq!(render(1), 1);
+    let after_first = count();
+    assert_eq!(render(2), 2);
+    assert_eq!(render(3), 3);
+    assert_eq!(count(), after_first);
+}
+
+#[test]
+fn test_scoped_reusable_runs_previous_cleanups_first() {
+    use std::{cell::RefCell, rc::Rc};
+
+    let log = Rc::new(RefCell::new(Vec::new()));
+
+    let log_in_render = log.clone();
+    let mut render = scoped_reusable(move |n: i32| {
+        log_in_render.borrow_mut().push(format!("run {n}"));
+        let log_in_cleanup = log_in_render.clone();
+        on_cleanup(move || log_in_cleanup.borrow_mut().push(format!("cleanup {n}")));
+    });
+
+    render(1);
+    render(2);
+    render(3);
+    assert_eq!(
+        *log.borrow(),
+        vec!["run 1", "cleanup 1", "run 2", "cleanup 2", "run 3"]
+    );
+}
+
+#[test]
+fn test_scoped_reusable_keeps_contexts() {
+    let mut render = scoped_reusable(|n: i32| use_context_or_provide(|| n));
+
+    assert_eq!(render(1), 1);
+    // The same scope is reused, so the value provided by the first call is
+    // found again instead of the new default
+    assert_eq!(render(2), 1);
+}
@@ -132,3 +132,84 @@ fn test_multiple_modifications_same_signal() {
         signal.update(|v| *v += 1);
     });
 }
+
+#[test]
+fn test_unobserved_set_drops_old_value_and_marks_dirty() {
+    struct Tracked(i32, Rc<RefCell<Vec<i32>>>);
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            self.1.borrow_mut().push(self.0);
+        }
+    }
+
+    let dropped = Rc::new(RefCell::new(Vec::new()));
+    let s = signal(Tracked(0, dropped.clone()));
+    s.set(Tracked(1, dropped.clone()));
+    s.set(Tracked(2, dropped.clone()));
+    assert_eq!(*dropped.borrow(), vec![0, 1]);
+
+    // A subscriber showing up later sees the latest value and later writes
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let seen_by_effect = seen.clone();
+    effect(move || seen_by_effect.borrow_mut().push(s.read().0));
+    s.set(Tracked(3, dropped.clone()));
+    assert_eq!(*seen.borrow(), vec![2, 3]);
+    assert_eq!(*dropped.borrow(), vec![0, 1, 2]);
+}
+
+#[test]
+fn test_read_signal_inside_with() {
+    let a = signal(1);
+    let b = signal(vec![10, 20]);
+
+    // Both signals are tracked, and nesting `with` on the same signal is fine
+    let sums = Rc::new(RefCell::new(Vec::new()));
+    let sums_by_effect = sums.clone();
+    effect(move || {
+        let sum = b.with(|values| {
+            values.iter().sum::<i32>() + a.get() + b.with(|values| values.len() as i32)
+        });
+        sums_by_effect.borrow_mut().push(sum);
+    });
+    assert_eq!(*sums.borrow(), vec![33]);
+
+    a.set(2);
+    b.update(|b| b.push(30));
+    assert_eq!(*sums.borrow(), vec![33, 34, 65]);
+
+    let doubled = memo(move || a.with(|a| b.with(|values| values[0] * a * 2)));
+    assert_eq!(doubled.get(), 40);
+    a.set(3);
+    assert_eq!(doubled.get(), 60);
+}
+
+#[test]
+fn test_at_only_notifies_for_its_index() {
+    let items = signal(vec![1, 2, 3]);
+    let seen: Vec<_> = (0..3)
+        .map(|index| {
+            let element = items.at(index);
+            let seen = Rc::new(RefCell::new(Vec::new()));
+            let seen_by_effect = seen.clone();
+            effect(move || seen_by_effect.borrow_mut().push(element.get()));
+            seen
+        })
+        .collect();
+
+    items.set_at(1, 20);
+    assert_eq!(*seen[0].borrow(), vec![Some(1)]);
+    assert_eq!(*seen[1].borrow(), vec![Some(2), Some(20)]);
+    assert_eq!(*seen[2].borrow(), vec![Some(3)]);
+
+    // Shrinking past a watched index and growing back moves it through None
+    items.set(vec![1, 20]);
+    items.update(|items| items.push(30));
+    assert_eq!(*seen[0].borrow(), vec![Some(1)]);
+    assert_eq!(*seen[1].borrow(), vec![Some(2), Some(20)]);
+    assert_eq!(*seen[2].borrow(), vec![Some(3), None, Some(30)]);
+
+    let fourth = items.at(3);
+    assert_eq!(fourth.get(), None);
+    items.update(|items| items.push(40));
+    assert_eq!(fourth.get(), Some(40));
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use common::record;
+use samara_signals::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[test]
+fn test_insert_reruns_only_the_range_it_falls_in() {
+    let samples = signal_btree_map::<u32, &str>();
+    let low = record(samples.range_signal(0..10));
+    let high = record(samples.range_signal(10..20));
+
+    samples.insert(3, "a");
+    samples.insert(15, "b");
+    samples.insert(25, "c");
+    samples.insert(3, "A");
+    assert_eq!(*low.borrow(), vec![vec![], vec![(3, "a")], vec![(3, "A")]]);
+    assert_eq!(*high.borrow(), vec![vec![], vec![(15, "b")]]);
+
+    samples.remove(&15);
+    samples.remove(&16);
+    assert_eq!(low.borrow().len(), 3);
+    assert_eq!(*high.borrow(), vec![vec![], vec![(15, "b")], vec![]]);
+}
+
+#[test]
+fn test_first_and_last_only_follow_their_ends() {
+    let samples = signal_btree_map::<u32, u32>();
+    let first = record(samples.first_signal());
+    let last = record(samples.last_signal());
+
+    samples.insert(5, 50);
+    samples.insert(1, 10);
+    samples.insert(9, 90);
+    samples.insert(3, 30);
+    samples.remove(&9);
+    assert_eq!(*first.borrow(), vec![None, Some((5, 50)), Some((1, 10))]);
+    assert_eq!(
+        *last.borrow(),
+        vec![None, Some((5, 50)), Some((9, 90)), Some((5, 50))]
+    );
+}
+
+#[test]
+fn test_subscriptions_are_disposed_with_the_map() {
+    let before = count();
+    let (samples, owner) = scope_with(signal_btree_map::<u32, u32>);
+    let window = scope({
+        let samples = samples.clone();
+        move || {
+            samples.range_signal(0..5);
+        }
+    });
+    window.dispose();
+    samples.insert(1, 1);
+    owner.dispose();
+    assert_eq!(count(), before);
+}
+
+#[test]
+fn test_subscribe_deltas_reports_inserts_and_removals() {
+    let map = signal_btree_map();
+    let received = Rc::new(RefCell::new(Vec::new()));
+    let received_by_subscriber = received.clone();
+    map.subscribe_deltas(move |deltas| {
+        received_by_subscriber
+            .borrow_mut()
+            .extend_from_slice(deltas)
+    });
+
+    map.insert(2, "b");
+    map.remove(&2);
+    map.remove(&3);
+    assert_eq!(
+        *received.borrow(),
+        vec![
+            MapDelta::Insert { key: 2, value: "b" },
+            MapDelta::Remove { key: 2 }
+        ]
+    );
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use common::record;
+use samara_signals::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[test]
+fn test_writes_to_one_key_do_not_rerun_another() {
+    let map = signal_map();
+    map.insert("a", 1);
+    map.insert("b", 2);
+
+    let a = record(map.get_signal(&"a"));
+    let b = record(map.get_signal(&"b"));
+
+    map.insert("a", 10);
+    map.insert("a", 11);
+    map.remove(&"a");
+    assert_eq!(*a.borrow(), vec![Some(1), Some(10), Some(11), None]);
+    assert_eq!(*b.borrow(), vec![Some(2)]);
+
+    map.insert("b", 20);
+    map.insert("c", 3);
+    assert_eq!(*a.borrow(), vec![Some(1), Some(10), Some(11), None]);
+    assert_eq!(*b.borrow(), vec![Some(2), Some(20)]);
+}
+
+#[test]
+fn test_get_signal_is_cached_per_key() {
+    let map = signal_map::<i32, i32>();
+    assert_eq!(map.get_signal(&1).node(), map.get_signal(&1).node());
+    assert_ne!(map.get_signal(&1).node(), map.get_signal(&2).node());
+}
+
+#[test]
+fn test_contains_and_keys_only_notify_on_key_set_changes() {
+    let map = signal_map();
+    let contains = record(map.contains_key_signal(&"a"));
+    let keys = record(memo({
+        let map = map.clone();
+        move || {
+            let mut keys = map.keys_signal().get();
+            keys.sort();
+            keys
+        }
+    }));
+    let key_runs = Rc::new(RefCell::new(0));
+    let key_runs_by_effect = key_runs.clone();
+    let keys_signal = map.keys_signal();
+    effect(move || {
+        keys_signal.track();
+        *key_runs_by_effect.borrow_mut() += 1;
+    });
+
+    map.insert("a", 1);
+    map.insert("a", 2);
+    map.insert("b", 3);
+    map.insert("b", 4);
+    map.remove(&"a");
+    map.remove(&"a");
+
+    assert_eq!(*contains.borrow(), vec![false, true, false]);
+    assert_eq!(
+        *keys.borrow(),
+        vec![vec![], vec!["a"], vec!["a", "b"], vec!["b"]]
+    );
+    assert_eq!(*key_runs.borrow(), 4);
+}
+
+#[test]
+fn test_disposing_the_scope_disposes_per_key_nodes() {
+    let before = count();
+    let (map, owner) = scope_with(signal_map::<i32, String>);
+    map.insert(1, String::from("one"));
+
+    // Views are created under the map's scope wherever they are asked for
+    let view = scope({
+        let map = map.clone();
+        move || {
+            map.get_signal(&1);
+            map.contains_key_signal(&2);
+        }
+    });
+    view.dispose();
+    assert_eq!(map.get_signal(&1).get().as_deref(), Some("one"));
+    assert!(!map.contains_key_signal(&2).get());
+
+    owner.dispose();
+    assert_eq!(count(), before);
+}
+
+#[test]
+fn test_subscribe_deltas_reports_inserts_and_removals() {
+    let map = signal_map();
+    let received = Rc::new(RefCell::new(Vec::new()));
+    let received_by_subscriber = received.clone();
+    map.subscribe_deltas(move |deltas| received_by_subscriber.borrow_mut().push(deltas.to_vec()));
+
+    start_batch();
+    map.insert("a", 1);
+    map.insert("a", 2);
+    map.remove(&"a");
+    map.remove(&"missing");
+    end_batch();
+    assert_eq!(
+        *received.borrow(),
+        vec![vec![
+            MapDelta::Insert { key: "a", value: 1 },
+            MapDelta::Insert { key: "a", value: 2 },
+            MapDelta::Remove { key: "a" },
+        ]]
+    );
+}
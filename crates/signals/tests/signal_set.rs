@@ -0,0 +1,80 @@
+use samara_signals::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[test]
+fn test_toggling_one_member_reruns_one_effect() {
+    let selected = signal_set::<u64>();
+    let runs = Rc::new(RefCell::new(vec![0; 100]));
+    for id in 0..100 {
+        let contains = selected.contains_signal(&id);
+        let runs = runs.clone();
+        effect(move || {
+            contains.get();
+            runs.borrow_mut()[id as usize] += 1;
+        });
+    }
+    let total = || runs.borrow().iter().sum::<usize>();
+    assert_eq!(total(), 100);
+
+    assert!(selected.toggle(42));
+    assert_eq!(total(), 101);
+    assert_eq!(runs.borrow()[42], 2);
+
+    // Inserting a present member changes nothing
+    assert!(!selected.insert(42));
+    assert_eq!(total(), 101);
+
+    assert!(!selected.toggle(42));
+    assert_eq!(total(), 102);
+    assert_eq!(runs.borrow()[42], 3);
+    assert!(!selected.contains(&42));
+}
+
+#[test]
+fn test_len_signal_follows_membership() {
+    let selected = signal_set();
+    let len = selected.len_signal();
+    let lengths = Rc::new(RefCell::new(Vec::new()));
+    let lengths_by_effect = lengths.clone();
+    effect(move || lengths_by_effect.borrow_mut().push(len.get()));
+
+    selected.insert("a");
+    selected.insert("b");
+    selected.insert("b");
+    selected.remove(&"c");
+    selected.remove(&"a");
+    assert_eq!(*lengths.borrow(), vec![0, 1, 2, 1]);
+}
+
+#[test]
+fn test_member_nodes_are_disposed_with_the_set() {
+    let before = count();
+    let (selected, owner) = scope_with(signal_set::<u64>);
+    for id in 0..10 {
+        selected.contains_signal(&id);
+    }
+    selected.insert(3);
+    owner.dispose();
+    assert_eq!(count(), before);
+}
+
+#[test]
+fn test_subscribe_deltas_reports_membership_changes() {
+    let set = signal_set();
+    let received = Rc::new(RefCell::new(Vec::new()));
+    let received_by_subscriber = received.clone();
+    set.subscribe_deltas(move |deltas| {
+        received_by_subscriber
+            .borrow_mut()
+            .extend_from_slice(deltas)
+    });
+
+    set.insert(1);
+    set.insert(1);
+    set.toggle(1);
+    assert_eq!(
+        *received.borrow(),
+        vec![SetDelta::Insert(1), SetDelta::Remove(1)]
+    );
+}
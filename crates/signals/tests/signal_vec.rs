@@ -0,0 +1,315 @@
+use samara_signals::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[test]
+fn test_len_signal_only_notifies_on_length_changes() {
+    let items = signal_vec(vec![1, 2]);
+    let len = items.len_signal();
+    let lengths = Rc::new(RefCell::new(Vec::new()));
+
+    let lengths_for_effect = lengths.clone();
+    effect(move || lengths_for_effect.borrow_mut().push(len.get()));
+    assert_eq!(*lengths.borrow(), vec![2]);
+
+    items.set(0, 10);
+    items.set(1, 20);
+    assert_eq!(*lengths.borrow(), vec![2]);
+
+    items.push(3);
+    items.insert(0, 0);
+    assert_eq!(*lengths.borrow(), vec![2, 3, 4]);
+
+    // Removing and inserting within one batch leaves the length as it was
+    start_batch();
+    items.remove(0);
+    items.push(4);
+    end_batch();
+    assert_eq!(*lengths.borrow(), vec![2, 3, 4]);
+
+    items.clear();
+    assert_eq!(*lengths.borrow(), vec![2, 3, 4, 0]);
+}
+
+#[test]
+fn test_subscriber_receives_batched_deltas_in_order() {
+    let items = signal_vec(vec!["a", "b"]);
+    let received = Rc::new(RefCell::new(Vec::new()));
+
+    let received_by_subscriber = received.clone();
+    items.subscribe_deltas(move |deltas| received_by_subscriber.borrow_mut().push(deltas.to_vec()));
+
+    start_batch();
+    items.push("c");
+    items.insert(0, "z");
+    assert_eq!(items.set(1, "A"), "a");
+    assert_eq!(items.remove(2), "b");
+    end_batch();
+
+    assert_eq!(
+        *received.borrow(),
+        vec![vec![
+            VecDelta::Insert {
+                index: 2,
+                value: "c"
+            },
+            VecDelta::Insert {
+                index: 0,
+                value: "z"
+            },
+            VecDelta::Set {
+                index: 1,
+                value: "A"
+            },
+            VecDelta::Remove { index: 2 },
+        ]]
+    );
+    assert_eq!(items.with(|items| items.to_vec()), vec!["z", "A", "c"]);
+
+    // Outside a batch every operation flushes on its own
+    items.clear();
+    items.push("d");
+    assert_eq!(
+        received.borrow()[1..],
+        [
+            vec![VecDelta::Clear],
+            vec![VecDelta::Insert {
+                index: 0,
+                value: "d"
+            }],
+        ]
+    );
+}
+
+#[test]
+fn test_iter_tracked_follows_every_change() {
+    let items = signal_vec(vec![1, 2, 3]);
+    let sums = Rc::new(RefCell::new(Vec::new()));
+
+    let sums_for_effect = sums.clone();
+    let items_for_effect = items.clone();
+    effect(move || {
+        let sum: i32 = items_for_effect.iter_tracked().sum();
+        sums_for_effect.borrow_mut().push(sum);
+    });
+    assert_eq!(*sums.borrow(), vec![6]);
+
+    items.set(0, 10);
+    items.remove(1);
+    assert_eq!(*sums.borrow(), vec![6, 15, 13]);
+}
+
+#[test]
+fn test_signal_vec_is_disposed_with_its_scope() {
+    let before = count();
+    let owner = scope(|| {
+        let items = signal_vec(vec![1]);
+        items.subscribe_deltas(|_| {});
+        items.push(2);
+    });
+    assert!(count().0 > before.0);
+
+    owner.dispose();
+    assert_eq!(count(), before);
+}
+
+#[test]
+fn test_map_memo_maps_only_changed_items() {
+    let items = signal_vec((0..1000).collect::<Vec<i32>>());
+    let calls = Rc::new(RefCell::new(0));
+    let doubled = items.map_memo({
+        let calls = calls.clone();
+        move |value| {
+            *calls.borrow_mut() += 1;
+            value * 2
+        }
+    });
+    assert_eq!(*calls.borrow(), 1000);
+
+    let deltas = Rc::new(RefCell::new(Vec::new()));
+    let deltas_by_subscriber = deltas.clone();
+    doubled.subscribe_deltas(move |changes| {
+        deltas_by_subscriber.borrow_mut().extend_from_slice(changes)
+    });
+
+    items.set(500, -1);
+    assert_eq!(*calls.borrow(), 1001);
+    assert_eq!(
+        *deltas.borrow(),
+        vec![VecDelta::Set {
+            index: 500,
+            value: -2
+        }]
+    );
+
+    // Insertions and removals keep the output in order
+    start_batch();
+    items.insert(0, 7);
+    items.remove(1);
+    items.push(9);
+    end_batch();
+    assert_eq!(*calls.borrow(), 1003);
+    assert_eq!(
+        doubled.with(|values| values.to_vec()),
+        items.with(|values| values.iter().map(|value| value * 2).collect::<Vec<_>>())
+    );
+}
+
+#[test]
+fn test_subscribe_deltas_buffers_per_subscriber() {
+    let items = signal_vec(vec![1, 2, 3]);
+    let first = Rc::new(RefCell::new(Vec::new()));
+    let first_by_subscriber = first.clone();
+    items.subscribe_deltas(move |deltas| first_by_subscriber.borrow_mut().push(deltas.to_vec()));
+
+    start_batch();
+    items.push(4);
+    items.remove(0);
+    items.set(0, 20);
+    end_batch();
+    assert_eq!(
+        *first.borrow(),
+        vec![vec![
+            VecDelta::Insert { index: 3, value: 4 },
+            VecDelta::Remove { index: 0 },
+            VecDelta::Set {
+                index: 0,
+                value: 20
+            },
+        ]]
+    );
+
+    // A later subscriber starts with an empty buffer
+    let second = Rc::new(RefCell::new(Vec::new()));
+    let second_by_subscriber = second.clone();
+    let subscription = items
+        .subscribe_deltas(move |deltas| second_by_subscriber.borrow_mut().push(deltas.to_vec()));
+    assert!(second.borrow().is_empty());
+
+    items.push(5);
+    assert_eq!(
+        *second.borrow(),
+        vec![vec![VecDelta::Insert { index: 3, value: 5 }]]
+    );
+    assert_eq!(first.borrow().len(), 2);
+
+    subscription.unsubscribe();
+    items.clear();
+    assert_eq!(second.borrow().len(), 1);
+    assert_eq!(first.borrow()[2], vec![VecDelta::Clear]);
+}
+
+#[test]
+fn test_subscriber_added_mid_flush_sees_only_later_changes() {
+    let items = signal_vec(vec![0]);
+    let late = Rc::new(RefCell::new(Vec::new()));
+
+    let subscribed = Rc::new(RefCell::new(false));
+    items.subscribe_deltas({
+        let items = items.clone();
+        let late = late.clone();
+        move |_| {
+            if !subscribed.replace(true) {
+                let late = late.clone();
+                items.subscribe_deltas(move |deltas| late.borrow_mut().push(deltas.to_vec()));
+            }
+        }
+    });
+
+    items.push(1);
+    assert!(late.borrow().is_empty());
+
+    items.push(2);
+    assert_eq!(
+        *late.borrow(),
+        vec![vec![VecDelta::Insert { index: 2, value: 2 }]]
+    );
+}
+
+#[test]
+fn test_extend_notifies_once() {
+    let items = signal_vec(vec![0]);
+    let runs = Rc::new(RefCell::new(0));
+    let runs_by_effect = runs.clone();
+    let items_for_effect = items.clone();
+    effect(move || {
+        items_for_effect.with(|_| ());
+        *runs_by_effect.borrow_mut() += 1;
+    });
+    let received = Rc::new(RefCell::new(Vec::new()));
+    let received_by_subscriber = received.clone();
+    items.subscribe_deltas(move |deltas| received_by_subscriber.borrow_mut().push(deltas.to_vec()));
+
+    items.extend(1..=1000);
+    assert_eq!(*runs.borrow(), 2);
+    assert_eq!(
+        *received.borrow(),
+        vec![vec![VecDelta::Splice {
+            index: 1,
+            removed: 0,
+            inserted: (1..=1000).collect()
+        }]]
+    );
+    assert_eq!(items.len_signal().get(), 1001);
+}
+
+#[test]
+fn test_retain_reports_the_removed_indices() {
+    let items = signal_vec((0..10).collect::<Vec<i32>>());
+    let received = Rc::new(RefCell::new(Vec::new()));
+    let received_by_subscriber = received.clone();
+    items.subscribe_deltas(move |deltas| received_by_subscriber.borrow_mut().push(deltas.to_vec()));
+
+    items.retain(|value| value % 2 == 0);
+    assert_eq!(items.with(|items| items.to_vec()), vec![0, 2, 4, 6, 8]);
+    assert_eq!(
+        *received.borrow(),
+        vec![
+            [9, 7, 5, 3, 1]
+                .map(|index| VecDelta::Remove { index })
+                .to_vec()
+        ]
+    );
+
+    // Runs of removed items are coalesced
+    items.retain(|value| *value == 8);
+    assert_eq!(
+        received.borrow()[1],
+        vec![VecDelta::Splice {
+            index: 0,
+            removed: 4,
+            inserted: Vec::new()
+        }]
+    );
+}
+
+#[test]
+fn test_splice_replaces_a_range() {
+    let items = signal_vec(vec!["a", "b", "c", "d"]);
+    let lengths = items.map_memo(|item| item.len());
+    let received = Rc::new(RefCell::new(Vec::new()));
+    let received_by_subscriber = received.clone();
+    items.subscribe_deltas(move |deltas| {
+        received_by_subscriber
+            .borrow_mut()
+            .extend_from_slice(deltas)
+    });
+
+    assert_eq!(items.splice(1..3, ["xx", "yyy", "z"]), vec!["b", "c"]);
+    assert_eq!(
+        items.with(|items| items.to_vec()),
+        vec!["a", "xx", "yyy", "z", "d"]
+    );
+    assert_eq!(
+        *received.borrow(),
+        vec![VecDelta::Splice {
+            index: 1,
+            removed: 2,
+            inserted: vec!["xx", "yyy", "z"]
+        }]
+    );
+    assert_eq!(
+        lengths.with(|lengths| lengths.to_vec()),
+        vec![1, 2, 3, 1, 1]
+    );
+}
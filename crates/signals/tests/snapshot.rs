@@ -0,0 +1,136 @@
+use samara_signals::*;
+
+#[test]
+fn test_snapshot_kinds_and_edges() {
+    let a = signal(1);
+    let doubled = memo(move || a.get() * 2);
+    let ui = scope(move || {
+        effect(move || {
+            doubled.get();
+        });
+    });
+    ui.set_name("ui");
+
+    let snapshot = snapshot();
+    let kind_of = |key| snapshot.node(key).unwrap().kind;
+
+    assert_eq!(kind_of(snapshot.root), NodeKind::Scope);
+    assert_eq!(snapshot.nodes.len(), count().0);
+    assert_eq!(snapshot.links.len(), count().1);
+
+    let ui_node = snapshot.node(ui.node()).unwrap();
+    assert_eq!(ui_node.kind, NodeKind::Scope);
+    assert_eq!(ui_node.name.as_deref(), Some("ui"));
+    assert_eq!(ui_node.parent, Some(snapshot.root));
+
+    let effect = snapshot
+        .nodes
+        .iter()
+        .find(|node| node.kind == NodeKind::Effect)
+        .unwrap();
+    assert_eq!(effect.parent, Some(ui.node()));
+    assert!(effect.flags.iter().any(|flag| flag == "WATCHING"));
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    assert!(effect.location.as_deref().unwrap().contains("snapshot.rs"));
+
+    // signal -> memo -> effect, plus the owning scope's link to the effect
+    let mut edges: Vec<_> = snapshot
+        .links
+        .iter()
+        .map(|link| (kind_of(link.dep), kind_of(link.sub)))
+        .collect();
+    edges.sort_by_key(|edge| format!("{edge:?}"));
+    assert_eq!(
+        edges,
+        [
+            (NodeKind::Computed, NodeKind::Effect),
+            (NodeKind::Effect, NodeKind::Scope),
+            (NodeKind::Signal, NodeKind::Computed),
+        ]
+    );
+
+    let memo_node = snapshot
+        .nodes
+        .iter()
+        .find(|node| node.kind == NodeKind::Computed)
+        .unwrap();
+    assert_eq!((memo_node.dependencies, memo_node.subscribers), (1, 1));
+}
+
+#[test]
+fn test_snapshot_json_round_trip() {
+    let a = signal(0);
+    effect(move || {
+        a.get();
+    });
+
+    let snapshot = snapshot();
+    let json = serde_json::to_string(&snapshot).unwrap();
+    assert!(json.contains("\"kind\":\"Signal\""));
+    let parsed: GraphSnapshot = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, snapshot);
+}
+
+// Run with `cargo test --release --features track-caller` to check that the
+// feature keeps locations without debug assertions
+#[cfg(feature = "track-caller")]
+#[test]
+fn test_track_caller_keeps_locations() {
+    let line = line!() + 1;
+    let a = signal(0);
+
+    let snapshot = snapshot();
+    let location = snapshot.node(a.node()).unwrap().location.clone().unwrap();
+    assert!(location.starts_with(&format!("{}:{line}:", file!())));
+    let json = serde_json::to_string(&snapshot).unwrap();
+    assert!(json.contains(&location));
+    assert!(serialize().contains(&format!("\"line\":{line}")));
+}
+
+#[test]
+fn test_restore_topology_round_trip() {
+    let a = signal(1);
+    let doubled = memo(move || a.get() * 2);
+    let ui = scope(move || {
+        effect(move || {
+            doubled.get();
+        })
+        .set_name("render");
+    });
+    ui.set_name("ui");
+    let captured = snapshot();
+
+    std::thread::spawn(move || {
+        let keys = restore_topology(&captured);
+        let restored = snapshot();
+        assert_eq!(restored.nodes.len(), captured.nodes.len());
+        assert_eq!(restored.links.len(), captured.links.len());
+
+        for node in &captured.nodes {
+            let copy = restored.node(keys[&node.key]).unwrap();
+            assert_eq!(copy.kind, node.kind);
+            assert_eq!(copy.name, node.name);
+            assert_eq!(copy.flags, node.flags);
+            assert_eq!(copy.location, node.location);
+            assert_eq!(copy.parent, node.parent.map(|parent| keys[&parent]));
+            assert_eq!(copy.subscribers, node.subscribers);
+            assert_eq!(copy.dependencies, node.dependencies);
+        }
+
+        let mut edges: Vec<_> = captured
+            .links
+            .iter()
+            .map(|link| (keys[&link.dep], keys[&link.sub]))
+            .collect();
+        let mut restored_edges: Vec<_> = restored
+            .links
+            .iter()
+            .map(|link| (link.dep, link.sub))
+            .collect();
+        edges.sort();
+        restored_edges.sort();
+        assert_eq!(edges, restored_edges);
+    })
+    .join()
+    .unwrap();
+}
@@ -0,0 +1,105 @@
+use samara_signals::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Theme {
+    Light,
+    Dark,
+}
+
+#[derive(Clone, Debug, PartialEq, Store)]
+struct Audio {
+    volume: f32,
+    muted: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Store)]
+struct Settings {
+    theme: Theme,
+    #[store(nested)]
+    audio: Audio,
+}
+
+fn settings() -> Settings {
+    Settings {
+        theme: Theme::Light,
+        audio: Audio {
+            volume: 0.5,
+            muted: false,
+        },
+    }
+}
+
+#[test]
+fn test_fields_are_reactive_one_by_one() {
+    let store = SettingsStore::new(settings());
+    let volumes = Rc::new(RefCell::new(Vec::new()));
+
+    let volumes_by_effect = volumes.clone();
+    effect(move || {
+        volumes_by_effect
+            .borrow_mut()
+            .push(store.audio.volume.get())
+    });
+
+    store.theme.set(Theme::Dark);
+    store.audio.muted.set(true);
+    assert_eq!(*volumes.borrow(), vec![0.5]);
+
+    store.audio.volume.set(0.8);
+    assert_eq!(*volumes.borrow(), vec![0.5, 0.8]);
+
+    assert_eq!(
+        store.snapshot(),
+        Settings {
+            theme: Theme::Dark,
+            audio: Audio {
+                volume: 0.8,
+                muted: true,
+            },
+        }
+    );
+}
+
+#[test]
+fn test_set_all_writes_in_one_batch() {
+    let store = SettingsStore::new(settings());
+    let seen = Rc::new(RefCell::new(Vec::new()));
+
+    let seen_by_effect = seen.clone();
+    effect(move || {
+        seen_by_effect.borrow_mut().push((
+            store.theme.get(),
+            store.audio.volume.get(),
+            store.audio.muted.get(),
+        ))
+    });
+
+    store.set_all(Settings {
+        theme: Theme::Dark,
+        audio: Audio {
+            volume: 1.0,
+            muted: true,
+        },
+    });
+    assert_eq!(
+        *seen.borrow(),
+        vec![(Theme::Light, 0.5, false), (Theme::Dark, 1.0, true)]
+    );
+}
+
+#[test]
+fn test_snapshot_does_not_track() {
+    let store = SettingsStore::new(settings());
+    let runs = Rc::new(RefCell::new(0));
+
+    let runs_by_effect = runs.clone();
+    effect(move || {
+        store.snapshot();
+        *runs_by_effect.borrow_mut() += 1;
+    });
+
+    store.set_all(settings());
+    assert_eq!(*runs.borrow(), 1);
+}
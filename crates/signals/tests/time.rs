@@ -0,0 +1,161 @@
+use samara_signals::*;
+use std::time::{Duration, Instant};
+
+/// Drive the executor for `duration`; `poll()` itself never completes
+async fn drive_for(duration: Duration) {
+    tokio::select! {
+        _ = poll() => unreachable!(),
+        _ = tokio::time::sleep(duration) => {}
+    }
+}
+
+#[tokio::test]
+async fn test_sleep() {
+    let start = Instant::now();
+    let done = signal(false);
+    spawn(async move {
+        sleep(Duration::from_millis(20)).await;
+        done.set(true);
+    });
+    join().await;
+    assert!(done.get());
+    assert!(start.elapsed() >= Duration::from_millis(20));
+}
+
+#[tokio::test]
+async fn test_interval_signal_ticks_and_stops() {
+    let seen = signal(Vec::new());
+    let clock = scope(move || {
+        let ticks = interval_signal(Duration::from_millis(10));
+        effect(move || {
+            let tick = ticks.get();
+            seen.write().push(tick);
+        });
+    });
+
+    drive_for(Duration::from_millis(55)).await;
+    let ticks = seen.get();
+    assert_eq!(ticks[0], 0);
+    assert!((3..=6).contains(&ticks.len()), "{ticks:?}");
+    assert!(ticks.windows(2).all(|pair| pair[1] == pair[0] + 1));
+
+    clock.dispose();
+    let after_dispose = seen.get().len();
+    // The timer task was aborted, so join() has nothing left to wait for
+    join().await;
+    drive_for(Duration::from_millis(30)).await;
+    assert_eq!(seen.get().len(), after_dispose);
+}
+
+#[tokio::test]
+async fn test_timeout_signal() {
+    let fired = timeout_signal(Duration::from_millis(10));
+    assert!(!fired.get());
+    join().await;
+    assert!(fired.get());
+}
+
+#[tokio::test]
+async fn test_timeout_signal_cancelled_with_scope() {
+    let runs = signal(0);
+    let s = scope(move || {
+        let fired = timeout_signal(Duration::from_millis(10));
+        effect(move || {
+            fired.get();
+            runs.update(|runs| *runs += 1);
+        });
+    });
+    s.dispose();
+    drive_for(Duration::from_millis(30)).await;
+    assert_eq!(runs.get(), 1);
+}
+
+#[tokio::test]
+async fn test_debounce_collapses_bursts() {
+    let source = signal(0);
+    let debounced = debounce(source, Duration::from_millis(20));
+    let seen = signal(Vec::new());
+    effect(move || {
+        let value = debounced.get();
+        seen.write().push(value);
+    });
+
+    for value in 1..=5 {
+        source.set(value);
+        drive_for(Duration::from_millis(1)).await;
+    }
+    assert_eq!(debounced.get(), 0);
+
+    join().await;
+    assert_eq!(debounced.get(), 5);
+    assert_eq!(seen.get(), vec![0, 5]);
+}
+
+async fn throttled_notifications(leading: bool, trailing: bool) -> Vec<i32> {
+    let source = signal(0);
+    let throttled = throttle(source, Duration::from_millis(10), leading, trailing);
+    let seen = signal(Vec::new());
+    effect(move || {
+        let value = throttled.get();
+        seen.write().push(value);
+    });
+
+    for value in 1..=10 {
+        source.set(value);
+    }
+    join().await;
+
+    // Skip the initial run
+    seen.get()[1..].to_vec()
+}
+
+#[tokio::test]
+async fn test_throttle_leading_and_trailing() {
+    assert_eq!(throttled_notifications(true, true).await, vec![1, 10]);
+}
+
+#[tokio::test]
+async fn test_throttle_leading_only() {
+    assert_eq!(throttled_notifications(true, false).await, vec![1]);
+}
+
+#[tokio::test]
+async fn test_throttle_trailing_only() {
+    assert_eq!(throttled_notifications(false, true).await, vec![10]);
+}
+
+#[tokio::test]
+async fn test_throttle_windows_reopen() {
+    let source = signal(0);
+    let throttled = throttle(source, Duration::from_millis(10), true, false);
+
+    source.set(1);
+    join().await;
+    // The window has closed, so the next change is forwarded immediately
+    source.set(2);
+    assert_eq!(throttled.get(), 2);
+    join().await;
+}
+
+#[tokio::test]
+async fn test_throttle_disposed_mid_burst() {
+    let source = signal(0);
+    let seen = signal(Vec::new());
+    let s = scope(move || {
+        let throttled = throttle(source, Duration::from_millis(10), false, true);
+        effect(move || {
+            let value = throttled.get();
+            seen.write().push(value);
+        });
+    });
+
+    for value in 1..=5 {
+        source.set(value);
+    }
+    s.dispose();
+    for value in 6..=10 {
+        source.set(value);
+    }
+    drive_for(Duration::from_millis(30)).await;
+    assert_eq!(seen.get(), vec![0]);
+}
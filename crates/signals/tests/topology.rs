@@ -1,4 +1,6 @@
 use samara_signals::*;
+use std::cell::Cell;
+use std::rc::Rc;
 
 #[test]
 fn test_topology_drop_a_b_a_updates() {
@@ -233,6 +235,94 @@ fn test_topology_support_lazy_branches() {
     assert_eq!(c.get(), 0);
 }
 
+#[test]
+fn test_topology_unwatched_chain_is_not_walked() {
+    //   A
+    //   |
+    //  *B
+    //   |
+    //  *C   <- read directly, no effect anywhere
+    let a = signal(1i32);
+    let b = memo(move || a.get() * 2);
+    let c = memo(move || b.get() + 1);
+    assert_eq!(c.get(), 3);
+
+    // Only B, the direct subscriber, is marked by the write
+    let plan = explain_set(a.node());
+    assert_eq!(plan.marked.len(), 1);
+    assert_eq!(plan.marked[0].node, b.node());
+    a.set(2);
+    assert_eq!(c.get(), 5);
+
+    // Reading B first settles it before C is asked
+    a.set(3);
+    assert_eq!(b.get(), 6);
+    assert_eq!(c.get(), 7);
+    assert_eq!(validate(), Ok(()));
+}
+
+#[test]
+fn test_topology_watcher_flag_follows_effects() {
+    //   A
+    //   |
+    //  *B
+    //   |
+    //  *C
+    //   |
+    //   E  <- effect, disposed halfway
+    let a = signal(1i32);
+    let b = memo(move || a.get() * 2);
+    let c = memo(move || b.get() + 1);
+    let runs = std::rc::Rc::new(std::cell::Cell::new(0));
+    let runs_for_effect = runs.clone();
+    let e = effect(move || {
+        c.get();
+        runs_for_effect.set(runs_for_effect.get() + 1);
+    });
+    let flags = |node: NodeKey| snapshot().node(node).unwrap().flags.clone();
+    assert!(flags(b.node()).contains(&"HAS_WATCHER".to_string()));
+    assert_eq!(explain_set(a.node()).marked.len(), 3);
+
+    a.set(2);
+    assert_eq!(runs.get(), 2);
+
+    e.dispose();
+    assert!(!flags(b.node()).contains(&"HAS_WATCHER".to_string()));
+    assert_eq!(validate(), Ok(()));
+    a.set(3);
+    assert_eq!(c.get(), 7);
+    assert_eq!(runs.get(), 2);
+}
+
+#[test]
+fn test_topology_watcher_flag_counts_shared_subscribers() {
+    //   A
+    //   |
+    //  *B
+    //  / \
+    // E1  E2  <- effects, disposed one after the other
+    let a = signal(1i32);
+    let b = memo(move || a.get() * 2);
+    let e1 = effect(move || {
+        b.get();
+    });
+    let e2 = effect(move || {
+        b.get();
+    });
+    let flags = |node: NodeKey| snapshot().node(node).unwrap().flags.clone();
+
+    // B is still watched by the other effect
+    e1.dispose();
+    assert!(flags(a.node()).contains(&"HAS_WATCHER".to_string()));
+    assert!(flags(b.node()).contains(&"HAS_WATCHER".to_string()));
+    assert_eq!(validate(), Ok(()));
+
+    e2.dispose();
+    assert!(!flags(a.node()).contains(&"HAS_WATCHER".to_string()));
+    assert!(!flags(b.node()).contains(&"HAS_WATCHER".to_string()));
+    assert_eq!(validate(), Ok(()));
+}
+
 #[test]
 fn test_topology_not_update_sub_if_all_deps_unmark() {
     // In this scenario "B" and "C" always return the same value. When "A"
@@ -267,3 +357,145 @@ fn test_topology_not_update_sub_if_all_deps_unmark() {
     a.set("aa");
     assert_eq!(*d_count.borrow(), 1);
 }
+
+#[test]
+fn test_topology_explain_diamond_with_tail() {
+    //     A
+    //   /   \
+    //  B     C
+    //   \   /
+    //     D
+    //     |
+    //     E (effect)
+    let a = signal("a");
+    let b = memo(move || a.get());
+    let c = memo(move || a.get());
+    let d = memo(move || format!("{} {}", b.get(), c.get()));
+    let e = effect(move || {
+        d.get();
+    });
+
+    let before = snapshot();
+    let plan = explain_set(a.node());
+    assert_eq!(snapshot(), before, "explaining must not touch any flags");
+
+    assert_eq!(plan.source, a.node());
+    let marked: Vec<_> = plan.marked.iter().map(|planned| planned.node).collect();
+    assert_eq!(marked.len(), 4);
+    for node in [b.node(), c.node(), d.node(), e.node()] {
+        assert_eq!(marked.iter().filter(|&&key| key == node).count(), 1);
+    }
+    assert_eq!(plan.effects, [e.node()]);
+
+    assert_eq!(plan.get(b.node()).unwrap().mark, PlannedMark::Dirty);
+    assert_eq!(plan.get(c.node()).unwrap().mark, PlannedMark::Dirty);
+    let d_plan = plan.get(d.node()).unwrap();
+    assert_eq!(d_plan.mark, PlannedMark::Pending);
+    assert_eq!(d_plan.kind, NodeKind::Computed);
+    assert_eq!(d_plan.path.first(), Some(&a.node()));
+    assert_eq!(d_plan.path.last(), Some(&d.node()));
+    assert_eq!(d_plan.path.len(), 3);
+    let e_plan = plan.get(e.node()).unwrap();
+    assert_eq!(e_plan.kind, NodeKind::Effect);
+    assert_eq!(&e_plan.path[..3], &d_plan.path[..]);
+    #[cfg(any(debug_assertions, feature = "track-caller"))]
+    assert!(e_plan.location.as_deref().unwrap().contains("topology.rs"));
+}
+
+#[test]
+fn test_topology_explain_unobserved_signal() {
+    let a = signal(1);
+    let plan = explain_set(a.node());
+    assert!(plan.marked.is_empty());
+    assert!(plan.effects.is_empty());
+}
+
+// Release builds only check keys with the `checked-slotmap` feature
+#[cfg(any(debug_assertions, feature = "checked-slotmap"))]
+#[test]
+fn test_topology_stale_key_panics_with_key() {
+    let a = signal(1);
+    let stale = a.node();
+    a.dispose();
+
+    let err = std::panic::catch_unwind(|| explain_set(stale)).unwrap_err();
+    let message = err.downcast_ref::<String>().unwrap();
+    assert!(
+        message.starts_with(&format!(
+            "{stale:?} used after its node or link was removed, at "
+        )),
+        "{message}"
+    );
+    assert!(message.contains("src/system/"), "{message}");
+}
+
+#[test]
+fn test_topology_nested_check_keeps_outer_stack() {
+    let s0 = signal(0);
+    let s1 = signal(0);
+    let s2 = signal(0);
+    let s3 = signal(0);
+    let n4 = computed(move |_| {
+        if s2.get() % 2 == 0 {
+            s0.get() + 1
+        } else {
+            s1.get() * 2 % 7
+        }
+    });
+    let n5 = memo(move || s3.get() % 5);
+    let n7 = computed(move |_| (s2.get() + s1.get()) % 5);
+    let n8 = computed(move |_| {
+        if n7.get() % 2 == 0 {
+            n4.get() + 1
+        } else {
+            s2.get() * 2 % 7
+        }
+    });
+    let n9 = computed(move |_| {
+        if n8.get() % 2 == 0 {
+            n5.get() + 1
+        } else {
+            s1.get() * 2 % 7
+        }
+    });
+
+    s0.set(5);
+    s1.set(1);
+    let seen = Rc::new(Cell::new(0));
+    let seen_for_first = seen.clone();
+    let _first = effect(move || seen_for_first.set(n9.get()));
+    n4.get();
+    let seen_for_second = seen.clone();
+    let _second = effect(move || seen_for_second.set(n9.get()));
+
+    s1.set(5);
+    assert_eq!(n9.get(), 3);
+    assert_eq!(seen.get(), 3);
+}
+
+#[test]
+fn test_topology_getter_reading_written_signal_keeps_sibling_dirty() {
+    let a = signal(0);
+    let b = signal(0);
+    let c = signal(0);
+    let inner = memo(move || {
+        if c.get() % 2 == 0 {
+            c.get() + 1
+        } else {
+            b.get() * 2 % 7
+        }
+    });
+    let outer = memo(move || inner.get() + c.get() + a.get());
+
+    c.set(1);
+    a.set(4);
+    let seen = Rc::new(Cell::new(0));
+    let seen_for_effect = seen.clone();
+    let _effect = effect(move || seen_for_effect.set(outer.get()));
+    assert_eq!(seen.get(), 5);
+
+    // `inner` reads `c` while it is recomputed, before `outer` is checked
+    c.set(3);
+    assert_eq!(outer.get(), 7);
+    assert_eq!(seen.get(), 7);
+}
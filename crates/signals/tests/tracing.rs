@@ -0,0 +1,99 @@
+#![cfg(feature = "tracing")]
+
+use samara_signals::*;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+/// Records the names of spans and the messages of events, in order
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<String>>>);
+
+impl Recorder {
+    fn take(&self) -> Vec<String> {
+        std::mem::take(&mut self.0.lock().unwrap())
+    }
+
+    fn count(records: &[String], name: &str) -> usize {
+        records.iter().filter(|record| *record == name).count()
+    }
+}
+
+struct Message(String);
+
+impl Visit for Message {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.0 = format!("{value:?}");
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for Recorder {
+    fn on_new_span(&self, attrs: &Attributes<'_>, _: &Id, _: Context<'_, S>) {
+        let name = attrs.metadata().name();
+        self.0.lock().unwrap().push(format!("span {name}"));
+    }
+
+    fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+        let mut message = Message(String::new());
+        event.record(&mut message);
+        self.0.lock().unwrap().push(message.0);
+    }
+}
+
+fn with_recorder<T>(f: impl FnOnce(&Recorder) -> T) -> T {
+    let recorder = Recorder::default();
+    let subscriber = tracing_subscriber::registry().with(recorder.clone());
+    tracing::subscriber::with_default(subscriber, || f(&recorder))
+}
+
+#[test]
+fn test_spans_for_signal_effect_update() {
+    with_recorder(|recorder| {
+        let s = signal(0);
+        let e = effect(move || {
+            s.get();
+        });
+        assert_eq!(recorder.take(), ["node_created", "node_created"]);
+
+        s.set(1);
+        let records = recorder.take();
+        assert_eq!(
+            records,
+            ["signal_set", "span flush", "span effect_run"],
+            "{records:?}"
+        );
+
+        start_batch();
+        s.set(2);
+        s.set(3);
+        end_batch();
+        let records = recorder.take();
+        assert_eq!(Recorder::count(&records, "signal_set"), 2);
+        assert_eq!(Recorder::count(&records, "span flush"), 1);
+        assert_eq!(Recorder::count(&records, "span effect_run"), 1);
+
+        e.dispose();
+        assert_eq!(recorder.take(), ["node_disposed"]);
+    });
+}
+
+#[test]
+fn test_clean_effect_does_not_open_run_span() {
+    with_recorder(|recorder| {
+        let s = signal(0);
+        let parity = memo(move || s.get() % 2);
+        effect(move || {
+            parity.get();
+        });
+        recorder.take();
+
+        s.set(2);
+        let records = recorder.take();
+        assert_eq!(Recorder::count(&records, "span flush"), 1);
+        assert_eq!(Recorder::count(&records, "span effect_run"), 0);
+    });
+}
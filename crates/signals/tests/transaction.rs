@@ -0,0 +1,207 @@
+use samara_signals::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[test]
+fn test_transaction_rollback_on_err() {
+    let a = signal(1i32);
+    let b = signal(String::from("x"));
+    let runs = Rc::new(RefCell::new(0));
+
+    let runs_for_closure = runs.clone();
+    let _effect = effect(move || {
+        a.get();
+        b.get();
+        *runs_for_closure.borrow_mut() += 1;
+    });
+
+    let result: Result<(), &str> = transaction(|| {
+        a.set(2);
+        a.set(3);
+        b.set(String::from("y"));
+        // Writes are visible inside the transaction
+        assert_eq!(a.get(), 3);
+        Err("rejected")
+    });
+
+    assert_eq!(result, Err("rejected"));
+    assert_eq!(a.get(), 1);
+    assert_eq!(b.get(), "x");
+    assert_eq!(*runs.borrow(), 1);
+}
+
+#[test]
+fn test_transaction_commit_is_batched() {
+    let a = signal(1i32);
+    let b = signal(1i32);
+    let values = Rc::new(RefCell::new(Vec::new()));
+
+    let values_for_closure = values.clone();
+    let _effect = effect(move || {
+        values_for_closure.borrow_mut().push(a.get() + b.get());
+    });
+
+    let result: Result<i32, ()> = transaction(|| {
+        a.set(2);
+        b.set(3);
+        assert_eq!(*values.borrow(), vec![2]);
+        Ok(42)
+    });
+
+    assert_eq!(result, Ok(42));
+    assert_eq!(*values.borrow(), vec![2, 5]);
+}
+
+#[test]
+fn test_transaction_rollback_on_panic() {
+    let a = signal(1i32);
+    let c = memo(move || a.get() * 10);
+    let runs = Rc::new(RefCell::new(0));
+
+    let runs_for_closure = runs.clone();
+    let _effect = effect(move || {
+        c.get();
+        *runs_for_closure.borrow_mut() += 1;
+    });
+
+    let result = std::panic::catch_unwind(|| {
+        let _: Result<(), ()> = transaction(|| {
+            a.set(5);
+            panic!("boom");
+        });
+    });
+
+    assert!(result.is_err());
+    assert_eq!(a.get(), 1);
+    assert_eq!(c.get(), 10);
+    assert_eq!(*runs.borrow(), 1);
+
+    // The runtime keeps working after the failed transaction
+    a.set(2);
+    assert_eq!(c.get(), 20);
+    assert_eq!(*runs.borrow(), 2);
+}
+
+#[test]
+fn test_nested_transaction() {
+    let a = signal(1i32);
+    let b = signal(1i32);
+    let runs = Rc::new(RefCell::new(0));
+
+    let runs_for_closure = runs.clone();
+    let _effect = effect(move || {
+        a.get();
+        b.get();
+        *runs_for_closure.borrow_mut() += 1;
+    });
+
+    let result: Result<(), ()> = transaction(|| {
+        a.set(2);
+        let inner: Result<(), ()> = transaction(|| {
+            b.set(2);
+            Err(())
+        });
+        assert!(inner.is_err());
+        assert_eq!(b.get(), 1);
+        Ok(())
+    });
+
+    assert!(result.is_ok());
+    assert_eq!((a.get(), b.get()), (2, 1));
+    assert_eq!(*runs.borrow(), 2);
+}
+
+#[test]
+fn test_computed_reads_writes_inside_transaction() {
+    let a = signal(1i32);
+    let doubled = memo(move || a.get() * 2);
+    let values = Rc::new(RefCell::new(Vec::new()));
+
+    let values_for_closure = values.clone();
+    let _effect = effect(move || {
+        values_for_closure.borrow_mut().push(doubled.get());
+    });
+
+    let _: Result<(), ()> = transaction(|| {
+        a.set(2);
+        assert_eq!(doubled.get(), 4);
+        a.set(3);
+        assert_eq!(doubled.get(), 6);
+        assert_eq!(*values.borrow(), vec![2]);
+        Err(())
+    });
+
+    assert_eq!(doubled.get(), 2);
+    assert_eq!(*values.borrow(), vec![2]);
+}
+
+#[test]
+fn test_in_place_writes_roll_back() {
+    let mut count = signal(1i32);
+    let flag = signal(false);
+    let items = signal(vec![1, 2]);
+    let total = memo(move || count.get() + items.with(|items| items.iter().sum::<i32>()));
+    assert_eq!(total.get(), 4);
+
+    let _: Result<(), ()> = transaction(|| {
+        count += 10;
+        flag.toggle();
+        items.set_at(0, 5);
+        // Once replaced with `set`, the value can be mutated in place
+        items.set(vec![5, 2, 3]);
+        items.write().push(4);
+        assert_eq!(total.get(), 25);
+        Err(())
+    });
+
+    assert_eq!(count.get(), 1);
+    assert!(!flag.get());
+    assert_eq!(items.get(), vec![1, 2]);
+    assert_eq!(total.get(), 4);
+
+    let _: Result<(), ()> = transaction(|| {
+        count += 1;
+        items.set_at(1, 3);
+        Ok(())
+    });
+    assert_eq!((count.get(), items.get()), (2, vec![1, 3]));
+    assert_eq!(total.get(), 6);
+}
+
+#[test]
+#[should_panic(expected = "cannot be rolled back inside a transaction")]
+fn test_in_place_write_without_set_panics() {
+    let items = signal(vec![1, 2]);
+
+    let _: Result<(), ()> = transaction(|| {
+        items.update(|items| items.push(3));
+        Ok(())
+    });
+}
+
+#[test]
+fn test_rollback_skips_effects_queued_by_discarded_writes() {
+    let a = signal(1i32);
+    let b = signal(1i32);
+    let runs = Rc::new(RefCell::new(Vec::new()));
+
+    let runs_for_closure = runs.clone();
+    let _effect = effect(move || {
+        runs_for_closure.borrow_mut().push(b.get());
+    });
+
+    let _: Result<(), ()> = transaction(|| {
+        a.set(2);
+        // `b` ends the transaction with the value it started with, but is
+        // still restored and must not re-run its reader
+        b.set(2);
+        b.set(1);
+        Err(())
+    });
+
+    assert_eq!(a.get(), 1);
+    assert_eq!(*runs.borrow(), vec![1]);
+
+    b.set(3);
+    assert_eq!(*runs.borrow(), vec![1, 3]);
+}
@@ -55,3 +55,25 @@ fn test_trigger_with_multiple_sources() {
 
     assert_eq!(*triggers.borrow(), 2);
 }
+
+#[test]
+fn test_trigger_releases_every_dependency() {
+    let src1 = signal(1i32);
+    let src2 = signal(1i32);
+    let src3 = signal(1i32);
+    let _effect = effect(move || {
+        src1.get();
+        src2.get();
+        src3.get();
+    });
+
+    let before = count();
+    trigger(move || {
+        src1.get();
+        src2.get();
+        src3.get();
+    });
+
+    assert_eq!(count(), before);
+    assert_eq!(validate(), Ok(()));
+}